@@ -2,18 +2,53 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 use std::str::FromStr;
 use solana_program::{system_instruction, instruction::Instruction};
-use anchor_lang::{solana_program::program::{invoke, invoke_signed}, prelude::Signer};
+use anchor_lang::{solana_program::program::{invoke, invoke_signed}, prelude::Signer, Discriminator};
 
 declare_id!("vBcHBCoQLGDvKejC5MHEZW4pLZi17FS8qPtyA2S6NVt");
 
-// Constants moved to a separate section for better organization
+// Constants moved to a separate section for better organization.
+// The DEFAULT_* values seed `LaunchConfigParams::default()`; the bounds are
+// what `initialize` accepts for a per-mint `LaunchConfig`.
 pub mod constants {
-    pub const MONITORING_BLOCKS: u64 = 5;
-    pub const MIN_TRADE_SOL: u64 = 100_000; // 0.0001 SOL
-    pub const MAX_TRADE_SOL: u64 = 1_000_000_000; // 1 SOL
+    pub const DEFAULT_MONITORING_BLOCKS: u64 = 5;
+    pub const DEFAULT_MIN_TRADE_SOL: u64 = 100_000; // 0.0001 SOL
+    pub const DEFAULT_MAX_TRADE_SOL: u64 = 1_000_000_000; // 1 SOL
+    pub const DEFAULT_MAX_PRICE_IMPACT_BPS: u64 = 1000; // 10%
+    pub const DEFAULT_SLIPPAGE_TOLERANCE_BPS: u64 = 100; // 1%
+    pub const DEFAULT_CONFIG_TIMELOCK_SLOTS: u64 = 216_000; // ~1 day
+    pub const DEFAULT_POOL_FEE_BPS: u64 = 25; // 0.25%, Raydium AMM v4
+    pub const DEFAULT_REFUND_PENALTY_BPS: u64 = 0;
+    pub const DEFAULT_WALLET_CAP_SOL: u64 = 0; // fair-launch caps off
+    pub const DEFAULT_WALLET_CAP_WINDOW_SLOTS: u64 = 0;
+    pub const DEFAULT_MAX_PAUSE_SLOTS: u64 = 216_000; // ~1 day
+    pub const DEFAULT_COMMIT_ALLOCATION_TOKENS: u64 = 0; // commit-reveal off
+    pub const DEFAULT_REVEAL_WINDOW_SLOTS: u64 = 0;
+    pub const DEFAULT_AUCTION_TOKENS: u64 = 0; // batch auction off
     pub const RAYDIUM_PROGRAM_ID: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
-    pub const MAX_PRICE_IMPACT_BPS: u64 = 1000; // 10%
-    pub const SLIPPAGE_TOLERANCE_BPS: u64 = 100; // 1%
+    pub const WHIRLPOOL_PROGRAM_ID: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+
+    pub const MAX_MONITORING_BLOCKS: u64 = 150; // ~1 minute
+    pub const MIN_TRADE_FLOOR_SOL: u64 = 5_000; // never below a signature fee
+    pub const MAX_TRADE_CEILING_SOL: u64 = 1_000_000_000_000; // 1,000 SOL
+    pub const MAX_PRICE_IMPACT_CEILING_BPS: u64 = 5000; // 50%
+    pub const MAX_SLIPPAGE_TOLERANCE_BPS: u64 = 5000; // 50%
+    pub const MIN_CONFIG_TIMELOCK_SLOTS: u64 = 150; // ~1 minute
+    pub const MAX_CONFIG_TIMELOCK_SLOTS: u64 = 1_512_000; // ~1 week
+    pub const MAX_POOL_FEE_BPS: u64 = 1000; // 10%
+    pub const MAX_REFUND_PENALTY_BPS: u64 = 5000; // 50%
+    pub const MAX_PAUSE_SLOTS: u64 = 1_512_000; // ~1 week
+    pub const MAX_REVEAL_WINDOW_SLOTS: u64 = 216_000; // ~1 day
+    pub const AUCTION_PRICE_TICKS: usize = 32;
+    pub const PRICE_UNIT: u64 = 1_000_000_000; // token base units a price is quoted for
+    pub const MAX_CURVE_STEPS: u64 = 256;
+    pub const MAX_CURVE_GROWTH_BPS: u64 = 10_000; // doubling every step
+    pub const MAX_APPROVERS: usize = 10;
+    pub const MAX_PROPOSAL_LIFETIME_SLOTS: u64 = 1_512_000; // ~1 week
+    pub const MIN_LP_LOCK_SLOTS: u64 = 216_000; // ~1 day
+    pub const MIN_LP_LOCK_SECONDS: u64 = 86_400; // 1 day
+    pub const BPS_DENOMINATOR: u64 = 10_000;
+
+    pub const MANAGER_VERSION: u8 = 1;
 }
 
 use constants::*;
@@ -24,9 +59,20 @@ use constants::*;
 pub mod abc_token {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, reserve_amount: u64) -> Result<()> {
-        let manager = &mut ctx.accounts.manager;
+    pub fn initialize<'info>(
+        ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
+        reserve_amount: u64,
+        params: LaunchConfigParams,
+    ) -> Result<()> {
         let clock = Clock::get()?;
+        params.validate()?;
+        params.validate_lp_lock(&clock)?;
+        require!(
+            params.reserve_vesting.amount <= reserve_amount,
+            ErrorCode::InvalidLaunchConfig
+        );
+
+        let manager = &mut ctx.accounts.manager;
 
         manager.initialize(
             ctx.accounts.authority.key(),
@@ -34,7 +80,15 @@ pub mod abc_token {
             clock.slot,
             reserve_amount,
             *ctx.bumps.get("manager").unwrap(),
-            ctx.accounts.token_vault.key(),
+            Pubkey::default(),
+            params.initial_phase(),
+        );
+
+        let launch_config = &mut ctx.accounts.launch_config;
+        launch_config.initialize(
+            manager.mint,
+            &params,
+            *ctx.bumps.get("launch_config").unwrap(),
         );
 
         // Transfer initial reserve tokens
@@ -50,414 +104,7033 @@ pub mod abc_token {
             reserve_amount,
         )?;
 
-        // Create Raydium pool with initial liquidity
-        let raydium_init_ix = create_raydium_pool_ix(
-            &Pubkey::from_str(RAYDIUM_PROGRAM_ID).unwrap(),
-            &manager.mint,
-            reserve_amount / 2, // 50% of reserve as initial liquidity
-            MIN_TRADE_SOL,      // Minimum SOL liquidity
-        )?;
+        // Book what actually arrived rather than what was asked for
+        ctx.accounts.reserve_account.reload()?;
+        manager.reconcile_reserve(ctx.accounts.reserve_account.amount);
 
-        invoke(
-            &raydium_init_ix,
-            &[
-                ctx.accounts.authority.to_account_info(),
-                ctx.accounts.token_vault.to_account_info(),
-                ctx.accounts.treasury.to_account_info(),
-                ctx.accounts.token_program.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
-        )?;
+        manager.raydium_pool = match launch_config.dex {
+            DexKind::RaydiumAmmV4 => {
+                // Create Raydium pool with initial liquidity
+                let pool = raydium::CreatePool::load(ctx.remaining_accounts, launch_config)?;
+                pool.initialize2(
+                    &ctx.accounts.token_program.to_account_info(),
+                    &ctx.accounts.system_program.to_account_info(),
+                    &ctx.accounts.rent.to_account_info(),
+                    &ctx.accounts.mint.to_account_info(),
+                    &ctx.accounts.authority.to_account_info(),
+                    &ctx.accounts.token_source.to_account_info(),
+                    launch_config.min_trade_sol, // Minimum SOL liquidity
+                    reserve_amount / 2,          // 50% of reserve as initial liquidity
+                )?;
+
+                // The pool's LP goes straight under the launch's lock; the
+                // lp_lock and lp_vault PDAs follow the pool accounts
+                trade::lock_initial_liquidity(
+                    manager,
+                    launch_config,
+                    &ctx.accounts.authority.to_account_info(),
+                    pool.lp_mint,
+                    pool.user_token_lp,
+                    &ctx.remaining_accounts[raydium::CreatePool::ACCOUNTS_LEN..],
+                    &ctx.accounts.token_program.to_account_info(),
+                    &ctx.accounts.system_program.to_account_info(),
+                    &ctx.accounts.rent.to_account_info(),
+                )?;
+                pool.amm.key()
+            }
+            DexKind::Whirlpool => {
+                // Whirlpools are opened and funded through Orca's position
+                // instructions; the launch only records which one it trades on
+                let (program, pool) = match ctx.remaining_accounts {
+                    [program, pool, ..] => (program, pool),
+                    _ => return err!(ErrorCode::MissingPoolAccounts),
+                };
+                whirlpool::load_state(program, pool, launch_config, &manager.mint)?;
+                pool.key()
+            }
+            // Created and funded afterwards through `create_native_pool` and
+            // `add_liquidity`
+            DexKind::ConstantProduct => native::pool_address(&manager.mint),
+        };
 
         emit!(ProgramInitialized {
             launch_slot: manager.launch_slot,
             reserve_amount,
-            raydium_pool: ctx.accounts.token_vault.key(),
+            raydium_pool: manager.raydium_pool,
         });
 
         Ok(())
     }
 
     // Buy entry point with cleaner error handling
-    pub fn buy(ctx: Context<Trade>, sol_amount: u64) -> Result<()> {
+    pub fn buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, Trade<'info>>,
+        sol_amount: u64,
+        min_tokens_out: u64,
+    ) -> Result<()> {
         let clock = Clock::get()?;
-        
-        if ctx.accounts.manager.is_in_monitoring_period(clock.slot) {
-            trade::process_monitored_buy(ctx, sol_amount)
-        } else {
-            trade::process_regular_buy(ctx, sol_amount)
-        }
-    }
+        let phase = trade::sync_phase(
+            &mut ctx.accounts.manager,
+            &ctx.accounts.launch_config,
+            clock.slot,
+        )?;
+        ctx.accounts
+            .manager
+            .reconcile_reserve(ctx.accounts.token_vault.amount);
 
-    // Sell entry point with validation
-    pub fn sell(ctx: Context<Trade>, token_amount: u64) -> Result<()> {
-        require!(
-            !ctx.accounts.manager.is_in_monitoring_period(Clock::get()?.slot),
-            ErrorCode::TradingNotActive
-        );
+        match phase {
+            // Commit-reveal and auction launches take their own orders in the window
+            LaunchPhase::Monitoring if ctx.accounts.launch_config.defers_window_buys() => {
+                err!(ErrorCode::TradingNotActive)
+            }
+            LaunchPhase::Monitoring => {
+                let cap_window = ctx
+                    .accounts
+                    .manager
+                    .wallet_cap_window(&ctx.accounts.launch_config, clock.slot);
 
-        trade::process_sell(ctx, token_amount)
+                // Fair-launch mode caps early buyers instead of counter-trading them
+                if let Some(window_start) = cap_window {
+                    trade::record_capped_purchase(
+                        ctx.accounts,
+                        *ctx.bumps.get("wallet_purchases").unwrap(),
+                        sol_amount,
+                        window_start,
+                    )?;
+                    trade::process_regular_buy(ctx, sol_amount, min_tokens_out)
+                } else if !trade::is_cleared(&ctx.accounts.flagged_wallet)? {
+                    trade::process_monitored_buy(ctx, sol_amount)
+                } else {
+                    trade::process_regular_buy(ctx, sol_amount, min_tokens_out)
+                }
+            }
+            LaunchPhase::Open => trade::process_regular_buy(ctx, sol_amount, min_tokens_out),
+            LaunchPhase::Paused => err!(ErrorCode::TradingPaused),
+            _ => err!(ErrorCode::TradingNotActive),
+        }
     }
-}
 
-// Implementation methods for accounts
-impl ABCManager {
-    pub fn initialize(
-        &mut self,
-        authority: Pubkey,
-        mint: Pubkey,
-        launch_slot: u64,
-        reserve_amount: u64,
-        bump: u8,
-        raydium_pool: Pubkey,
-    ) {
-        self.authority = authority;
-        self.mint = mint;
-        self.launch_slot = launch_slot;
-        self.is_launched = true;
-        self.captured_sol = 0;
-        self.reserve_tokens = reserve_amount;
-        self.bump = bump;
-        self.last_blocked_address = Pubkey::default();
-        self.raydium_pool = raydium_pool;
-    }
+    // Sell entry point with validation
+    pub fn sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, Trade<'info>>,
+        token_amount: u64,
+        min_sol_out: u64,
+    ) -> Result<()> {
+        let phase = trade::sync_phase(
+            &mut ctx.accounts.manager,
+            &ctx.accounts.launch_config,
+            Clock::get()?.slot,
+        )?;
+        ctx.accounts
+            .manager
+            .reconcile_reserve(ctx.accounts.token_vault.amount);
+        require!(phase != LaunchPhase::Paused, ErrorCode::TradingPaused);
+        require!(phase == LaunchPhase::Open, ErrorCode::TradingNotActive);
 
-    pub fn is_in_monitoring_period(&self, current_slot: u64) -> bool {
-        current_slot <= self.launch_slot + MONITORING_BLOCKS
+        trade::process_sell(ctx, token_amount, min_sol_out)
     }
 
-    pub fn update_bot_capture(&mut self, bot_address: Pubkey, sol_amount: u64) -> Result<()> {
-        self.last_blocked_address = bot_address;
-        self.captured_sol = self.captured_sol
-            .checked_add(sol_amount)
-            .ok_or(ErrorCode::MathOverflow)?;
-        Ok(())
-    }
-}
+    // Allowlisted buy at the fixed presale price. The leaf commits to the
+    // buyer and their SOL allocation, which is spread over as many buys as
+    // they like
+    pub fn presale_buy(
+        ctx: Context<PresaleBuy>,
+        sol_amount: u64,
+        allocation: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.manager.phase == LaunchPhase::Presale,
+            ErrorCode::PresaleNotActive
+        );
 
-// Separate module for trading logic
-mod trade {
-    use super::*;
+        let config = &ctx.accounts.launch_config;
+        let buyer = ctx.accounts.buyer.key();
+        require!(
+            merkle::verify(&proof, config.presale_merkle_root, merkle::leaf(&buyer, allocation)),
+            ErrorCode::InvalidMerkleProof
+        );
 
-    pub fn process_regular_buy(ctx: Context<Trade>, sol_amount: u64) -> Result<()> {
-        validate_trade_amount(sol_amount)?;
+        let token_amount =
+            pricing::calculate_presale_tokens(sol_amount, config.presale_tokens_per_sol)?;
+        require!(token_amount > 0, ErrorCode::TradeTooSmall);
+        let manager = &mut ctx.accounts.manager;
+        manager.reconcile_reserve(ctx.accounts.token_vault.amount);
+        manager.debit_reserve(token_amount, config)?;
 
-        // Execute trade through Raydium
-        let raydium_swap_ix = create_raydium_swap_ix(
-            &Pubkey::from_str(RAYDIUM_PROGRAM_ID).unwrap(),
-            &ctx.accounts.token_vault.key(),
+        trade::record_presale_purchase(
+            ctx.accounts,
+            *ctx.bumps.get("presale_purchase").unwrap(),
             sol_amount,
-            true, // buying
+            allocation,
         )?;
 
         invoke(
-            &raydium_swap_ix,
+            &system_instruction::transfer(&buyer, &ctx.accounts.treasury.key(), sol_amount),
             &[
-                ctx.accounts.trader.to_account_info(),
-                ctx.accounts.token_vault.to_account_info(),
+                ctx.accounts.buyer.to_account_info(),
                 ctx.accounts.treasury.to_account_info(),
                 ctx.accounts.system_program.to_account_info(),
             ],
         )?;
 
-        let tokens_received = pricing::calculate_tokens_from_sol(sol_amount)?;
+        let manager = &ctx.accounts.manager;
+        let seeds = [b"abc_manager".as_ref(), manager.mint.as_ref(), &[manager.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.token_vault.to_account_info(),
+                    to: ctx.accounts.buyer_token_account.to_account_info(),
+                    authority: manager.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            token_amount,
+        )?;
 
-        emit!(TradeExecuted {
-            trader: ctx.accounts.trader.key(),
-            is_buy: true,
+        emit!(PresalePurchased {
+            mint: manager.mint,
+            buyer,
             sol_amount,
-            token_amount: tokens_received,
+            token_amount,
             slot: Clock::get()?.slot,
         });
 
         Ok(())
     }
 
-    pub fn process_monitored_buy(ctx: Context<Trade>, sol_amount: u64) -> Result<()> {
-        ctx.accounts.manager.update_bot_capture(
-            ctx.accounts.trader.key(),
-            sol_amount,
-        )?;
+    // Sets the slot trading opens at, ending any presale. The monitoring
+    // window and fair-launch caps count from `start_slot`. Can be moved until
+    // the launch is underway
+    pub fn schedule_launch(ctx: Context<ScheduleLaunch>, start_slot: u64) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let manager = &mut ctx.accounts.manager;
+        let from = manager.schedule_launch(start_slot, slot)?;
 
-        let tokens_out = pricing::calculate_tokens_from_sol(sol_amount)?;
+        emit!(LaunchScheduled {
+            mint: manager.mint,
+            from,
+            start_slot,
+            slot,
+        });
 
-        // Transfer SOL from buyer
-        let transfer_ix = system_instruction::transfer(
-            &ctx.accounts.trader.key(),
-            &ctx.accounts.treasury.key(),
-            sol_amount,
-        );
+        Ok(())
+    }
 
-        invoke(
-            &transfer_ix,
-            &[
-                ctx.accounts.trader.to_account_info(),
-                ctx.accounts.treasury.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
-        )?;
+    // Marks a flagged wallet as a false positive. The record stays for audit;
+    // the wallet trades normally from then on, even inside the window
+    pub fn clear_flagged_wallet(ctx: Context<ClearFlaggedWallet>) -> Result<()> {
+        let flagged = &mut ctx.accounts.flagged_wallet;
+        require!(!flagged.cleared, ErrorCode::WalletAlreadyCleared);
 
-        // Get manager signer seeds
-        let seeds = [
-            b"abc_manager".as_ref(),
-            ctx.accounts.manager.mint.as_ref(),
-            &[ctx.accounts.manager.bump]
-        ];
-        let signer_seeds = &[&seeds[..]];
+        let slot = Clock::get()?.slot;
+        flagged.cleared = true;
+        flagged.cleared_slot = slot;
 
-        // Counter-trade through Raydium
-        let raydium_swap_ix = create_raydium_swap_ix(
-            &Pubkey::from_str(RAYDIUM_PROGRAM_ID).unwrap(),
-            &ctx.accounts.token_vault.key(),
-            tokens_out,
-            false, // selling same amount
-        )?;
+        emit!(FlaggedWalletCleared {
+            mint: ctx.accounts.manager.mint,
+            wallet: flagged.wallet,
+            flag_count: flagged.flag_count,
+            total_sol: flagged.total_sol,
+            slot,
+        });
 
+        Ok(())
+    }
+
+    // Returns SOL taken by a monitored buy, less the launch's published
+    // penalty, once the window has closed
+    pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(
+            !ctx.accounts.manager.is_in_monitoring_period(&ctx.accounts.launch_config, clock.slot),
+            ErrorCode::MonitoringPeriodActive
+        );
+
+        let (refund, penalty) = ctx
+            .accounts
+            .monitored_purchase
+            .refund_amounts(ctx.accounts.launch_config.refund_penalty_bps)?;
+
+        let mint = ctx.accounts.manager.mint;
+        let treasury_bump = *ctx.bumps.get("treasury").unwrap();
         invoke_signed(
-            &raydium_swap_ix,
+            &system_instruction::transfer(
+                &ctx.accounts.treasury.key(),
+                &ctx.accounts.buyer.key(),
+                refund,
+            ),
             &[
-                ctx.accounts.token_vault.to_account_info(),
                 ctx.accounts.treasury.to_account_info(),
+                ctx.accounts.buyer.to_account_info(),
                 ctx.accounts.system_program.to_account_info(),
             ],
-            signer_seeds,
+            &[&[b"treasury", mint.as_ref(), &[treasury_bump]]],
         )?;
 
-        emit!(BotPurchaseHandled {
-            bot_address: ctx.accounts.trader.key(),
-            tokens_purchased: tokens_out,
-            sol_captured: sol_amount,
-            tokens_sold: tokens_out,
-            slot: Clock::get()?.slot,
+        let manager = &mut ctx.accounts.manager;
+        manager.refunded_sol = manager
+            .refunded_sol
+            .checked_add(refund)
+            .ok_or(ErrorCode::MathOverflow)?;
+        manager.refund_penalty_sol = manager
+            .refund_penalty_sol
+            .checked_add(penalty)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(RefundClaimed {
+            mint,
+            buyer: ctx.accounts.buyer.key(),
+            sol_refunded: refund,
+            penalty,
+            slot: clock.slot,
         });
 
         Ok(())
     }
 
-    pub fn process_sell(ctx: Context<Trade>, token_amount: u64) -> Result<()> {
-        let sol_out = pricing::calculate_sol_from_tokens(token_amount)?;
-        validate_trade_amount(sol_out)?;
+    // Commit-reveal mode: during the monitoring window buyers escrow SOL
+    // behind a hash of what they'll actually spend, so landing first gains
+    // nothing
+    pub fn commit_buy(
+        ctx: Context<CommitBuy>,
+        commitment: [u8; 32],
+        escrow_sol: u64,
+    ) -> Result<()> {
+        let config = &ctx.accounts.launch_config;
+        require!(config.commit_reveal_enabled(), ErrorCode::CommitRevealDisabled);
 
-        // Execute sell through Raydium
-        let raydium_swap_ix = create_raydium_swap_ix(
-            &Pubkey::from_str(RAYDIUM_PROGRAM_ID).unwrap(),
-            &ctx.accounts.token_vault.key(),
-            token_amount,
-            false, // selling
-        )?;
+        let slot = Clock::get()?.slot;
+        let phase = trade::sync_phase(&mut ctx.accounts.manager, config, slot)?;
+        require!(phase == LaunchPhase::Monitoring, ErrorCode::CommitWindowClosed);
+        require!(escrow_sol >= config.min_trade_sol, ErrorCode::TradeTooSmall);
 
         invoke(
-            &raydium_swap_ix,
+            &system_instruction::transfer(
+                &ctx.accounts.buyer.key(),
+                &ctx.accounts.treasury.key(),
+                escrow_sol,
+            ),
             &[
-                ctx.accounts.trader.to_account_info(),
-                ctx.accounts.token_vault.to_account_info(),
+                ctx.accounts.buyer.to_account_info(),
                 ctx.accounts.treasury.to_account_info(),
                 ctx.accounts.system_program.to_account_info(),
             ],
         )?;
 
-        emit!(TradeExecuted {
-            trader: ctx.accounts.trader.key(),
-            is_buy: false,
-            sol_amount: sol_out,
-            token_amount,
-            slot: Clock::get()?.slot,
+        let manager = &mut ctx.accounts.manager;
+        manager.escrowed_sol = manager
+            .escrowed_sol
+            .checked_add(escrow_sol)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let record = &mut ctx.accounts.commitment;
+        record.manager = manager.key();
+        record.buyer = ctx.accounts.buyer.key();
+        record.commitment = commitment;
+        record.escrow_sol = escrow_sol;
+        record.bump = *ctx.bumps.get("commitment").unwrap();
+
+        emit!(PurchaseCommitted {
+            mint: manager.mint,
+            buyer: record.buyer,
+            escrow_sol,
+            slot,
         });
 
         Ok(())
     }
 
-    fn validate_trade_amount(amount: u64) -> Result<()> {
-        require!(amount >= MIN_TRADE_SOL, ErrorCode::TradeTooSmall);
-        require!(amount <= MAX_TRADE_SOL, ErrorCode::TradeTooLarge);
-        Ok(())
-    }
-}
+    // Opens a commitment once the window has closed. Revealed SOL counts
+    // towards the pro-rata split of `commit_allocation_tokens`
+    pub fn reveal_buy(ctx: Context<RevealBuy>, sol_amount: u64, salt: [u8; 32]) -> Result<()> {
+        let config = &ctx.accounts.launch_config;
+        let slot = Clock::get()?.slot;
+        let manager = &mut ctx.accounts.manager;
+        require!(manager.is_reveal_window(config, slot), ErrorCode::RevealWindowClosed);
 
-// Separate module for pricing calculations
-mod pricing {
-    use super::*;
+        let record = &mut ctx.accounts.commitment;
+        record.reveal(sol_amount, &salt, config.min_trade_sol)?;
 
-    pub fn calculate_tokens_from_sol(sol_amount: u64) -> Result<u64> {
-        let sol_reserve: u128 = 1_000_000_000;
-        let token_reserve: u128 = 1_000_000_000_000;
-        
-        calculate_swap_output(
+        manager.revealed_sol = manager
+            .revealed_sol
+            .checked_add(sol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(PurchaseRevealed {
+            mint: manager.mint,
+            buyer: record.buyer,
             sol_amount,
-            sol_reserve,
-            token_reserve
-        )
-    }
+            slot,
+        });
 
-    pub fn calculate_sol_from_tokens(token_amount: u64) -> Result<u64> {
-        let sol_reserve: u128 = 1_000_000_000;
-        let token_reserve: u128 = 1_000_000_000_000;
-        
-        calculate_swap_output(
-            token_amount,
-            token_reserve,
-            sol_reserve
-        )
+        Ok(())
     }
 
-    fn calculate_swap_output(
-        amount_in: u64,
-        reserve_in: u128,
-        reserve_out: u128
-    ) -> Result<u64> {
-        let k = reserve_in
-            .checked_mul(reserve_out)
-            .ok_or(ErrorCode::MathOverflow)?;
+    // After the reveal window: delivers the buyer's share of the allocation
+    // and returns the rest of the escrow. Unrevealed commitments are refunded
+    // in full and fill nothing
+    pub fn settle_commitment(ctx: Context<SettleCommitment>) -> Result<()> {
+        let config = &ctx.accounts.launch_config;
+        let slot = Clock::get()?.slot;
+        let manager = &ctx.accounts.manager;
+        require!(
+            slot > manager.reveal_window_end(config),
+            ErrorCode::RevealWindowActive
+        );
 
-        let new_reserve_in = reserve_in
-            .checked_add(amount_in as u128)
-            .ok_or(ErrorCode::MathOverflow)?;
+        let record = &ctx.accounts.commitment;
+        let (token_amount, refund) =
+            record.fill(config.commit_allocation_tokens, manager.revealed_sol)?;
 
-        let new_reserve_out = k
-            .checked_div(new_reserve_in)
-            .ok_or(ErrorCode::MathOverflow)?;
+        let manager = &mut ctx.accounts.manager;
+        manager.reconcile_reserve(ctx.accounts.token_vault.amount);
+        manager.debit_reserve(token_amount, config)?;
 
-        let amount_out = reserve_out
-            .checked_sub(new_reserve_out)
-            .ok_or(ErrorCode::MathOverflow)?;
+        let mint = manager.mint;
+        if token_amount > 0 {
+            let seeds = [b"abc_manager".as_ref(), mint.as_ref(), &[manager.bump]];
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.token_vault.to_account_info(),
+                        to: ctx.accounts.buyer_token_account.to_account_info(),
+                        authority: manager.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                token_amount,
+            )?;
+        }
 
-        Ok(amount_out as u64)
-    }
-}
+        if refund > 0 {
+            let treasury_bump = *ctx.bumps.get("treasury").unwrap();
+            invoke_signed(
+                &system_instruction::transfer(
+                    &ctx.accounts.treasury.key(),
+                    &ctx.accounts.buyer.key(),
+                    refund,
+                ),
+                &[
+                    ctx.accounts.treasury.to_account_info(),
+                    ctx.accounts.buyer.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                ],
+                &[&[b"treasury", mint.as_ref(), &[treasury_bump]]],
+            )?;
+        }
 
-// Helper functions for Raydium integration
-fn create_raydium_pool_ix(
-    program_id: &Pubkey,
-    mint: &Pubkey,
-    token_amount: u64,
-    sol_amount: u64,
-) -> Result<Instruction> {
-    let mut data = Vec::with_capacity(40);
-    data.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 0]); // Initialize pool discriminator
-    data.extend_from_slice(&token_amount.to_le_bytes());
-    data.extend_from_slice(&sol_amount.to_le_bytes());
-    data.extend_from_slice(mint.as_ref());
+        let escrow_sol = record.escrow_sol;
+        let sol_paid = escrow_sol - refund;
+        let manager = &mut ctx.accounts.manager;
+        manager.escrowed_sol = manager.escrowed_sol.saturating_sub(escrow_sol);
 
-    Ok(Instruction {
-        program_id: *program_id,
-        accounts: vec![], // Accounts provided in invoke context
-        data,
-    })
-}
+        emit!(CommitmentSettled {
+            mint,
+            buyer: ctx.accounts.buyer.key(),
+            sol_paid,
+            token_amount,
+            sol_refunded: refund,
+            slot,
+        });
 
-fn create_raydium_swap_ix(
-    program_id: &Pubkey,
-    pool: &Pubkey,
-    amount: u64,
-    is_buy: bool,
-) -> Result<Instruction> {
-    let mut data = Vec::with_capacity(41);
-    data.extend_from_slice(&[2, 0, 0, 0, 0, 0, 0, 0]); // Swap discriminator
-    data.extend_from_slice(&amount.to_le_bytes());
-    data.push(is_buy as u8);
-    data.extend_from_slice(pool.as_ref());
+        Ok(())
+    }
 
-    Ok(Instruction {
-        program_id: *program_id,
-        accounts: vec![], // Accounts provided in invoke context
-        data,
-    })
-}
+    // Sets up the order book for a batch-auction launch
+    pub fn create_auction(ctx: Context<CreateAuction>) -> Result<()> {
+        require!(
+            ctx.accounts.launch_config.auction_enabled(),
+            ErrorCode::AuctionDisabled
+        );
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+        let auction = &mut ctx.accounts.auction;
+        auction.manager = ctx.accounts.manager.key();
+        auction.bump = *ctx.bumps.get("auction").unwrap();
 
-    pub mint: Account<'info, Mint>,
+        Ok(())
+    }
 
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + 32 + 32 + 8 + 1 + 8 + 8 + 1 + 32,
-        seeds = [b"abc_manager", mint.key().as_ref()],
-        bump
-    )]
-    pub manager: Account<'info, ABCManager>,
+    // Batch auction: bids collected over the monitoring window all clear at
+    // one price, so landing first gains nothing. `tick` picks the highest
+    // price the bidder accepts from the launch's price ladder
+    pub fn place_bid(ctx: Context<PlaceBid>, sol_amount: u64, tick: u8) -> Result<()> {
+        let config = &ctx.accounts.launch_config;
+        let slot = Clock::get()?.slot;
+        let phase = trade::sync_phase(&mut ctx.accounts.manager, config, slot)?;
+        require!(phase == LaunchPhase::Monitoring, ErrorCode::AuctionClosed);
+        require!(!ctx.accounts.auction.cleared, ErrorCode::AuctionClosed);
+        require!(
+            (tick as usize) < AUCTION_PRICE_TICKS,
+            ErrorCode::InvalidBidPrice
+        );
+        require!(sol_amount >= config.min_trade_sol, ErrorCode::TradeTooSmall);
 
-    #[account(
-        mut,
-        constraint = token_source.mint == mint.key(),
-        constraint = token_source.owner == authority.key()
-    )]
-    pub token_source: Account<'info, TokenAccount>,
+        invoke(
+            &system_instruction::transfer(
+                &ctx.accounts.bidder.key(),
+                &ctx.accounts.treasury.key(),
+                sol_amount,
+            ),
+            &[
+                ctx.accounts.bidder.to_account_info(),
+                ctx.accounts.treasury.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
 
-    #[account(
-        init,
-        payer = authority,
-        seeds = [b"reserve", mint.key().as_ref()],
-        bump,
-        token::mint = mint,
-        token::authority = manager,
-    )]
-    pub reserve_account: Account<'info, TokenAccount>,
+        ctx.accounts.auction.add_bid(sol_amount, tick)?;
 
-    #[account(mut)]
-    /// CHECK: Raydium pool account
-    pub token_vault: AccountInfo<'info>,
+        let manager = &mut ctx.accounts.manager;
+        manager.escrowed_sol = manager
+            .escrowed_sol
+            .checked_add(sol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
 
-    #[account(mut)]
-    /// CHECK: Raydium SOL vault
-    pub treasury: AccountInfo<'info>,
+        let bid = &mut ctx.accounts.bid;
+        bid.manager = manager.key();
+        bid.bidder = ctx.accounts.bidder.key();
+        bid.sol_amount = sol_amount;
+        bid.tick = tick;
+        bid.bump = *ctx.bumps.get("bid").unwrap();
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-    pub clock: Sysvar<'info, Clock>,
-}
+        emit!(BidPlaced {
+            mint: manager.mint,
+            bidder: bid.bidder,
+            sol_amount,
+            price: auction::price(config, tick),
+            slot,
+        });
 
-#[derive(Accounts)]
-pub struct Trade<'info> {
-    #[account(mut)]
-    pub manager: Account<'info, ABCManager>,
+        Ok(())
+    }
 
-    #[account(mut)]
-    pub trader: Signer<'info>,
+    // Permissionless crank once the window closes. The first call fixes the
+    // clearing price; each call then settles the bids passed as
+    // [bid, bidder, bidder_token_account] triples in remaining accounts
+    pub fn settle_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleAuction<'info>>,
+    ) -> Result<()> {
+        // Only a window that actually ran can clear: an unscheduled launch
+        // still carries its `initialize` slot as `launch_slot`
+        let config = &ctx.accounts.launch_config;
+        let slot = Clock::get()?.slot;
+        let phase = trade::sync_phase(&mut ctx.accounts.manager, config, slot)?;
+        let window_over = match phase {
+            LaunchPhase::Open => true,
+            LaunchPhase::Closed => !ctx.accounts.manager.is_in_monitoring_period(config, slot),
+            _ => false,
+        };
+        require!(window_over, ErrorCode::AuctionActive);
 
-    #[account(
-        mut,
-        constraint = trader_token_account.mint == manager.mint,
-        constraint = trader_token_account.owner == trader.key()
-    )]
-    pub trader_token_account: Account<'info, TokenAccount>,
+        let auction = &mut ctx.accounts.auction;
+        if !auction.cleared {
+            auction.clear(config)?;
+
+            emit!(AuctionCleared {
+                mint: ctx.accounts.manager.mint,
+                clearing_price: auction::price(config, auction.clearing_tick),
+                total_bid_sol: auction.total_bid_sol,
+                slot,
+            });
+        }
+
+        trade::settle_bids(ctx, slot)
+    }
+
+    // Buys from the reserve along the bonding curve. A buy that would pass
+    // the graduation target is cut down to it and charged only for that
+    pub fn curve_buy(ctx: Context<CurveBuy>, sol_amount: u64, min_tokens_out: u64) -> Result<()> {
+        require!(
+            ctx.accounts.manager.phase == LaunchPhase::BondingCurve,
+            ErrorCode::CurveNotActive
+        );
+
+        let config = &ctx.accounts.launch_config;
+        let manager = &ctx.accounts.manager;
+        let sol_left = config
+            .graduation_sol
+            .saturating_sub(manager.curve_sol_raised);
+        require!(sol_left > 0, ErrorCode::CurveComplete);
+        require!(sol_amount >= config.min_trade_sol, ErrorCode::TradeTooSmall);
+
+        let (token_amount, sol_cost) = pricing::calculate_curve_buy(
+            &config.curve,
+            manager.curve_tokens_sold,
+            sol_amount.min(sol_left),
+        )?;
+        require!(token_amount > 0, ErrorCode::TradeTooSmall);
+        require!(token_amount >= min_tokens_out, ErrorCode::SlippageExceeded);
+
+        let manager = &mut ctx.accounts.manager;
+        manager.reconcile_reserve(ctx.accounts.token_vault.amount);
+        manager.debit_reserve(token_amount, config)?;
+
+        invoke(
+            &system_instruction::transfer(
+                &ctx.accounts.buyer.key(),
+                &ctx.accounts.treasury.key(),
+                sol_cost,
+            ),
+            &[
+                ctx.accounts.buyer.to_account_info(),
+                ctx.accounts.treasury.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+
+        let seeds = [b"abc_manager".as_ref(), manager.mint.as_ref(), &[manager.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.token_vault.to_account_info(),
+                    to: ctx.accounts.buyer_token_account.to_account_info(),
+                    authority: manager.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            token_amount,
+        )?;
+
+        let manager = &mut ctx.accounts.manager;
+        manager.curve_tokens_sold = manager
+            .curve_tokens_sold
+            .checked_add(token_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        manager.curve_sol_raised = manager
+            .curve_sol_raised
+            .checked_add(sol_cost)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(CurvePurchased {
+            mint: manager.mint,
+            buyer: ctx.accounts.buyer.key(),
+            sol_amount: sol_cost,
+            token_amount,
+            sol_raised: manager.curve_sol_raised,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless once the curve hits its target: opens the built-in pool
+    // with the raised SOL and everything left in the reserve, and opens
+    // trading. The LP tokens stay with the manager so the liquidity can't be
+    // pulled
+    pub fn graduate(ctx: Context<Graduate>) -> Result<()> {
+        let config = &ctx.accounts.launch_config;
+        let manager = &ctx.accounts.manager;
+        require!(
+            manager.phase == LaunchPhase::BondingCurve,
+            ErrorCode::CurveNotActive
+        );
+        require!(
+            manager.curve_sol_raised >= config.graduation_sol,
+            ErrorCode::GraduationTargetNotReached
+        );
+
+        // The pool gets whatever reserve the vesting schedule doesn't still
+        // hold back
+        let sol_amount = manager.curve_sol_raised;
+        let manager = &mut ctx.accounts.manager;
+        manager.reconcile_reserve(ctx.accounts.token_vault.amount);
+        let token_amount = manager.spendable_reserve(config);
+        manager.debit_reserve(token_amount, config)?;
+        let (_, lp_amount) = pricing::calculate_liquidity_deposit(
+            &pricing::PoolReserves { sol: 0, token: 0 },
+            0,
+            sol_amount,
+            token_amount,
+        )?;
+        require!(lp_amount > 0, ErrorCode::InsufficientLiquidity);
+
+        let seeds = [b"abc_manager".as_ref(), manager.mint.as_ref(), &[manager.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.token_vault.to_account_info(),
+                    to: ctx.accounts.pool_vault.to_account_info(),
+                    authority: manager.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            token_amount,
+        )?;
+
+        let pool = &mut ctx.accounts.native_pool;
+        pool.initialize(
+            ctx.accounts.mint.key(),
+            ctx.accounts.lp_mint.key(),
+            ctx.accounts.pool_vault.key(),
+            *ctx.bumps.get("native_pool").unwrap(),
+            *ctx.bumps.get("treasury").unwrap(),
+        );
+        pool.sol_reserve = sol_amount;
+        pool.token_reserve = token_amount;
+
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: ctx.accounts.lp_vault.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                &[&[b"native_pool", pool.mint.as_ref(), &[pool.bump]]],
+            ),
+            lp_amount,
+        )?;
+
+        // The graduation LP stays in `lp_vault`, locked for the configured
+        // duration from now: when the target is met isn't known up front
+        let lock_until = config
+            .lp_lock_clock
+            .now(&Clock::get()?)
+            .saturating_add(config.lp_lock_duration);
+        let lp_lock = &mut ctx.accounts.lp_lock;
+        lp_lock.initialize(
+            ctx.accounts.manager.key(),
+            ctx.accounts.lp_mint.key(),
+            lp_amount,
+            config.lp_lock_clock,
+            lock_until,
+            *ctx.bumps.get("lp_lock").unwrap(),
+        );
+
+        let manager = &mut ctx.accounts.manager;
+        manager.set_phase(LaunchPhase::Open)?;
+
+        emit!(CurveGraduated {
+            mint: manager.mint,
+            pool: pool.key(),
+            sol_amount,
+            token_amount,
+            lp_amount,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    // Moves LP tokens from the built-in pool into `lp_vault`, where they stay
+    // until the configured `lp_lock_until`
+    pub fn lock_liquidity(ctx: Context<LockLiquidity>, amount: u64) -> Result<()> {
+        let config = &ctx.accounts.launch_config;
+        require!(amount > 0, ErrorCode::InsufficientLiquidity);
+
+        let lp_mint = &ctx.accounts.lp_mint;
+        let mint = ctx.accounts.mint.key();
+        require!(
+            config.lockable_lp_mint() == Some(lp_mint.key()),
+            ErrorCode::InvalidLpMint
+        );
+
+        let clock = Clock::get()?;
+        require!(
+            config.lp_lock_clock.now(&clock) < config.lp_lock_until,
+            ErrorCode::InvalidLiquidityLock
+        );
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.lp_source.to_account_info(),
+                    to: ctx.accounts.lp_vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let lp_lock = &mut ctx.accounts.lp_lock;
+        lp_lock.initialize(
+            ctx.accounts.manager.key(),
+            lp_mint.key(),
+            amount,
+            config.lp_lock_clock,
+            config.lp_lock_until,
+            *ctx.bumps.get("lp_lock").unwrap(),
+        );
+
+        emit!(LiquidityLocked {
+            mint,
+            lp_mint: lp_lock.lp_mint,
+            amount,
+            lock_until: lp_lock.lock_until,
+            slot: clock.slot,
+        });
+
+        Ok(())
+    }
+
+    // Locks can only be pushed out, never shortened
+    pub fn extend_lock(ctx: Context<ExtendLock>, lock_until: u64) -> Result<()> {
+        let lp_lock = &mut ctx.accounts.lp_lock;
+        require!(
+            lock_until > lp_lock.lock_until,
+            ErrorCode::InvalidLiquidityLock
+        );
+        lp_lock.lock_until = lock_until;
+
+        emit!(LiquidityLockExtended {
+            mint: ctx.accounts.manager.mint,
+            lock_until,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    pub fn unlock_liquidity(ctx: Context<UnlockLiquidity>) -> Result<()> {
+        require!(
+            ctx.accounts.manager.approver_set == Pubkey::default(),
+            ErrorCode::ApprovalRequired
+        );
+
+        let amount = trade::unlock_liquidity(
+            &ctx.accounts.manager,
+            &mut ctx.accounts.lp_lock,
+            ctx.accounts.lp_vault.as_ref(),
+            ctx.accounts.destination.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+        )?;
+
+        emit!(LiquidityUnlocked {
+            mint: ctx.accounts.manager.mint,
+            destination: ctx.accounts.destination.key(),
+            amount,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    // Opens the built-in pool for a launch configured with DexKind::ConstantProduct
+    pub fn create_native_pool(ctx: Context<CreateNativePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.native_pool;
+        pool.initialize(
+            ctx.accounts.mint.key(),
+            ctx.accounts.lp_mint.key(),
+            ctx.accounts.pool_vault.key(),
+            *ctx.bumps.get("native_pool").unwrap(),
+            *ctx.bumps.get("treasury").unwrap(),
+        );
+
+        emit!(NativePoolCreated {
+            mint: pool.mint,
+            pool: pool.key(),
+            lp_mint: pool.lp_mint,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    // Deposits SOL plus the matching share of tokens for LP tokens; the first
+    // deposit sets the price
+    pub fn add_liquidity(
+        ctx: Context<NativeLiquidity>,
+        sol_amount: u64,
+        max_token_amount: u64,
+        min_lp_out: u64,
+    ) -> Result<()> {
+        native::process_add_liquidity(ctx, sol_amount, max_token_amount, min_lp_out)
+    }
+
+    pub fn remove_liquidity(
+        ctx: Context<NativeLiquidity>,
+        lp_amount: u64,
+        min_sol_out: u64,
+        min_token_out: u64,
+    ) -> Result<()> {
+        native::process_remove_liquidity(ctx, lp_amount, min_sol_out, min_token_out)
+    }
+
+    // Direct trade against the built-in pool, under the same limits as buy/sell
+    pub fn swap(
+        ctx: Context<NativeSwap>,
+        direction: SwapDirection,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        let phase = ctx
+            .accounts
+            .manager
+            .current_phase(&ctx.accounts.launch_config, Clock::get()?.slot);
+        require!(phase != LaunchPhase::Paused, ErrorCode::TradingPaused);
+        require!(phase == LaunchPhase::Open, ErrorCode::TradingNotActive);
+
+        trade::process_native_swap(ctx, direction, amount_in, minimum_amount_out)
+    }
+
+    // Queue new trading limits / pool address behind the config timelock
+    pub fn propose_config_update(
+        ctx: Context<ProposeConfigUpdate>,
+        update: ConfigUpdate,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.manager.approver_set == Pubkey::default(),
+            ErrorCode::ApprovalRequired
+        );
+        update.validate()?;
+
+        let clock = Clock::get()?;
+        let eta_slot = clock
+            .slot
+            .checked_add(ctx.accounts.launch_config.config_timelock_slots)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let pending_update = &mut ctx.accounts.pending_update;
+        pending_update.mint = ctx.accounts.manager.mint;
+        pending_update.update = update.clone();
+        pending_update.proposed_slot = clock.slot;
+        pending_update.eta_slot = eta_slot;
+        pending_update.bump = *ctx.bumps.get("pending_update").unwrap();
+
+        emit!(ConfigUpdateProposed {
+            mint: pending_update.mint,
+            update,
+            proposed_slot: clock.slot,
+            eta_slot,
+        });
+
+        Ok(())
+    }
+
+    pub fn apply_config_update(ctx: Context<ApplyConfigUpdate>) -> Result<()> {
+        require!(
+            ctx.accounts.manager.approver_set == Pubkey::default(),
+            ErrorCode::ApprovalRequired
+        );
+        let clock = Clock::get()?;
+        let pending_update = &ctx.accounts.pending_update;
+
+        if clock.slot < pending_update.eta_slot {
+            msg!(
+                "Config update locked until slot {} (current {})",
+                pending_update.eta_slot,
+                clock.slot
+            );
+            return err!(ErrorCode::TimelockNotExpired);
+        }
+
+        ctx.accounts
+            .launch_config
+            .validate_update(&pending_update.update, &ctx.accounts.manager)?;
+        ctx.accounts.launch_config.apply_update(&pending_update.update);
+        ctx.accounts.manager.raydium_pool = pending_update.update.raydium_pool;
+
+        emit!(ConfigUpdateApplied {
+            mint: pending_update.mint,
+            update: pending_update.update.clone(),
+            slot: clock.slot,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_config_update(ctx: Context<CancelConfigUpdate>) -> Result<()> {
+        emit!(ConfigUpdateCancelled {
+            mint: ctx.accounts.pending_update.mint,
+            eta_slot: ctx.accounts.pending_update.eta_slot,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    // Two-step handover: the new key has to accept before it takes effect
+    pub fn propose_authority(ctx: Context<UpdateAuthority>, new_authority: Pubkey) -> Result<()> {
+        require!(new_authority != Pubkey::default(), ErrorCode::InvalidAuthority);
+
+        let manager = &mut ctx.accounts.manager;
+        manager.pending_authority = new_authority;
+
+        emit!(AuthorityTransferProposed {
+            mint: manager.mint,
+            authority: manager.authority,
+            pending_authority: new_authority,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let manager = &mut ctx.accounts.manager;
+        let previous_authority = manager.transfer_authority(ctx.accounts.new_authority.key());
+
+        emit!(AuthorityTransferred {
+            mint: manager.mint,
+            previous_authority,
+            new_authority: manager.authority,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    // Permanently gives up admin control; authority becomes the default key.
+    // A queued config update has to be applied or cancelled first, since
+    // nobody could do either afterwards
+    pub fn renounce_authority(ctx: Context<RenounceAuthority>) -> Result<()> {
+        require!(
+            ctx.accounts.pending_update.data_is_empty(),
+            ErrorCode::ConfigUpdatePending
+        );
+
+        let manager = &mut ctx.accounts.manager;
+        let previous_authority = manager.transfer_authority(Pubkey::default());
+
+        emit!(AuthorityTransferred {
+            mint: manager.mint,
+            previous_authority,
+            new_authority: Pubkey::default(),
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    // Names a second key that may pause and resume trading. The default key
+    // removes it
+    pub fn set_guardian(ctx: Context<UpdateAuthority>, guardian: Pubkey) -> Result<()> {
+        let manager = &mut ctx.accounts.manager;
+        let previous_guardian = std::mem::replace(&mut manager.guardian, guardian);
+
+        emit!(GuardianUpdated {
+            mint: manager.mint,
+            previous_guardian,
+            guardian,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    // Circuit breaker for buy, sell and swap. Lifts itself after the
+    // launch's `max_pause_slots` if nobody resumes first
+    pub fn pause_trading(ctx: Context<PauseTrading>, reason: u8) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let manager = &mut ctx.accounts.manager;
+        manager.pause(&ctx.accounts.launch_config, slot)?;
+
+        emit!(TradingPaused {
+            mint: manager.mint,
+            operator: ctx.accounts.operator.key(),
+            reason,
+            slot,
+            expires_slot: manager.pause_expires_slot,
+        });
+
+        Ok(())
+    }
+
+    // Ends the launch for good: buy, sell and swap stay shut, while refunds,
+    // settlement and liquidity withdrawals keep working
+    pub fn close_launch(ctx: Context<CloseLaunch>) -> Result<()> {
+        require!(
+            ctx.accounts.manager.approver_set == Pubkey::default(),
+            ErrorCode::ApprovalRequired
+        );
+
+        trade::close_launch(
+            &mut ctx.accounts.manager,
+            &ctx.accounts.launch_config,
+            Clock::get()?.slot,
+        )
+    }
+
+    pub fn resume_trading(ctx: Context<PauseTrading>, reason: u8) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let manager = &mut ctx.accounts.manager;
+        let phase = manager.resume(&ctx.accounts.launch_config, slot)?;
+
+        emit!(TradingResumed {
+            mint: manager.mint,
+            operator: ctx.accounts.operator.key(),
+            reason,
+            phase,
+            slot,
+        });
+
+        Ok(())
+    }
+
+    // Moves free treasury SOL to `destination`. SOL owed to buyers, the
+    // built-in pool's reserve and the PDA's rent-exempt minimum stay put. On
+    // the built-in pool, the pool account follows as a remaining account
+    pub fn withdraw_treasury<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawTreasury<'info>>,
+        amount: u64,
+        destination: Pubkey,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.manager.approver_set == Pubkey::default(),
+            ErrorCode::ApprovalRequired
+        );
+        require!(
+            ctx.accounts.destination.key() == destination,
+            ErrorCode::InvalidWithdrawalDestination
+        );
+
+        trade::withdraw_treasury(
+            &mut ctx.accounts.manager,
+            &ctx.accounts.launch_config,
+            &ctx.accounts.treasury,
+            *ctx.bumps.get("treasury").unwrap(),
+            ctx.accounts.destination.as_ref(),
+            ctx.accounts.system_program.as_ref(),
+            ctx.remaining_accounts,
+            amount,
+        )?;
+
+        let manager = &ctx.accounts.manager;
+        emit!(TreasuryWithdrawal {
+            mint: manager.mint,
+            authority: ctx.accounts.authority.key(),
+            destination,
+            amount,
+            total_withdrawn: manager.total_withdrawn,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    // Pays out reserve tokens that have vested under the launch's schedule
+    pub fn release_reserve(ctx: Context<ReleaseReserve>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.manager.approver_set == Pubkey::default(),
+            ErrorCode::ApprovalRequired
+        );
+
+        trade::release_reserve(
+            &mut ctx.accounts.manager,
+            &ctx.accounts.launch_config,
+            &ctx.accounts.token_vault,
+            ctx.accounts.destination.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            amount,
+        )?;
+
+        let manager = &ctx.accounts.manager;
+        emit!(ReserveReleased {
+            mint: manager.mint,
+            destination: ctx.accounts.destination.key(),
+            amount,
+            total_released: manager.reserve_released,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    // Escrows reserve tokens for one beneficiary, vesting from `start` to
+    // `end` with nothing claimable before `cliff`
+    pub fn create_vesting(
+        ctx: Context<CreateVesting>,
+        amount: u64,
+        start: u64,
+        cliff: u64,
+        end: u64,
+        clock: VestingClock,
+        revocable: bool,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.manager.approver_set == Pubkey::default(),
+            ErrorCode::ApprovalRequired
+        );
+        require!(
+            amount > 0 && start <= cliff && cliff <= end,
+            ErrorCode::InvalidVestingSchedule
+        );
+        let schedule = VestingSchedule {
+            amount,
+            clock,
+            start,
+            cliff: cliff - start,
+            duration: end - start,
+        };
+        schedule.validate()?;
+
+        // Grants can't dip into reserve still vesting under the launch's own
+        // schedule
+        let manager = &mut ctx.accounts.manager;
+        manager.reconcile_reserve(ctx.accounts.token_vault.amount);
+        manager.debit_reserve(amount, &ctx.accounts.launch_config)?;
+
+        let mint = manager.mint;
+        let seeds = [b"abc_manager".as_ref(), mint.as_ref(), &[manager.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.token_vault.to_account_info(),
+                    to: ctx.accounts.grant_vault.to_account_info(),
+                    authority: manager.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+
+        let manager = &mut ctx.accounts.manager;
+        manager.reserve_granted = manager
+            .reserve_granted
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let grant = &mut ctx.accounts.grant;
+        grant.manager = manager.key();
+        grant.beneficiary = ctx.accounts.beneficiary.key();
+        grant.schedule = schedule;
+        grant.claimed = 0;
+        grant.revocable = revocable;
+        grant.revoked_at = None;
+        grant.bump = *ctx.bumps.get("grant").unwrap();
+
+        emit!(VestingCreated {
+            mint,
+            beneficiary: grant.beneficiary,
+            amount,
+            start,
+            cliff,
+            end,
+            revocable,
+        });
+
+        Ok(())
+    }
+
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        let clock = Clock::get()?;
+        let grant = &ctx.accounts.grant;
+        let claimable = grant.vested(&clock).saturating_sub(grant.claimed);
+        require!(claimable > 0, ErrorCode::NothingToClaim);
+
+        let manager = &ctx.accounts.manager;
+        let seeds = [b"abc_manager".as_ref(), manager.mint.as_ref(), &[manager.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.grant_vault.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: manager.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            claimable,
+        )?;
+
+        let grant = &mut ctx.accounts.grant;
+        grant.claimed = grant
+            .claimed
+            .checked_add(claimable)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(VestingClaimed {
+            mint: manager.mint,
+            beneficiary: grant.beneficiary,
+            amount: claimable,
+            total_claimed: grant.claimed,
+            slot: clock.slot,
+        });
+
+        Ok(())
+    }
+
+    // Stops a revocable grant where it stands: the unvested part goes back
+    // to the reserve and what has vested stays claimable
+    pub fn revoke_vesting(ctx: Context<RevokeVesting>) -> Result<()> {
+        let grant = &ctx.accounts.grant;
+        require!(grant.revocable, ErrorCode::VestingNotRevocable);
+        require!(grant.revoked_at.is_none(), ErrorCode::VestingAlreadyRevoked);
+
+        let clock = Clock::get()?;
+        let now = grant.schedule.now(&clock);
+        let unvested = grant
+            .schedule
+            .amount
+            .saturating_sub(grant.schedule.vested_at(now));
+
+        let manager = &ctx.accounts.manager;
+        let seeds = [b"abc_manager".as_ref(), manager.mint.as_ref(), &[manager.bump]];
+        if unvested > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.grant_vault.to_account_info(),
+                        to: ctx.accounts.token_vault.to_account_info(),
+                        authority: manager.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                unvested,
+            )?;
+        }
+
+        // Book what actually came back rather than what was sent
+        ctx.accounts.token_vault.reload()?;
+        let manager = &mut ctx.accounts.manager;
+        manager.reconcile_reserve(ctx.accounts.token_vault.amount);
+        manager.reserve_granted = manager
+            .reserve_granted
+            .checked_sub(unvested)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let grant = &mut ctx.accounts.grant;
+        grant.revoked_at = Some(now);
+
+        emit!(VestingRevoked {
+            mint: manager.mint,
+            beneficiary: grant.beneficiary,
+            returned: unvested,
+            slot: clock.slot,
+        });
+
+        Ok(())
+    }
+
+    // Hands treasury withdrawals, reserve releases and config changes to an
+    // M-of-N approver set. Once installed the authority can't make those
+    // moves alone, and the set only changes through its own proposals
+    pub fn create_approver_set(
+        ctx: Context<CreateApproverSet>,
+        approvers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        ApproverSet::validate(&approvers, threshold)?;
+
+        let approver_set = &mut ctx.accounts.approver_set;
+        approver_set.manager = ctx.accounts.manager.key();
+        approver_set.approvers = approvers;
+        approver_set.threshold = threshold;
+        approver_set.bump = *ctx.bumps.get("approver_set").unwrap();
+
+        let manager = &mut ctx.accounts.manager;
+        manager.approver_set = approver_set.key();
+
+        emit!(ApproversUpdated {
+            mint: manager.mint,
+            approvers: approver_set.approvers.clone(),
+            threshold,
+            generation: approver_set.generation,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    // Opens a proposal at the set's next id, counting the proposer's approval.
+    // A config update can't execute before its timelock, so its lifetime
+    // runs from the eta slot rather than from creation.
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        action: ProposalAction,
+        lifetime_slots: u64,
+    ) -> Result<()> {
+        require!(
+            lifetime_slots > 0 && lifetime_slots <= MAX_PROPOSAL_LIFETIME_SLOTS,
+            ErrorCode::InvalidProposal
+        );
+        action.validate()?;
+
+        let approver_set = &mut ctx.accounts.approver_set;
+        let proposer = ctx.accounts.proposer.key();
+        let index = approver_set
+            .index_of(&proposer)
+            .ok_or(ErrorCode::NotApprover)?;
+
+        let slot = Clock::get()?.slot;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.manager = approver_set.manager;
+        proposal.id = approver_set.proposal_count;
+        proposal.proposer = proposer;
+        proposal.action = action.clone();
+        proposal.generation = approver_set.generation;
+        proposal.created_slot = slot;
+        let opens_slot = match action {
+            ProposalAction::UpdateConfig(_) => slot
+                .checked_add(ctx.accounts.launch_config.config_timelock_slots)
+                .ok_or(ErrorCode::MathOverflow)?,
+            _ => slot,
+        };
+        proposal.expires_slot = opens_slot
+            .checked_add(lifetime_slots)
+            .ok_or(ErrorCode::MathOverflow)?;
+        proposal.bump = *ctx.bumps.get("proposal").unwrap();
+        proposal.approve(index)?;
+
+        approver_set.proposal_count += 1;
+
+        emit!(ProposalCreated {
+            mint: ctx.accounts.manager.mint,
+            proposal: proposal.key(),
+            id: proposal.id,
+            proposer,
+            action,
+            expires_slot: proposal.expires_slot,
+        });
+
+        Ok(())
+    }
+
+    pub fn approve(ctx: Context<ApproveProposal>) -> Result<()> {
+        let approver_set = &ctx.accounts.approver_set;
+        let approver = ctx.accounts.approver.key();
+        let index = approver_set
+            .index_of(&approver)
+            .ok_or(ErrorCode::NotApprover)?;
+
+        let slot = Clock::get()?.slot;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.require_open(approver_set, slot)?;
+        proposal.approve(index)?;
+
+        emit!(ProposalApproved {
+            proposal: proposal.key(),
+            id: proposal.id,
+            approver,
+            approvals: proposal.approval_count(),
+            slot,
+        });
+
+        Ok(())
+    }
+
+    // Anyone may execute a proposal that has enough approvals. Config
+    // changes still wait out the config timelock, counted from the proposal.
+    // Withdrawals and releases take their destination, and on the built-in
+    // pool then the pool account, as remaining accounts
+    pub fn execute<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let proposal = &ctx.accounts.proposal;
+        proposal.require_open(&ctx.accounts.approver_set, slot)?;
+        require!(
+            proposal.approval_count() >= ctx.accounts.approver_set.threshold as u32,
+            ErrorCode::InsufficientApprovals
+        );
+
+        let mint = ctx.accounts.manager.mint;
+        let proposal_key = proposal.key();
+        match proposal.action.clone() {
+            ProposalAction::WithdrawTreasury {
+                amount,
+                destination,
+            } => {
+                let (destination_info, pool_accounts) = ctx
+                    .remaining_accounts
+                    .split_first()
+                    .ok_or(ErrorCode::InvalidProposal)?;
+                require!(
+                    destination_info.key() == destination,
+                    ErrorCode::InvalidWithdrawalDestination
+                );
+
+                trade::withdraw_treasury(
+                    &mut ctx.accounts.manager,
+                    &ctx.accounts.launch_config,
+                    &ctx.accounts.treasury,
+                    *ctx.bumps.get("treasury").unwrap(),
+                    destination_info,
+                    ctx.accounts.system_program.as_ref(),
+                    pool_accounts,
+                    amount,
+                )?;
+
+                emit!(TreasuryWithdrawal {
+                    mint,
+                    authority: proposal_key,
+                    destination,
+                    amount,
+                    total_withdrawn: ctx.accounts.manager.total_withdrawn,
+                    slot,
+                });
+            }
+            ProposalAction::ReleaseReserve {
+                amount,
+                destination,
+            } => {
+                let destination_info = ctx
+                    .remaining_accounts
+                    .first()
+                    .ok_or(ErrorCode::InvalidProposal)?;
+                require!(
+                    destination_info.key() == destination,
+                    ErrorCode::InvalidWithdrawalDestination
+                );
+
+                trade::release_reserve(
+                    &mut ctx.accounts.manager,
+                    &ctx.accounts.launch_config,
+                    &ctx.accounts.token_vault,
+                    destination_info,
+                    ctx.accounts.token_program.as_ref(),
+                    amount,
+                )?;
+
+                emit!(ReserveReleased {
+                    mint,
+                    destination,
+                    amount,
+                    total_released: ctx.accounts.manager.reserve_released,
+                    slot,
+                });
+            }
+            ProposalAction::UpdateConfig(update) => {
+                let eta_slot = proposal
+                    .created_slot
+                    .saturating_add(ctx.accounts.launch_config.config_timelock_slots);
+                if slot < eta_slot {
+                    msg!("Config update locked until slot {} (current {})", eta_slot, slot);
+                    return err!(ErrorCode::TimelockNotExpired);
+                }
+
+                ctx.accounts
+                    .launch_config
+                    .validate_update(&update, &ctx.accounts.manager)?;
+                ctx.accounts.launch_config.apply_update(&update);
+                ctx.accounts.manager.raydium_pool = update.raydium_pool;
+
+                emit!(ConfigUpdateApplied { mint, update, slot });
+            }
+            ProposalAction::SetApprovers {
+                approvers,
+                threshold,
+            } => {
+                let approver_set = &mut ctx.accounts.approver_set;
+                approver_set.replace(approvers, threshold)?;
+
+                emit!(ApproversUpdated {
+                    mint,
+                    approvers: approver_set.approvers.clone(),
+                    threshold,
+                    generation: approver_set.generation,
+                    slot,
+                });
+            }
+            ProposalAction::CloseLaunch => {
+                trade::close_launch(
+                    &mut ctx.accounts.manager,
+                    &ctx.accounts.launch_config,
+                    slot,
+                )?;
+            }
+            ProposalAction::UnlockLiquidity { destination } => {
+                let (lock_info, vault_info, destination_info) = match ctx.remaining_accounts {
+                    [lock, vault, destination, ..] => (lock, vault, destination),
+                    _ => return err!(ErrorCode::InvalidProposal),
+                };
+                require!(
+                    destination_info.key() == destination,
+                    ErrorCode::InvalidWithdrawalDestination
+                );
+
+                let mut lp_lock: Account<LiquidityLock> = Account::try_from(lock_info)?;
+                let (lock_address, _) =
+                    Pubkey::find_program_address(&[b"lp_lock", mint.as_ref()], &crate::ID);
+                let (vault_address, _) =
+                    Pubkey::find_program_address(&[b"lp_vault", mint.as_ref()], &crate::ID);
+                require!(
+                    lock_info.key() == lock_address
+                        && lp_lock.manager == ctx.accounts.manager.key()
+                        && vault_info.key() == vault_address,
+                    ErrorCode::InvalidLiquidityLock
+                );
+
+                let amount = trade::unlock_liquidity(
+                    &ctx.accounts.manager,
+                    &mut lp_lock,
+                    vault_info,
+                    destination_info,
+                    ctx.accounts.token_program.as_ref(),
+                )?;
+                // Not one of the instruction's typed accounts, so persist by hand
+                lp_lock.exit(&crate::ID)?;
+
+                emit!(LiquidityUnlocked {
+                    mint,
+                    destination,
+                    amount,
+                    slot,
+                });
+            }
+        }
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.executed = true;
+
+        emit!(ProposalExecuted {
+            proposal: proposal_key,
+            id: proposal.id,
+            executor: ctx.accounts.executor.key(),
+            slot,
+        });
+
+        Ok(())
+    }
+
+    // Upgrades a pre-versioning ABCManager account in place and creates the
+    // LaunchConfig it predates, so the launch can trade again
+    pub fn migrate_manager(ctx: Context<MigrateManager>, params: LaunchConfigParams) -> Result<()> {
+        params.validate()?;
+
+        let manager_info = ctx.accounts.manager.to_account_info();
+        require!(
+            manager_info.owner == ctx.program_id,
+            ErrorCode::InvalidManagerAccount
+        );
+
+        let legacy = {
+            let data = manager_info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == ABCManager::discriminator(),
+                ErrorCode::InvalidManagerAccount
+            );
+            require!(data.len() < ABCManager::LEN, ErrorCode::AlreadyMigrated);
+
+            // Older layouts may be missing trailing keys; zero-fill reads them as default
+            let mut legacy_data = data[8..].to_vec();
+            legacy_data.resize(LegacyABCManager::LEN, 0);
+            LegacyABCManager::deserialize(&mut legacy_data.as_slice())?
+        };
+
+        require!(
+            legacy.authority == ctx.accounts.authority.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(legacy.mint == ctx.accounts.mint.key(), ErrorCode::InvalidManagerAccount);
+        require!(
+            params.reserve_vesting.amount <= legacy.reserve_tokens,
+            ErrorCode::InvalidLaunchConfig
+        );
+
+        let previous_len = manager_info.data_len();
+        let required_lamports = Rent::get()?.minimum_balance(ABCManager::LEN);
+        let top_up = required_lamports.saturating_sub(manager_info.lamports());
+        if top_up > 0 {
+            invoke(
+                &system_instruction::transfer(
+                    &ctx.accounts.authority.key(),
+                    &manager_info.key(),
+                    top_up,
+                ),
+                &[
+                    ctx.accounts.authority.to_account_info(),
+                    manager_info.clone(),
+                    ctx.accounts.system_program.to_account_info(),
+                ],
+            )?;
+        }
+
+        manager_info.realloc(ABCManager::LEN, true)?;
+
+        let manager = ABCManager::from(legacy);
+        {
+            let mut data = manager_info.try_borrow_mut_data()?;
+            manager.try_serialize(&mut &mut data[..])?;
+        }
+
+        ctx.accounts.launch_config.initialize(
+            manager.mint,
+            &params,
+            *ctx.bumps.get("launch_config").unwrap(),
+        );
+
+        emit!(ManagerMigrated {
+            mint: manager.mint,
+            previous_len: previous_len as u64,
+            version: manager.version,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+}
+
+// Implementation methods for accounts
+impl ABCManager {
+    pub const LEN: usize = 8 // discriminator
+        + 1 // version
+        + 32 // authority
+        + 32 // mint
+        + 8 // launch_slot
+        + 1 // phase
+        + 8 // captured_sol
+        + 8 // reserve_tokens
+        + 1 // bump
+        + 32 // last_blocked_address
+        + 32 // raydium_pool
+        + 32 // pending_authority
+        + 8 // refunded_sol
+        + 32 // guardian
+        + 8 // paused_slot
+        + 8 // pause_expires_slot
+        + 8 // escrowed_sol
+        + 8 // revealed_sol
+        + 8 // curve_tokens_sold
+        + 8 // curve_sol_raised
+        + 8 // refund_penalty_sol
+        + 8 // total_withdrawn
+        + 32 // approver_set
+        + 8 // reserve_released
+        + 8 // reserve_granted
+        + 8 * 13; // reserved
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        &mut self,
+        authority: Pubkey,
+        mint: Pubkey,
+        launch_slot: u64,
+        reserve_amount: u64,
+        bump: u8,
+        raydium_pool: Pubkey,
+        phase: LaunchPhase,
+    ) {
+        self.version = MANAGER_VERSION;
+        self.authority = authority;
+        self.mint = mint;
+        self.launch_slot = launch_slot;
+        self.phase = phase;
+        self.captured_sol = 0;
+        self.reserve_tokens = reserve_amount;
+        self.bump = bump;
+        self.last_blocked_address = Pubkey::default();
+        self.raydium_pool = raydium_pool;
+        self.pending_authority = Pubkey::default();
+        self.refunded_sol = 0;
+        self.guardian = Pubkey::default();
+        self.paused_slot = 0;
+        self.pause_expires_slot = 0;
+        self.escrowed_sol = 0;
+        self.revealed_sol = 0;
+        self.curve_tokens_sold = 0;
+        self.curve_sol_raised = 0;
+        self.refund_penalty_sol = 0;
+        self.total_withdrawn = 0;
+        self.approver_set = Pubkey::default();
+        self.reserve_released = 0;
+        self.reserve_granted = 0;
+    }
+
+    /// Hands the manager to `new_authority`, clearing any pending proposal.
+    /// Returns the previous authority.
+    pub fn transfer_authority(&mut self, new_authority: Pubkey) -> Pubkey {
+        let previous_authority = self.authority;
+        self.authority = new_authority;
+        self.pending_authority = Pubkey::default();
+        previous_authority
+    }
+
+    pub fn is_in_monitoring_period(&self, config: &LaunchConfig, current_slot: u64) -> bool {
+        current_slot <= self.launch_slot.saturating_add(config.monitoring_blocks)
+    }
+
+    /// Phase at `current_slot`. A scheduled launch moves through Monitoring
+    /// to Open as slots pass; every other phase changes only by instruction.
+    pub fn current_phase(&self, config: &LaunchConfig, current_slot: u64) -> LaunchPhase {
+        match self.phase {
+            LaunchPhase::Scheduled | LaunchPhase::Monitoring
+                if current_slot >= self.launch_slot =>
+            {
+                self.trading_phase(config, current_slot)
+            }
+            LaunchPhase::Paused
+                if self.pause_expires_slot != 0 && current_slot >= self.pause_expires_slot =>
+            {
+                self.trading_phase(config, current_slot)
+            }
+            phase => phase,
+        }
+    }
+
+    fn trading_phase(&self, config: &LaunchConfig, current_slot: u64) -> LaunchPhase {
+        if self.is_in_monitoring_period(config, current_slot) {
+            LaunchPhase::Monitoring
+        } else {
+            LaunchPhase::Open
+        }
+    }
+
+    /// Last slot of the commit-reveal reveal window.
+    pub fn reveal_window_end(&self, config: &LaunchConfig) -> u64 {
+        self.launch_slot
+            .saturating_add(config.monitoring_blocks)
+            .saturating_add(config.reveal_window_slots)
+    }
+
+    pub fn is_reveal_window(&self, config: &LaunchConfig, current_slot: u64) -> bool {
+        !self.is_in_monitoring_period(config, current_slot)
+            && current_slot <= self.reveal_window_end(config)
+    }
+
+    pub fn can_pause(&self, operator: &Pubkey) -> bool {
+        *operator == self.authority
+            || (self.guardian != Pubkey::default() && *operator == self.guardian)
+    }
+
+    /// Pauses a live launch, setting when the pause lifts by itself.
+    pub fn pause(&mut self, config: &LaunchConfig, current_slot: u64) -> Result<()> {
+        let phase = self.current_phase(config, current_slot);
+        if phase != self.phase {
+            self.set_phase(phase)?;
+        }
+        self.set_phase(LaunchPhase::Paused)?;
+
+        self.paused_slot = current_slot;
+        self.pause_expires_slot = match config.max_pause_slots {
+            0 => 0,
+            max_pause_slots => current_slot.saturating_add(max_pause_slots),
+        };
+        Ok(())
+    }
+
+    /// Ends a pause early, or records one that already expired. Returns the
+    /// phase trading resumes in.
+    pub fn resume(&mut self, config: &LaunchConfig, current_slot: u64) -> Result<LaunchPhase> {
+        require!(self.phase == LaunchPhase::Paused, ErrorCode::TradingNotPaused);
+
+        let phase = self.trading_phase(config, current_slot);
+        self.set_phase(phase)?;
+        self.pause_expires_slot = 0;
+        Ok(phase)
+    }
+
+    /// Moves to `next` if the lifecycle allows it. Returns the previous phase.
+    pub fn set_phase(&mut self, next: LaunchPhase) -> Result<LaunchPhase> {
+        require!(
+            self.phase.can_transition_to(next),
+            ErrorCode::InvalidPhaseTransition
+        );
+        Ok(std::mem::replace(&mut self.phase, next))
+    }
+
+    /// Sets the launch to open at `start_slot`, from Created, Presale, or a
+    /// Scheduled launch that hasn't started. Returns the previous phase.
+    pub fn schedule_launch(&mut self, start_slot: u64, current_slot: u64) -> Result<LaunchPhase> {
+        require!(start_slot >= current_slot, ErrorCode::InvalidLaunchSlot);
+        require!(
+            self.phase != LaunchPhase::Scheduled || current_slot < self.launch_slot,
+            ErrorCode::InvalidPhaseTransition
+        );
+
+        let previous = self.set_phase(LaunchPhase::Scheduled)?;
+        self.launch_slot = start_slot;
+        Ok(previous)
+    }
+
+    /// Start of the cap window `current_slot` falls in, or None outside
+    /// fair-launch mode and once the monitoring period is over.
+    pub fn wallet_cap_window(&self, config: &LaunchConfig, current_slot: u64) -> Option<u64> {
+        if config.wallet_cap_sol == 0 || !self.is_in_monitoring_period(config, current_slot) {
+            return None;
+        }
+
+        let elapsed = current_slot.saturating_sub(self.launch_slot);
+        Some(self.launch_slot + elapsed - elapsed % config.wallet_cap_window_slots)
+    }
+
+    /// Treasury SOL that still belongs to someone: unclaimed refunds,
+    /// unsettled escrow and SOL raised on a curve that hasn't graduated. The
+    /// built-in pool's share is counted separately.
+    pub fn committed_treasury_sol(&self) -> Result<u64> {
+        let unclaimed_refunds = self
+            .captured_sol
+            .saturating_sub(self.refunded_sol)
+            .saturating_sub(self.refund_penalty_sol);
+        let curve_sol = if self.phase == LaunchPhase::BondingCurve {
+            self.curve_sol_raised
+        } else {
+            0
+        };
+
+        unclaimed_refunds
+            .checked_add(self.escrowed_sol)
+            .and_then(|sol| sol.checked_add(curve_sol))
+            .ok_or_else(|| error!(ErrorCode::MathOverflow))
+    }
+
+    pub fn update_bot_capture(&mut self, bot_address: Pubkey, sol_amount: u64) -> Result<()> {
+        self.last_blocked_address = bot_address;
+        self.captured_sol = self.captured_sol
+            .checked_add(sol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Brings `reserve_tokens` in line with the reserve account's actual SPL
+    /// balance, which every debit and credit is then booked against.
+    pub fn reconcile_reserve(&mut self, vault_balance: u64) {
+        if self.reserve_tokens != vault_balance {
+            msg!(
+                "Reserve reconciled from {} to {}",
+                self.reserve_tokens,
+                vault_balance
+            );
+            self.reserve_tokens = vault_balance;
+        }
+    }
+
+    /// Reserve not owed to `LaunchConfig::reserve_vesting`. Everything the
+    /// schedule hasn't released yet stays ring-fenced.
+    pub fn spendable_reserve(&self, config: &LaunchConfig) -> u64 {
+        let unreleased = config
+            .reserve_vesting
+            .amount
+            .saturating_sub(self.reserve_released);
+        self.reserve_tokens.saturating_sub(unreleased)
+    }
+
+    /// Books an outflow against the spendable reserve. Vesting releases bump
+    /// `reserve_released` first so they draw on the ring-fenced part.
+    pub fn debit_reserve(&mut self, amount: u64, config: &LaunchConfig) -> Result<()> {
+        require!(
+            amount <= self.spendable_reserve(config),
+            ErrorCode::InsufficientReserve
+        );
+        self.reserve_tokens = self
+            .reserve_tokens
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientReserve)?;
+        Ok(())
+    }
+
+    pub fn credit_reserve(&mut self, amount: u64) -> Result<()> {
+        self.reserve_tokens = self
+            .reserve_tokens
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+}
+
+impl From<LegacyABCManager> for ABCManager {
+    fn from(legacy: LegacyABCManager) -> Self {
+        Self {
+            version: MANAGER_VERSION,
+            authority: legacy.authority,
+            mint: legacy.mint,
+            launch_slot: legacy.launch_slot,
+            phase: if legacy.is_launched {
+                LaunchPhase::Open
+            } else {
+                LaunchPhase::Created
+            },
+            captured_sol: legacy.captured_sol,
+            reserve_tokens: legacy.reserve_tokens,
+            bump: legacy.bump,
+            last_blocked_address: legacy.last_blocked_address,
+            raydium_pool: legacy.raydium_pool,
+            pending_authority: legacy.pending_authority,
+            refunded_sol: 0,
+            guardian: Pubkey::default(),
+            paused_slot: 0,
+            pause_expires_slot: 0,
+            escrowed_sol: 0,
+            revealed_sol: 0,
+            curve_tokens_sold: 0,
+            curve_sol_raised: 0,
+            refund_penalty_sol: 0,
+            total_withdrawn: 0,
+            approver_set: Pubkey::default(),
+            reserve_released: 0,
+            reserve_granted: 0,
+            reserved: [0; 13],
+        }
+    }
+}
+
+impl LegacyABCManager {
+    pub const LEN: usize = 32 + 32 + 8 + 1 + 8 + 8 + 1 + 32 + 32 + 32;
+}
+
+impl LaunchConfig {
+    pub const LEN: usize = 8 // discriminator
+        + 32 // mint
+        + 8 // monitoring_blocks
+        + 8 // min_trade_sol
+        + 8 // max_trade_sol
+        + 8 // max_price_impact_bps
+        + 8 // slippage_tolerance_bps
+        + 1 // dex
+        + 32 // dex_program
+        + 8 // config_timelock_slots
+        + 8 // pool_fee_bps
+        + 8 // refund_penalty_bps
+        + 8 // wallet_cap_sol
+        + 8 // wallet_cap_window_slots
+        + 32 // presale_merkle_root
+        + 8 // presale_tokens_per_sol
+        + 8 // max_pause_slots
+        + 8 // commit_allocation_tokens
+        + 8 // reveal_window_slots
+        + 8 // auction_tokens
+        + 8 // auction_floor_price
+        + 8 // auction_tick_size
+        + BondingCurve::LEN // curve
+        + 8 // graduation_sol
+        + VestingSchedule::LEN // reserve_vesting
+        + 1 // lp_lock_clock
+        + 8 // lp_lock_until
+        + 8 // lp_lock_duration
+        + 1; // bump
+
+    pub fn initialize(&mut self, mint: Pubkey, params: &LaunchConfigParams, bump: u8) {
+        self.mint = mint;
+        self.monitoring_blocks = params.monitoring_blocks;
+        self.min_trade_sol = params.min_trade_sol;
+        self.max_trade_sol = params.max_trade_sol;
+        self.max_price_impact_bps = params.max_price_impact_bps;
+        self.slippage_tolerance_bps = params.slippage_tolerance_bps;
+        self.dex = params.dex;
+        self.dex_program = params.dex_program;
+        self.config_timelock_slots = params.config_timelock_slots;
+        self.pool_fee_bps = params.pool_fee_bps;
+        self.refund_penalty_bps = params.refund_penalty_bps;
+        self.wallet_cap_sol = params.wallet_cap_sol;
+        self.wallet_cap_window_slots = params.wallet_cap_window_slots;
+        self.presale_merkle_root = params.presale_merkle_root;
+        self.presale_tokens_per_sol = params.presale_tokens_per_sol;
+        self.max_pause_slots = params.max_pause_slots;
+        self.commit_allocation_tokens = params.commit_allocation_tokens;
+        self.reveal_window_slots = params.reveal_window_slots;
+        self.auction_tokens = params.auction_tokens;
+        self.auction_floor_price = params.auction_floor_price;
+        self.auction_tick_size = params.auction_tick_size;
+        self.curve = params.curve;
+        self.graduation_sol = params.graduation_sol;
+        self.reserve_vesting = params.reserve_vesting;
+        self.lp_lock_clock = params.lp_lock_clock;
+        self.lp_lock_until = params.lp_lock_until;
+        self.lp_lock_duration = params.lp_lock_duration;
+        self.bump = bump;
+    }
+
+    pub fn commit_reveal_enabled(&self) -> bool {
+        self.commit_allocation_tokens > 0
+    }
+
+    /// The LP mint `lock_liquidity` takes, if any. Raydium launches lock
+    /// their pool's LP at `initialize`, and Whirlpool liquidity is held as
+    /// position NFTs, not LP tokens.
+    pub fn lockable_lp_mint(&self) -> Option<Pubkey> {
+        match self.dex {
+            DexKind::ConstantProduct => Some(native::lp_mint_address(&self.mint)),
+            DexKind::RaydiumAmmV4 | DexKind::Whirlpool => None,
+        }
+    }
+
+    pub fn auction_enabled(&self) -> bool {
+        self.auction_tokens > 0
+    }
+
+    /// Whether `buy` stays closed during the monitoring window because the
+    /// launch collects orders there another way.
+    pub fn defers_window_buys(&self) -> bool {
+        self.commit_reveal_enabled() || self.auction_enabled()
+    }
+
+    /// Checks a queued update against the launch as it stands when the
+    /// update lands. The built-in pool sits at a fixed PDA, so only
+    /// external venues can be repointed, and the minimum trade can't rise
+    /// past the wallet cap.
+    pub fn validate_update(&self, update: &ConfigUpdate, manager: &ABCManager) -> Result<()> {
+        require!(
+            self.dex != DexKind::ConstantProduct || update.raydium_pool == manager.raydium_pool,
+            ErrorCode::InvalidLaunchConfig
+        );
+        require!(
+            self.wallet_cap_sol == 0 || self.wallet_cap_sol >= update.min_trade_sol,
+            ErrorCode::InvalidLaunchConfig
+        );
+        Ok(())
+    }
+
+    pub fn apply_update(&mut self, update: &ConfigUpdate) {
+        self.min_trade_sol = update.min_trade_sol;
+        self.max_trade_sol = update.max_trade_sol;
+        self.max_price_impact_bps = update.max_price_impact_bps;
+        self.slippage_tolerance_bps = update.slippage_tolerance_bps;
+    }
+}
+
+fn validate_trading_limits(
+    min_trade_sol: u64,
+    max_trade_sol: u64,
+    max_price_impact_bps: u64,
+    slippage_tolerance_bps: u64,
+) -> Result<()> {
+    require!(
+        min_trade_sol >= MIN_TRADE_FLOOR_SOL
+            && min_trade_sol <= max_trade_sol
+            && max_trade_sol <= MAX_TRADE_CEILING_SOL,
+        ErrorCode::InvalidLaunchConfig
+    );
+    require!(
+        max_price_impact_bps > 0 && max_price_impact_bps <= MAX_PRICE_IMPACT_CEILING_BPS,
+        ErrorCode::InvalidLaunchConfig
+    );
+    require!(
+        slippage_tolerance_bps <= MAX_SLIPPAGE_TOLERANCE_BPS,
+        ErrorCode::InvalidLaunchConfig
+    );
+    Ok(())
+}
+
+impl LaunchConfigParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.monitoring_blocks <= MAX_MONITORING_BLOCKS,
+            ErrorCode::InvalidLaunchConfig
+        );
+        validate_trading_limits(
+            self.min_trade_sol,
+            self.max_trade_sol,
+            self.max_price_impact_bps,
+            self.slippage_tolerance_bps,
+        )?;
+        require!(
+            self.config_timelock_slots >= MIN_CONFIG_TIMELOCK_SLOTS
+                && self.config_timelock_slots <= MAX_CONFIG_TIMELOCK_SLOTS,
+            ErrorCode::InvalidLaunchConfig
+        );
+        require!(
+            self.pool_fee_bps <= MAX_POOL_FEE_BPS,
+            ErrorCode::InvalidLaunchConfig
+        );
+        require!(
+            self.refund_penalty_bps <= MAX_REFUND_PENALTY_BPS,
+            ErrorCode::InvalidLaunchConfig
+        );
+        // A cap below the minimum trade would block every early buy
+        if self.wallet_cap_sol > 0 {
+            require!(
+                self.wallet_cap_sol >= self.min_trade_sol
+                    && self.wallet_cap_window_slots > 0
+                    && self.wallet_cap_window_slots <= MAX_MONITORING_BLOCKS,
+                ErrorCode::InvalidLaunchConfig
+            );
+        }
+        require!(
+            !self.has_presale() || self.presale_tokens_per_sol > 0,
+            ErrorCode::InvalidLaunchConfig
+        );
+        require!(
+            self.max_pause_slots <= MAX_PAUSE_SLOTS,
+            ErrorCode::InvalidLaunchConfig
+        );
+        // Commit-reveal replaces the window's other buy handling, caps included
+        if self.commit_allocation_tokens > 0 {
+            require!(
+                self.monitoring_blocks > 0
+                    && self.wallet_cap_sol == 0
+                    && self.reveal_window_slots > 0
+                    && self.reveal_window_slots <= MAX_REVEAL_WINDOW_SLOTS,
+                ErrorCode::InvalidLaunchConfig
+            );
+        }
+        // Likewise for the batch auction, which also can't share the window
+        // with commit-reveal
+        if self.auction_tokens > 0 {
+            require!(
+                self.monitoring_blocks > 0
+                    && self.wallet_cap_sol == 0
+                    && self.commit_allocation_tokens == 0
+                    && self.auction_floor_price > 0,
+                ErrorCode::InvalidLaunchConfig
+            );
+        }
+        // The curve replaces the window entirely and graduates into the
+        // built-in pool, and it has to be able to reach its target
+        if self.curve.is_enabled() {
+            require!(
+                self.curve.step_tokens > 0
+                    && self.curve.start_price > 0
+                    && self.graduation_sol > 0
+                    && !self.has_presale()
+                    && self.commit_allocation_tokens == 0
+                    && self.auction_tokens == 0
+                    && self.dex == DexKind::ConstantProduct,
+                ErrorCode::InvalidLaunchConfig
+            );
+            require!(
+                self.curve.kind != CurveKind::Exponential
+                    || self.curve.growth <= MAX_CURVE_GROWTH_BPS,
+                ErrorCode::InvalidLaunchConfig
+            );
+            // A curve whose prices overflow before its last step is misconfigured
+            let (_, curve_sol) = pricing::calculate_curve_buy(&self.curve, 0, u64::MAX)
+                .map_err(|_| error!(ErrorCode::InvalidLaunchConfig))?;
+            require!(
+                curve_sol >= self.graduation_sol,
+                ErrorCode::InvalidLaunchConfig
+            );
+        }
+        self.reserve_vesting.validate()?;
+        // The built-in pool lives in this program; external venues can't
+        match self.dex {
+            DexKind::ConstantProduct => require!(
+                self.dex_program == crate::ID,
+                ErrorCode::InvalidLaunchConfig
+            ),
+            DexKind::RaydiumAmmV4 | DexKind::Whirlpool => require!(
+                self.dex_program != Pubkey::default() && self.dex_program != crate::ID,
+                ErrorCode::InvalidLaunchConfig
+            ),
+        }
+        Ok(())
+    }
+}
+
+impl LaunchConfigParams {
+    pub fn has_presale(&self) -> bool {
+        self.presale_merkle_root != [0; 32]
+    }
+
+    /// Launches wait in Created until `schedule_launch`, or sell to the
+    /// allowlist or along the curve first.
+    pub fn initial_phase(&self) -> LaunchPhase {
+        if self.has_presale() {
+            LaunchPhase::Presale
+        } else if self.curve.is_enabled() {
+            LaunchPhase::BondingCurve
+        } else {
+            LaunchPhase::Created
+        }
+    }
+
+    /// Launches that open a pool themselves lock its LP for at least a day:
+    /// curves for `lp_lock_duration` from graduation, Raydium launches until
+    /// `lp_lock_until`, counted from `initialize`.
+    pub fn validate_lp_lock(&self, clock: &Clock) -> Result<()> {
+        let min_lock = match self.lp_lock_clock {
+            VestingClock::Slot => MIN_LP_LOCK_SLOTS,
+            VestingClock::UnixTimestamp => MIN_LP_LOCK_SECONDS,
+        };
+
+        if self.curve.is_enabled() {
+            require!(
+                self.lp_lock_duration >= min_lock,
+                ErrorCode::InvalidLaunchConfig
+            );
+        } else if self.dex == DexKind::RaydiumAmmV4 {
+            let earliest = self
+                .lp_lock_clock
+                .now(clock)
+                .checked_add(min_lock)
+                .ok_or(ErrorCode::MathOverflow)?;
+            require!(
+                self.lp_lock_until >= earliest,
+                ErrorCode::InvalidLaunchConfig
+            );
+        }
+        Ok(())
+    }
+}
+
+impl PendingConfigUpdate {
+    pub const LEN: usize = 8 // discriminator
+        + 32 // mint
+        + ConfigUpdate::LEN // update
+        + 8 // proposed_slot
+        + 8 // eta_slot
+        + 1; // bump
+}
+
+impl MonitoredPurchase {
+    pub const LEN: usize = 8 // discriminator
+        + 32 // manager
+        + 32 // buyer
+        + 8 // sol_amount
+        + 8 // last_slot
+        + 1; // bump
+
+    /// Splits the recorded SOL into what goes back to the buyer and the
+    /// penalty the treasury keeps.
+    pub fn refund_amounts(&self, penalty_bps: u64) -> Result<(u64, u64)> {
+        let penalty = (self.sol_amount as u128)
+            .checked_mul(penalty_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / BPS_DENOMINATOR as u128;
+        let penalty = penalty as u64;
+
+        Ok((self.sol_amount - penalty, penalty))
+    }
+}
+
+impl FlaggedWallet {
+    pub const LEN: usize = 8 // discriminator
+        + 32 // manager
+        + 32 // wallet
+        + 8 // flag_count
+        + 8 // total_sol
+        + 8 // first_slot
+        + 8 // last_slot
+        + 1 // cleared
+        + 8 // cleared_slot
+        + 1; // bump
+
+    pub fn record(&mut self, sol_amount: u64, slot: u64) -> Result<()> {
+        self.flag_count = self.flag_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        self.total_sol = self
+            .total_sol
+            .checked_add(sol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.last_slot = slot;
+        Ok(())
+    }
+}
+
+impl Auction {
+    pub const LEN: usize = 8 // discriminator
+        + 32 // manager
+        + 8 * AUCTION_PRICE_TICKS // demand_sol
+        + 8 // total_bid_sol
+        + 1 // cleared
+        + 1 // clearing_tick
+        + 8 // marginal_tokens
+        + 1; // bump
+
+    pub fn add_bid(&mut self, sol_amount: u64, tick: u8) -> Result<()> {
+        let demand = &mut self.demand_sol[tick as usize];
+        *demand = demand
+            .checked_add(sol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_bid_sol = self
+            .total_bid_sol
+            .checked_add(sol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Fixes the uniform price: the highest tick where demand from bids at
+    /// or above it covers `auction_tokens`. Bids above the clearing tick
+    /// fill in full and bids on it share what's left. If the bids above a
+    /// tick already cover the supply at its price, the tick above clears in
+    /// full instead; unsold tokens, like an undersubscribed auction's, stay
+    /// in reserve.
+    pub fn clear(&mut self, config: &LaunchConfig) -> Result<()> {
+        let supply = config.auction_tokens;
+        let mut sol_above = 0u64;
+        let mut clearing = None;
+
+        for tick in (0..AUCTION_PRICE_TICKS).rev() {
+            let price = auction::price(config, tick as u8);
+            let tokens_above = pricing::tokens_at_price(sol_above, price)?;
+            let tokens_here = pricing::tokens_at_price(self.demand_sol[tick], price)?;
+
+            if tokens_above >= supply {
+                let tick = tick + 1;
+                let price = auction::price(config, tick as u8);
+                clearing = Some((tick as u8, pricing::tokens_at_price(self.demand_sol[tick], price)?));
+                break;
+            }
+            if tokens_above.saturating_add(tokens_here) >= supply {
+                clearing = Some((tick as u8, supply - tokens_above));
+                break;
+            }
+            sol_above = sol_above.saturating_add(self.demand_sol[tick]);
+        }
+
+        let (clearing_tick, marginal_tokens) = match clearing {
+            Some(clearing) => clearing,
+            None => (0, pricing::tokens_at_price(self.demand_sol[0], config.auction_floor_price)?),
+        };
+
+        self.clearing_tick = clearing_tick;
+        self.marginal_tokens = marginal_tokens;
+        self.cleared = true;
+        Ok(())
+    }
+
+    /// Tokens and SOL refund for `bid` at the clearing price.
+    pub fn fill(&self, config: &LaunchConfig, bid: &Bid) -> Result<(u64, u64)> {
+        require!(self.cleared, ErrorCode::AuctionActive);
+
+        let price = auction::price(config, self.clearing_tick);
+        let token_amount = match bid.tick.cmp(&self.clearing_tick) {
+            std::cmp::Ordering::Less => return Ok((0, bid.sol_amount)),
+            std::cmp::Ordering::Greater => pricing::tokens_at_price(bid.sol_amount, price)?,
+            std::cmp::Ordering::Equal => {
+                let tokens = (self.marginal_tokens as u128)
+                    .checked_mul(bid.sol_amount as u128)
+                    .ok_or(ErrorCode::MathOverflow)?
+                    / self.demand_sol[bid.tick as usize] as u128;
+                tokens as u64
+            }
+        };
+
+        let cost = pricing::cost_at_price(token_amount, price)?.min(bid.sol_amount);
+        Ok((token_amount, bid.sol_amount - cost))
+    }
+}
+
+impl Bid {
+    pub const LEN: usize = 8 // discriminator
+        + 32 // manager
+        + 32 // bidder
+        + 8 // sol_amount
+        + 1 // tick
+        + 1; // bump
+}
+
+impl VestingGrant {
+    pub const LEN: usize = 8 // discriminator
+        + 32 // manager
+        + 32 // beneficiary
+        + VestingSchedule::LEN // schedule
+        + 8 // claimed
+        + 1 // revocable
+        + 1 + 8 // revoked_at
+        + 1; // bump
+
+    /// Total vested so far, frozen at the revocation point if revoked.
+    pub fn vested(&self, clock: &Clock) -> u64 {
+        let now = self
+            .revoked_at
+            .unwrap_or_else(|| self.schedule.now(clock));
+        self.schedule.vested_at(now)
+    }
+}
+
+impl LiquidityLock {
+    pub const LEN: usize = 8 // discriminator
+        + 32 // manager
+        + 32 // lp_mint
+        + 8 // amount
+        + 1 // clock
+        + 8 // lock_until
+        + 1; // bump
+
+    pub fn initialize(
+        &mut self,
+        manager: Pubkey,
+        lp_mint: Pubkey,
+        amount: u64,
+        clock: VestingClock,
+        lock_until: u64,
+        bump: u8,
+    ) {
+        self.manager = manager;
+        self.lp_mint = lp_mint;
+        self.amount = amount;
+        self.clock = clock;
+        self.lock_until = lock_until;
+        self.bump = bump;
+    }
+
+    pub fn is_expired(&self, clock: &Clock) -> bool {
+        self.clock.now(clock) >= self.lock_until
+    }
+}
+
+impl BondingCurve {
+    pub const LEN: usize = 1 // kind
+        + 8 // start_price
+        + 8 // growth
+        + 8; // step_tokens
+
+    pub fn is_enabled(&self) -> bool {
+        self.kind != CurveKind::Disabled
+    }
+
+    /// Price of the `step`th block of `step_tokens`, in lamports per
+    /// PRICE_UNIT.
+    pub fn step_price(&self, step: u64) -> Result<u64> {
+        let mut price = self.start_price;
+        for _ in 0..step.min(MAX_CURVE_STEPS) {
+            price = self.next_price(price)?;
+        }
+        Ok(price)
+    }
+
+    fn next_price(&self, price: u64) -> Result<u64> {
+        let next = match self.kind {
+            CurveKind::Disabled => Some(price),
+            CurveKind::Linear => price.checked_add(self.growth),
+            CurveKind::Exponential => BPS_DENOMINATOR
+                .checked_add(self.growth)
+                .and_then(|factor| {
+                    (price as u128 * factor as u128 / BPS_DENOMINATOR as u128)
+                        .try_into()
+                        .ok()
+                }),
+        };
+        next.ok_or_else(|| error!(ErrorCode::MathOverflow))
+    }
+}
+
+impl ApproverSet {
+    pub const LEN: usize = 8 // discriminator
+        + 32 // manager
+        + 4 + 32 * MAX_APPROVERS // approvers
+        + 1 // threshold
+        + 8 // generation
+        + 8 // proposal_count
+        + 1; // bump
+
+    pub fn validate(approvers: &[Pubkey], threshold: u8) -> Result<()> {
+        require!(
+            !approvers.is_empty()
+                && approvers.len() <= MAX_APPROVERS
+                && threshold > 0
+                && threshold as usize <= approvers.len(),
+            ErrorCode::InvalidApproverSet
+        );
+        for (i, approver) in approvers.iter().enumerate() {
+            require!(
+                *approver != Pubkey::default() && !approvers[..i].contains(approver),
+                ErrorCode::InvalidApproverSet
+            );
+        }
+        Ok(())
+    }
+
+    pub fn index_of(&self, approver: &Pubkey) -> Option<usize> {
+        self.approvers.iter().position(|a| a == approver)
+    }
+
+    /// Swaps in a new set. Bumping the generation voids open proposals,
+    /// whose approvals index into the old one.
+    pub fn replace(&mut self, approvers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        Self::validate(&approvers, threshold)?;
+        self.approvers = approvers;
+        self.threshold = threshold;
+        self.generation += 1;
+        Ok(())
+    }
+}
+
+impl ProposalAction {
+    /// Largest variant: SetApprovers with a full set.
+    pub const LEN: usize = 1 + 4 + 32 * MAX_APPROVERS + 1;
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            ProposalAction::WithdrawTreasury { amount, .. }
+            | ProposalAction::ReleaseReserve { amount, .. } => {
+                require!(*amount > 0, ErrorCode::InvalidProposal);
+                Ok(())
+            }
+            ProposalAction::UpdateConfig(update) => update.validate(),
+            ProposalAction::SetApprovers {
+                approvers,
+                threshold,
+            } => ApproverSet::validate(approvers, *threshold),
+            ProposalAction::CloseLaunch | ProposalAction::UnlockLiquidity { .. } => Ok(()),
+        }
+    }
+}
+
+impl Proposal {
+    pub const LEN: usize = 8 // discriminator
+        + 32 // manager
+        + 8 // id
+        + 32 // proposer
+        + ProposalAction::LEN // action
+        + 8 // generation
+        + 2 // approvals
+        + 8 // created_slot
+        + 8 // expires_slot
+        + 1 // executed
+        + 1; // bump
+
+    pub fn approval_count(&self) -> u32 {
+        self.approvals.count_ones()
+    }
+
+    pub fn approve(&mut self, index: usize) -> Result<()> {
+        let bit = 1u16 << index;
+        require!(self.approvals & bit == 0, ErrorCode::AlreadyApproved);
+        self.approvals |= bit;
+        Ok(())
+    }
+
+    /// Errors unless the proposal can still be approved or executed.
+    pub fn require_open(&self, approver_set: &ApproverSet, current_slot: u64) -> Result<()> {
+        require!(!self.executed, ErrorCode::ProposalAlreadyExecuted);
+        require!(
+            current_slot < self.expires_slot,
+            ErrorCode::ProposalExpired
+        );
+        require!(
+            self.generation == approver_set.generation,
+            ErrorCode::ProposalStale
+        );
+        Ok(())
+    }
+}
+
+impl PurchaseCommitment {
+    pub const LEN: usize = 8 // discriminator
+        + 32 // manager
+        + 32 // buyer
+        + 32 // commitment
+        + 8 // escrow_sol
+        + 8 // revealed_sol
+        + 1; // bump
+
+    /// keccak(buyer || sol_amount LE || salt). Binding the buyer stops a
+    /// commitment being copied by another wallet.
+    pub fn hash(buyer: &Pubkey, sol_amount: u64, salt: &[u8; 32]) -> [u8; 32] {
+        anchor_lang::solana_program::keccak::hashv(&[
+            buyer.as_ref(),
+            &sol_amount.to_le_bytes(),
+            salt,
+        ])
+        .0
+    }
+
+    pub fn reveal(&mut self, sol_amount: u64, salt: &[u8; 32], min_trade_sol: u64) -> Result<()> {
+        require!(self.revealed_sol == 0, ErrorCode::AlreadyRevealed);
+        require!(
+            Self::hash(&self.buyer, sol_amount, salt) == self.commitment,
+            ErrorCode::InvalidCommitment
+        );
+        require!(sol_amount >= min_trade_sol, ErrorCode::TradeTooSmall);
+        require!(sol_amount <= self.escrow_sol, ErrorCode::InvalidCommitment);
+
+        self.revealed_sol = sol_amount;
+        Ok(())
+    }
+
+    /// Tokens owed from `allocation` given everything revealed, and escrow
+    /// to refund. The revealed amount is spent in full.
+    pub fn fill(&self, allocation: u64, total_revealed_sol: u64) -> Result<(u64, u64)> {
+        if self.revealed_sol == 0 {
+            return Ok((0, self.escrow_sol));
+        }
+
+        let tokens = (allocation as u128)
+            .checked_mul(self.revealed_sol as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(total_revealed_sol as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok((tokens as u64, self.escrow_sol - self.revealed_sol))
+    }
+}
+
+impl PresalePurchase {
+    pub const LEN: usize = 8 // discriminator
+        + 32 // manager
+        + 32 // buyer
+        + 8 // allocation
+        + 8 // purchased_sol
+        + 1; // bump
+
+    /// Adds a buy against the allocation proven in this call.
+    pub fn record(&mut self, sol_amount: u64, allocation: u64) -> Result<()> {
+        let purchased_sol = self
+            .purchased_sol
+            .checked_add(sol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            purchased_sol <= allocation,
+            ErrorCode::PresaleAllocationExceeded
+        );
+
+        self.allocation = allocation;
+        self.purchased_sol = purchased_sol;
+        Ok(())
+    }
+}
+
+impl WalletPurchases {
+    pub const LEN: usize = 8 // discriminator
+        + 32 // manager
+        + 32 // wallet
+        + 8 // window_start_slot
+        + 8 // window_sol
+        + 8 // total_sol
+        + 1; // bump
+
+    /// Adds a buy to the window starting at `window_start`, resetting the
+    /// running amount when a new window begins.
+    pub fn record(&mut self, sol_amount: u64, window_start: u64, cap: u64) -> Result<()> {
+        if self.window_start_slot != window_start {
+            self.window_start_slot = window_start;
+            self.window_sol = 0;
+        }
+
+        let window_sol = self
+            .window_sol
+            .checked_add(sol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(window_sol <= cap, ErrorCode::WalletCapExceeded);
+
+        self.window_sol = window_sol;
+        self.total_sol = self
+            .total_sol
+            .checked_add(sol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+}
+
+impl NativePool {
+    pub const LEN: usize = 8 // discriminator
+        + 32 // mint
+        + 32 // lp_mint
+        + 32 // token_vault
+        + 8 // sol_reserve
+        + 8 // token_reserve
+        + 1 // bump
+        + 1; // treasury_bump
+
+    pub fn initialize(
+        &mut self,
+        mint: Pubkey,
+        lp_mint: Pubkey,
+        token_vault: Pubkey,
+        bump: u8,
+        treasury_bump: u8,
+    ) {
+        self.mint = mint;
+        self.lp_mint = lp_mint;
+        self.token_vault = token_vault;
+        self.bump = bump;
+        self.treasury_bump = treasury_bump;
+    }
+
+    pub fn reserves(&self) -> pricing::PoolReserves {
+        pricing::PoolReserves {
+            sol: self.sol_reserve,
+            token: self.token_reserve,
+        }
+    }
+}
+
+impl ConfigUpdate {
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 32;
+
+    pub fn validate(&self) -> Result<()> {
+        validate_trading_limits(
+            self.min_trade_sol,
+            self.max_trade_sol,
+            self.max_price_impact_bps,
+            self.slippage_tolerance_bps,
+        )?;
+        require!(
+            self.raydium_pool != Pubkey::default(),
+            ErrorCode::InvalidLaunchConfig
+        );
+        Ok(())
+    }
+}
+
+impl Default for LaunchConfigParams {
+    fn default() -> Self {
+        Self {
+            monitoring_blocks: DEFAULT_MONITORING_BLOCKS,
+            min_trade_sol: DEFAULT_MIN_TRADE_SOL,
+            max_trade_sol: DEFAULT_MAX_TRADE_SOL,
+            max_price_impact_bps: DEFAULT_MAX_PRICE_IMPACT_BPS,
+            slippage_tolerance_bps: DEFAULT_SLIPPAGE_TOLERANCE_BPS,
+            config_timelock_slots: DEFAULT_CONFIG_TIMELOCK_SLOTS,
+            pool_fee_bps: DEFAULT_POOL_FEE_BPS,
+            refund_penalty_bps: DEFAULT_REFUND_PENALTY_BPS,
+            wallet_cap_sol: DEFAULT_WALLET_CAP_SOL,
+            wallet_cap_window_slots: DEFAULT_WALLET_CAP_WINDOW_SLOTS,
+            presale_merkle_root: [0; 32],
+            presale_tokens_per_sol: 0,
+            max_pause_slots: DEFAULT_MAX_PAUSE_SLOTS,
+            commit_allocation_tokens: DEFAULT_COMMIT_ALLOCATION_TOKENS,
+            reveal_window_slots: DEFAULT_REVEAL_WINDOW_SLOTS,
+            auction_tokens: DEFAULT_AUCTION_TOKENS,
+            auction_floor_price: 0,
+            auction_tick_size: 0,
+            curve: BondingCurve::default(),
+            graduation_sol: 0,
+            reserve_vesting: VestingSchedule::default(),
+            lp_lock_clock: VestingClock::Slot,
+            lp_lock_until: 0,
+            lp_lock_duration: 0,
+            dex: DexKind::RaydiumAmmV4,
+            dex_program: Pubkey::from_str(RAYDIUM_PROGRAM_ID).unwrap(),
+        }
+    }
+}
+
+// Separate module for trading logic
+mod trade {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token::spl_token::{self, native_mint};
+
+    pub fn process_regular_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, Trade<'info>>,
+        sol_amount: u64,
+        min_tokens_out: u64,
+    ) -> Result<()> {
+        validate_trade_amount(&ctx.accounts.launch_config, sol_amount)?;
+
+        let pool = dex::load(ctx.accounts, ctx.remaining_accounts)?;
+        let trader_sol_account = load_sol_account(
+            pool.as_ref(),
+            ctx.remaining_accounts,
+            &ctx.accounts.trader.key(),
+            ctx.accounts.trader.as_ref(),
+        )?;
+
+        let reserves = pool.reserves()?;
+        let tokens_received = pricing::calculate_tokens_from_sol(
+            &reserves,
+            sol_amount,
+            pool.fee_bps(&ctx.accounts.launch_config),
+        )?;
+        check_slippage(tokens_received, min_tokens_out)?;
+        check_price_impact(
+            &ctx.accounts.launch_config,
+            reserves.sol,
+            reserves.token,
+            sol_amount,
+            tokens_received,
+        )?;
+
+        // Execute trade on the launch's venue
+        pool.swap(
+            &dex::SwapAccounts {
+                token_program: &ctx.accounts.token_program.to_account_info(),
+                owner: &ctx.accounts.trader.to_account_info(),
+                token_account: &ctx.accounts.trader_token_account.to_account_info(),
+                sol_account: trader_sol_account,
+            },
+            SwapDirection::SolToToken,
+            sol_amount,
+            venue_minimum_out(&ctx.accounts.launch_config, tokens_received, min_tokens_out)?,
+            &[],
+        )?;
+
+        emit!(TradeExecuted {
+            trader: ctx.accounts.trader.key(),
+            is_buy: true,
+            sol_amount,
+            token_amount: tokens_received,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    pub fn process_monitored_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, Trade<'info>>,
+        sol_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.manager.update_bot_capture(
+            ctx.accounts.trader.key(),
+            sol_amount,
+        )?;
+
+        let pool = dex::load(ctx.accounts, ctx.remaining_accounts)?;
+        let manager_sol_account = load_sol_account(
+            pool.as_ref(),
+            ctx.remaining_accounts,
+            &ctx.accounts.manager.key(),
+            &ctx.accounts.treasury,
+        )?;
+
+        let reserves = pool.reserves()?;
+        let tokens_out = pricing::calculate_tokens_from_sol(
+            &reserves,
+            sol_amount,
+            pool.fee_bps(&ctx.accounts.launch_config),
+        )?;
+
+        // Transfer SOL from buyer
+        let transfer_ix = system_instruction::transfer(
+            &ctx.accounts.trader.key(),
+            &ctx.accounts.treasury.key(),
+            sol_amount,
+        );
+
+        invoke(
+            &transfer_ix,
+            &[
+                ctx.accounts.trader.to_account_info(),
+                ctx.accounts.treasury.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+
+        record_monitored_purchase(
+            ctx.accounts,
+            *ctx.bumps.get("monitored_purchase").unwrap(),
+            sol_amount,
+        )?;
+        record_flagged_wallet(
+            ctx.accounts,
+            *ctx.bumps.get("flagged_wallet").unwrap(),
+            sol_amount,
+        )?;
+
+        // Get manager signer seeds
+        let seeds = [
+            b"abc_manager".as_ref(),
+            ctx.accounts.manager.mint.as_ref(),
+            &[ctx.accounts.manager.bump]
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        // Counter-trade on the same venue out of the reserve; it only has to
+        // land, so no minimum out
+        require!(
+            tokens_out <= ctx.accounts.manager.spendable_reserve(&ctx.accounts.launch_config),
+            ErrorCode::InsufficientReserve
+        );
+        pool.swap(
+            &dex::SwapAccounts {
+                token_program: &ctx.accounts.token_program.to_account_info(),
+                owner: &ctx.accounts.manager.to_account_info(),
+                token_account: &ctx.accounts.token_vault.to_account_info(),
+                sol_account: manager_sol_account,
+            },
+            SwapDirection::TokenToSol,
+            tokens_out,
+            0,
+            signer_seeds,
+        )?;
+        drop(pool);
+        ctx.accounts
+            .manager
+            .debit_reserve(tokens_out, &ctx.accounts.launch_config)?;
+
+        emit!(BotPurchaseHandled {
+            bot_address: ctx.accounts.trader.key(),
+            tokens_purchased: tokens_out,
+            sol_captured: sol_amount,
+            tokens_sold: tokens_out,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    pub fn process_sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, Trade<'info>>,
+        token_amount: u64,
+        min_sol_out: u64,
+    ) -> Result<()> {
+        let pool = dex::load(ctx.accounts, ctx.remaining_accounts)?;
+        let trader_sol_account = load_sol_account(
+            pool.as_ref(),
+            ctx.remaining_accounts,
+            &ctx.accounts.trader.key(),
+            ctx.accounts.trader.as_ref(),
+        )?;
+
+        let reserves = pool.reserves()?;
+        let sol_out = pricing::calculate_sol_from_tokens(
+            &reserves,
+            token_amount,
+            pool.fee_bps(&ctx.accounts.launch_config),
+        )?;
+        validate_trade_amount(&ctx.accounts.launch_config, sol_out)?;
+        check_slippage(sol_out, min_sol_out)?;
+        check_price_impact(
+            &ctx.accounts.launch_config,
+            reserves.token,
+            reserves.sol,
+            token_amount,
+            sol_out,
+        )?;
+
+        // Execute sell on the launch's venue
+        pool.swap(
+            &dex::SwapAccounts {
+                token_program: &ctx.accounts.token_program.to_account_info(),
+                owner: &ctx.accounts.trader.to_account_info(),
+                token_account: &ctx.accounts.trader_token_account.to_account_info(),
+                sol_account: trader_sol_account,
+            },
+            SwapDirection::TokenToSol,
+            token_amount,
+            venue_minimum_out(&ctx.accounts.launch_config, sol_out, min_sol_out)?,
+            &[],
+        )?;
+
+        emit!(TradeExecuted {
+            trader: ctx.accounts.trader.key(),
+            is_buy: false,
+            sol_amount: sol_out,
+            token_amount,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    // Adds `sol_amount` to the buyer's MonitoredPurchase, creating it on the
+    // first flagged buy so regular trades never pay for the account
+    fn record_monitored_purchase(accounts: &Trade, bump: u8, sol_amount: u64) -> Result<()> {
+        let info = accounts.monitored_purchase.to_account_info();
+        let manager = accounts.manager.key();
+        let buyer = accounts.trader.key();
+
+        let mut purchase = load_or_create::<MonitoredPurchase>(
+            &accounts.trader.to_account_info(),
+            &info,
+            &accounts.system_program.to_account_info(),
+            MonitoredPurchase::LEN,
+            &[b"monitored_purchase", manager.as_ref(), buyer.as_ref(), &[bump]],
+        )?
+        .unwrap_or(MonitoredPurchase {
+            manager,
+            buyer,
+            bump,
+            ..Default::default()
+        });
+
+        purchase.sol_amount = purchase
+            .sol_amount
+            .checked_add(sol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        purchase.last_slot = Clock::get()?.slot;
+
+        let mut data = info.try_borrow_mut_data()?;
+        purchase.try_serialize(&mut &mut data[..])
+    }
+
+    fn record_flagged_wallet(accounts: &Trade, bump: u8, sol_amount: u64) -> Result<()> {
+        let info = accounts.flagged_wallet.to_account_info();
+        let manager = accounts.manager.key();
+        let wallet = accounts.trader.key();
+        let slot = Clock::get()?.slot;
+
+        let mut flagged = load_or_create::<FlaggedWallet>(
+            &accounts.trader.to_account_info(),
+            &info,
+            &accounts.system_program.to_account_info(),
+            FlaggedWallet::LEN,
+            &[b"flagged_wallet", manager.as_ref(), wallet.as_ref(), &[bump]],
+        )?
+        .unwrap_or(FlaggedWallet {
+            manager,
+            wallet,
+            first_slot: slot,
+            bump,
+            ..Default::default()
+        });
+
+        flagged.record(sol_amount, slot)?;
+
+        let mut data = info.try_borrow_mut_data()?;
+        flagged.try_serialize(&mut &mut data[..])
+    }
+
+    /// Sends `amount` of the treasury's free SOL to `destination` and
+    /// records it. On the built-in pool `pool_accounts` starts with the pool,
+    /// whose reserve stays locked along with SOL owed to buyers and rent.
+    #[allow(clippy::too_many_arguments)]
+    pub fn withdraw_treasury<'info>(
+        manager: &mut ABCManager,
+        config: &LaunchConfig,
+        treasury: &AccountInfo<'info>,
+        treasury_bump: u8,
+        destination: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        pool_accounts: &[AccountInfo<'info>],
+        amount: u64,
+    ) -> Result<()> {
+        let pool_sol = match config.dex {
+            DexKind::ConstantProduct => {
+                let pool = pool_accounts.first().ok_or(ErrorCode::MissingPoolAccounts)?;
+                native::pool_sol_reserve(pool, &manager.mint)?
+            }
+            DexKind::RaydiumAmmV4 | DexKind::Whirlpool => 0,
+        };
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+        let locked = manager
+            .committed_treasury_sol()?
+            .checked_add(pool_sol)
+            .and_then(|sol| sol.checked_add(rent_exempt_minimum))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let available = treasury.lamports().saturating_sub(locked);
+        if amount > available {
+            msg!("Withdrawal of {} exceeds the {} lamports free", amount, available);
+            return err!(ErrorCode::InsufficientTreasuryBalance);
+        }
+
+        invoke_signed(
+            &system_instruction::transfer(treasury.key, destination.key, amount),
+            &[treasury.clone(), destination.clone(), system_program.clone()],
+            &[&[b"treasury", manager.mint.as_ref(), &[treasury_bump]]],
+        )?;
+
+        manager.total_withdrawn = manager
+            .total_withdrawn
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Moves `amount` of vested, unreleased reserve to `destination`, a
+    /// token account for the launch's mint.
+    pub fn release_reserve<'info>(
+        manager: &mut Account<'info, ABCManager>,
+        config: &LaunchConfig,
+        token_vault: &Account<'info, TokenAccount>,
+        destination: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        let destination_account = Account::<TokenAccount>::try_from(destination)?;
+        require!(
+            destination_account.mint == manager.mint,
+            ErrorCode::InvalidWithdrawalDestination
+        );
+
+        let vesting = &config.reserve_vesting;
+        let vested = vesting.vested_at(vesting.now(&Clock::get()?));
+        let released = manager
+            .reserve_released
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if released > vested {
+            msg!(
+                "Release of {} exceeds the {} vested and unreleased",
+                amount,
+                vested.saturating_sub(manager.reserve_released)
+            );
+            return err!(ErrorCode::ReserveNotVested);
+        }
+        manager.reserve_released = released;
+        manager.reconcile_reserve(token_vault.amount);
+        manager.debit_reserve(amount, config)?;
+
+        let seeds = [b"abc_manager".as_ref(), manager.mint.as_ref(), &[manager.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                Transfer {
+                    from: token_vault.to_account_info(),
+                    to: destination.clone(),
+                    authority: manager.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn settle_bids<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleAuction<'info>>,
+        slot: u64,
+    ) -> Result<()> {
+        let config = &ctx.accounts.launch_config;
+        let auction = &ctx.accounts.auction;
+        let mint = ctx.accounts.manager.mint;
+        let manager_seeds = [b"abc_manager".as_ref(), mint.as_ref(), &[ctx.accounts.manager.bump]];
+        let treasury_seeds = [
+            b"treasury".as_ref(),
+            mint.as_ref(),
+            &[*ctx.bumps.get("treasury").unwrap()],
+        ];
+
+        require!(
+            ctx.remaining_accounts.len() % 3 == 0,
+            ErrorCode::InvalidBidAccount
+        );
+        ctx.accounts
+            .manager
+            .reconcile_reserve(ctx.accounts.token_vault.amount);
+        let mut settled_sol = 0u64;
+        for accounts in ctx.remaining_accounts.chunks(3) {
+            let (bid_info, bidder, bidder_token_info) = (&accounts[0], &accounts[1], &accounts[2]);
+
+            let bid = Account::<Bid>::try_from(bid_info)?;
+            let bidder_token_account = Account::<TokenAccount>::try_from(bidder_token_info)?;
+            require!(
+                bid.manager == ctx.accounts.manager.key()
+                    && bid.bidder == bidder.key()
+                    && bidder_token_account.mint == mint
+                    && bidder_token_account.owner == bidder.key(),
+                ErrorCode::InvalidBidAccount
+            );
+
+            let (token_amount, refund) = auction.fill(config, &bid)?;
+            ctx.accounts.manager.debit_reserve(token_amount, config)?;
+            if token_amount > 0 {
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.token_vault.to_account_info(),
+                            to: bidder_token_info.clone(),
+                            authority: ctx.accounts.manager.to_account_info(),
+                        },
+                        &[&manager_seeds[..]],
+                    ),
+                    token_amount,
+                )?;
+            }
+            if refund > 0 {
+                invoke_signed(
+                    &system_instruction::transfer(
+                        &ctx.accounts.treasury.key(),
+                        &bidder.key(),
+                        refund,
+                    ),
+                    &[
+                        ctx.accounts.treasury.to_account_info(),
+                        bidder.clone(),
+                        ctx.accounts.system_program.to_account_info(),
+                    ],
+                    &[&treasury_seeds[..]],
+                )?;
+            }
+
+            settled_sol = settled_sol
+                .checked_add(bid.sol_amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            emit!(BidSettled {
+                mint,
+                bidder: bid.bidder,
+                token_amount,
+                sol_paid: bid.sol_amount - refund,
+                sol_refunded: refund,
+                slot,
+            });
+
+            // Rent goes back to the bidder and the bid can't be settled twice
+            bid.close(bidder.clone())?;
+        }
+
+        let manager = &mut ctx.accounts.manager;
+        manager.escrowed_sol = manager.escrowed_sol.saturating_sub(settled_sol);
+        Ok(())
+    }
+
+    // Stores the slot-driven phase so it's visible on-chain, announcing changes
+    pub fn sync_phase(
+        manager: &mut ABCManager,
+        config: &LaunchConfig,
+        slot: u64,
+    ) -> Result<LaunchPhase> {
+        let phase = manager.current_phase(config, slot);
+        if phase != manager.phase {
+            let from = manager.set_phase(phase)?;
+            emit!(LaunchPhaseChanged {
+                mint: manager.mint,
+                from,
+                to: phase,
+                slot,
+            });
+        }
+        Ok(phase)
+    }
+
+    // Moves the LP minted by pool creation out of the authority's account into
+    // `lp_vault` and opens the launch's LiquidityLock over it. `lock_accounts`
+    // are the lp_lock and lp_vault PDAs, neither created yet since the LP mint
+    // only exists once the pool does.
+    #[allow(clippy::too_many_arguments)]
+    pub fn lock_initial_liquidity<'info>(
+        manager: &Account<'info, ABCManager>,
+        config: &LaunchConfig,
+        authority: &AccountInfo<'info>,
+        lp_mint: &AccountInfo<'info>,
+        lp_source: &AccountInfo<'info>,
+        lock_accounts: &[AccountInfo<'info>],
+        token_program: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        rent: &AccountInfo<'info>,
+    ) -> Result<()> {
+        let (lock_info, vault_info) = match lock_accounts {
+            [lock, vault, ..] => (lock, vault),
+            _ => return err!(ErrorCode::MissingPoolAccounts),
+        };
+        let mint = manager.mint;
+        let (lock_address, lock_bump) =
+            Pubkey::find_program_address(&[b"lp_lock", mint.as_ref()], &crate::ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[b"lp_vault", mint.as_ref()], &crate::ID);
+        require!(
+            lock_info.key() == lock_address && vault_info.key() == vault_address,
+            ErrorCode::InvalidLiquidityLock
+        );
+
+        let source = Account::<TokenAccount>::try_from(lp_source)?;
+        require!(
+            source.mint == lp_mint.key() && source.owner == authority.key(),
+            ErrorCode::InvalidLpMint
+        );
+        let amount = source.amount;
+        require!(amount > 0, ErrorCode::InsufficientLiquidity);
+
+        create_pda_account(
+            authority,
+            vault_info,
+            system_program,
+            spl_token::state::Account::LEN,
+            &spl_token::ID,
+            &[b"lp_vault", mint.as_ref(), &[vault_bump]],
+        )?;
+        token::initialize_account(CpiContext::new(
+            token_program.clone(),
+            token::InitializeAccount {
+                account: vault_info.clone(),
+                mint: lp_mint.clone(),
+                authority: manager.to_account_info(),
+                rent: rent.clone(),
+            },
+        ))?;
+        token::transfer(
+            CpiContext::new(
+                token_program.clone(),
+                Transfer {
+                    from: lp_source.clone(),
+                    to: vault_info.clone(),
+                    authority: authority.clone(),
+                },
+            ),
+            amount,
+        )?;
+
+        create_pda_account(
+            authority,
+            lock_info,
+            system_program,
+            LiquidityLock::LEN,
+            &crate::ID,
+            &[b"lp_lock", mint.as_ref(), &[lock_bump]],
+        )?;
+        let mut lp_lock = LiquidityLock::default();
+        lp_lock.initialize(
+            manager.key(),
+            lp_mint.key(),
+            amount,
+            config.lp_lock_clock,
+            config.lp_lock_until,
+            lock_bump,
+        );
+        lp_lock.try_serialize(&mut &mut lock_info.try_borrow_mut_data()?[..])?;
+
+        emit!(LiquidityLocked {
+            mint,
+            lp_mint: lp_lock.lp_mint,
+            amount,
+            lock_until: lp_lock.lock_until,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    // Pays out everything in `lp_vault` once the lock has run out
+    pub fn unlock_liquidity<'info>(
+        manager: &Account<'info, ABCManager>,
+        lp_lock: &mut LiquidityLock,
+        lp_vault: &AccountInfo<'info>,
+        destination: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> Result<u64> {
+        require!(
+            lp_lock.is_expired(&Clock::get()?),
+            ErrorCode::LiquidityStillLocked
+        );
+        let destination_account = Account::<TokenAccount>::try_from(destination)?;
+        require!(
+            destination_account.mint == lp_lock.lp_mint,
+            ErrorCode::InvalidLpMint
+        );
+
+        let amount = Account::<TokenAccount>::try_from(lp_vault)?.amount;
+        let seeds = [b"abc_manager".as_ref(), manager.mint.as_ref(), &[manager.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                Transfer {
+                    from: lp_vault.clone(),
+                    to: destination.clone(),
+                    authority: manager.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+
+        lp_lock.amount = 0;
+        Ok(amount)
+    }
+
+    pub fn close_launch(
+        manager: &mut ABCManager,
+        config: &LaunchConfig,
+        slot: u64,
+    ) -> Result<()> {
+        // Catch up on timed transitions first so `from` is the real phase
+        sync_phase(manager, config, slot)?;
+        let from = manager.set_phase(LaunchPhase::Closed)?;
+
+        emit!(LaunchPhaseChanged {
+            mint: manager.mint,
+            from,
+            to: LaunchPhase::Closed,
+            slot,
+        });
+
+        Ok(())
+    }
+
+    // Counts a fair-launch buy against the trader's cap for the current window
+    pub fn record_capped_purchase(
+        accounts: &Trade,
+        bump: u8,
+        sol_amount: u64,
+        window_start: u64,
+    ) -> Result<()> {
+        let info = accounts.wallet_purchases.to_account_info();
+        let manager = accounts.manager.key();
+        let wallet = accounts.trader.key();
+
+        let mut purchases = load_or_create::<WalletPurchases>(
+            &accounts.trader.to_account_info(),
+            &info,
+            &accounts.system_program.to_account_info(),
+            WalletPurchases::LEN,
+            &[b"wallet_purchases", manager.as_ref(), wallet.as_ref(), &[bump]],
+        )?
+        .unwrap_or(WalletPurchases {
+            manager,
+            wallet,
+            bump,
+            ..Default::default()
+        });
+
+        purchases.record(sol_amount, window_start, accounts.launch_config.wallet_cap_sol)?;
+
+        let mut data = info.try_borrow_mut_data()?;
+        purchases.try_serialize(&mut &mut data[..])
+    }
+
+    pub fn record_presale_purchase(
+        accounts: &PresaleBuy,
+        bump: u8,
+        sol_amount: u64,
+        allocation: u64,
+    ) -> Result<()> {
+        let info = accounts.presale_purchase.to_account_info();
+        let manager = accounts.manager.key();
+        let buyer = accounts.buyer.key();
+
+        let mut purchase = load_or_create::<PresalePurchase>(
+            &accounts.buyer.to_account_info(),
+            &info,
+            &accounts.system_program.to_account_info(),
+            PresalePurchase::LEN,
+            &[b"presale_purchase", manager.as_ref(), buyer.as_ref(), &[bump]],
+        )?
+        .unwrap_or(PresalePurchase {
+            manager,
+            buyer,
+            bump,
+            ..Default::default()
+        });
+
+        purchase.record(sol_amount, allocation)?;
+
+        let mut data = info.try_borrow_mut_data()?;
+        purchase.try_serialize(&mut &mut data[..])
+    }
+
+    // Whether the trader's FlaggedWallet exists and was cleared by the authority
+    pub fn is_cleared(flagged_wallet: &UncheckedAccount) -> Result<bool> {
+        if flagged_wallet.data_is_empty() {
+            return Ok(false);
+        }
+
+        let flagged = FlaggedWallet::try_deserialize(&mut &flagged_wallet.try_borrow_data()?[..])?;
+        Ok(flagged.cleared)
+    }
+
+    // Existing per-trader record, or None after allocating a fresh account
+    // for the caller to fill in
+    fn load_or_create<'info, T: AccountDeserialize>(
+        payer: &AccountInfo<'info>,
+        info: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        space: usize,
+        seeds: &[&[u8]],
+    ) -> Result<Option<T>> {
+        if !info.data_is_empty() {
+            // Only this program can allocate the PDA, so existing data is ours
+            return T::try_deserialize(&mut &info.try_borrow_data()?[..]).map(Some);
+        }
+
+        create_pda_account(payer, info, system_program, space, &crate::ID, seeds)?;
+        Ok(None)
+    }
+
+    // Allocates a PDA owned by `owner`, topping up instead of creating when
+    // the address has already been sent lamports
+    fn create_pda_account<'info>(
+        payer: &AccountInfo<'info>,
+        target: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        space: usize,
+        owner: &Pubkey,
+        seeds: &[&[u8]],
+    ) -> Result<()> {
+        let required_lamports = Rent::get()?.minimum_balance(space);
+
+        if target.lamports() == 0 {
+            return invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    target.key,
+                    required_lamports,
+                    space as u64,
+                    owner,
+                ),
+                &[payer.clone(), target.clone(), system_program.clone()],
+                &[seeds],
+            )
+            .map_err(Into::into);
+        }
+
+        let top_up = required_lamports.saturating_sub(target.lamports());
+        if top_up > 0 {
+            invoke(
+                &system_instruction::transfer(payer.key, target.key, top_up),
+                &[payer.clone(), target.clone(), system_program.clone()],
+            )?;
+        }
+        invoke_signed(
+            &system_instruction::allocate(target.key, space as u64),
+            &[target.clone(), system_program.clone()],
+            &[seeds],
+        )?;
+        invoke_signed(
+            &system_instruction::assign(target.key, owner),
+            &[target.clone(), system_program.clone()],
+            &[seeds],
+        )
+        .map_err(Into::into)
+    }
+
+    pub fn process_native_swap(
+        ctx: Context<NativeSwap>,
+        direction: SwapDirection,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        let config = &ctx.accounts.launch_config;
+        let reserves = ctx.accounts.native_pool.reserves().require_liquidity()?;
+
+        let (reserve_in, reserve_out, quoted_out) = match direction {
+            SwapDirection::SolToToken => (
+                reserves.sol,
+                reserves.token,
+                pricing::calculate_tokens_from_sol(&reserves, amount_in, config.pool_fee_bps)?,
+            ),
+            SwapDirection::TokenToSol => (
+                reserves.token,
+                reserves.sol,
+                pricing::calculate_sol_from_tokens(&reserves, amount_in, config.pool_fee_bps)?,
+            ),
+        };
+        let (sol_amount, token_amount) = match direction {
+            SwapDirection::SolToToken => (amount_in, quoted_out),
+            SwapDirection::TokenToSol => (quoted_out, amount_in),
+        };
+
+        validate_trade_amount(config, sol_amount)?;
+        check_slippage(quoted_out, minimum_amount_out)?;
+        check_price_impact(config, reserve_in, reserve_out, amount_in, quoted_out)?;
+        let venue_minimum = venue_minimum_out(config, quoted_out, minimum_amount_out)?;
+        let fee_bps = config.pool_fee_bps;
+
+        let trader = ctx.accounts.trader.to_account_info();
+        let pool = native::PoolAccounts {
+            pool: &ctx.accounts.native_pool.to_account_info(),
+            pool_vault: &ctx.accounts.pool_vault.to_account_info(),
+            treasury: &ctx.accounts.treasury.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+        };
+        native::swap(
+            &mut ctx.accounts.native_pool,
+            &pool,
+            &dex::SwapAccounts {
+                token_program: &ctx.accounts.token_program.to_account_info(),
+                owner: &trader,
+                token_account: &ctx.accounts.trader_token_account.to_account_info(),
+                sol_account: &trader,
+            },
+            direction,
+            amount_in,
+            venue_minimum,
+            fee_bps,
+            &[],
+        )?;
+
+        emit!(TradeExecuted {
+            trader: trader.key(),
+            is_buy: direction == SwapDirection::SolToToken,
+            sol_amount,
+            token_amount,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    // Wrapped-SOL account following the pool accounts, owned by `owner`;
+    // `lamports_account` when the pool takes plain SOL
+    fn load_sol_account<'a, 'info>(
+        pool: &dyn dex::DexAdapter<'info>,
+        remaining_accounts: &'a [AccountInfo<'info>],
+        owner: &Pubkey,
+        lamports_account: &'a AccountInfo<'info>,
+    ) -> Result<&'a AccountInfo<'info>> {
+        if !pool.wraps_sol() {
+            return Ok(lamports_account);
+        }
+
+        let sol_account = remaining_accounts
+            .get(pool.accounts_len())
+            .ok_or(ErrorCode::MissingPoolAccounts)?;
+
+        let token_account: Account<TokenAccount> = Account::try_from(sol_account)?;
+        require!(
+            token_account.mint == native_mint::id() && token_account.owner == *owner,
+            ErrorCode::InvalidSolAccount
+        );
+
+        Ok(sol_account)
+    }
+
+    fn check_slippage(quoted_out: u64, min_out: u64) -> Result<()> {
+        if quoted_out < min_out {
+            msg!("Quoted output {} below requested minimum {}", quoted_out, min_out);
+            return err!(ErrorCode::SlippageExceeded);
+        }
+        Ok(())
+    }
+
+    fn check_price_impact(
+        config: &LaunchConfig,
+        reserve_in: u64,
+        reserve_out: u64,
+        amount_in: u64,
+        amount_out: u64,
+    ) -> Result<()> {
+        let impact_bps =
+            pricing::calculate_price_impact_bps(reserve_in, reserve_out, amount_in, amount_out)?;
+
+        if impact_bps > config.max_price_impact_bps {
+            msg!(
+                "Price impact {} bps exceeds limit of {} bps",
+                impact_bps,
+                config.max_price_impact_bps
+            );
+            return err!(ErrorCode::PriceImpactTooHigh);
+        }
+        Ok(())
+    }
+
+    // Minimum handed to the venue: the caller's floor, but never looser than
+    // the launch's own slippage tolerance around the quote
+    fn venue_minimum_out(config: &LaunchConfig, quoted_out: u64, min_out: u64) -> Result<u64> {
+        let tolerated = pricing::apply_slippage_tolerance(quoted_out, config.slippage_tolerance_bps)?;
+        Ok(tolerated.max(min_out))
+    }
+
+    fn validate_trade_amount(config: &LaunchConfig, amount: u64) -> Result<()> {
+        require!(amount >= config.min_trade_sol, ErrorCode::TradeTooSmall);
+        require!(amount <= config.max_trade_sol, ErrorCode::TradeTooLarge);
+        Ok(())
+    }
+}
+
+// Separate module for pricing calculations
+mod pricing {
+    use super::*;
+
+    /// Live balances of the two sides of the pool.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct PoolReserves {
+        pub sol: u64,
+        pub token: u64,
+    }
+
+    impl PoolReserves {
+        /// Reads the token vault balance and the SOL vault's lamports above
+        /// its rent-exempt minimum.
+        pub fn load(token_vault: &TokenAccount, sol_vault: &AccountInfo) -> Result<Self> {
+            let rent_exempt = Rent::get()?.minimum_balance(sol_vault.data_len());
+            let reserves = Self {
+                sol: sol_vault.lamports().saturating_sub(rent_exempt),
+                token: token_vault.amount,
+            };
+
+            reserves.require_liquidity()
+        }
+
+        pub fn require_liquidity(self) -> Result<Self> {
+            require!(
+                self.sol > 0 && self.token > 0,
+                ErrorCode::InsufficientLiquidity
+            );
+            Ok(self)
+        }
+    }
+
+    pub fn calculate_tokens_from_sol(
+        reserves: &PoolReserves,
+        sol_amount: u64,
+        fee_bps: u64,
+    ) -> Result<u64> {
+        calculate_swap_output(
+            sol_amount,
+            reserves.sol as u128,
+            reserves.token as u128,
+            fee_bps,
+        )
+    }
+
+    pub fn calculate_sol_from_tokens(
+        reserves: &PoolReserves,
+        token_amount: u64,
+        fee_bps: u64,
+    ) -> Result<u64> {
+        calculate_swap_output(
+            token_amount,
+            reserves.token as u128,
+            reserves.sol as u128,
+            fee_bps,
+        )
+    }
+
+    /// Constant-product output for `amount_in`, with the pool fee taken from
+    /// the input side. Rounds down, in favour of the pool.
+    pub fn calculate_swap_output(
+        amount_in: u64,
+        reserve_in: u128,
+        reserve_out: u128,
+        fee_bps: u64,
+    ) -> Result<u64> {
+        let amount_in_after_fee = (amount_in as u128)
+            .checked_mul(BPS_DENOMINATOR.saturating_sub(fee_bps) as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(BPS_DENOMINATOR as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let numerator = reserve_out
+            .checked_mul(amount_in_after_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let denominator = reserve_in
+            .checked_add(amount_in_after_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let amount_out = numerator
+            .checked_div(denominator)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        u64::try_from(amount_out).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Move in the spot price of the output asset (reserve_in / reserve_out)
+    /// caused by swapping `amount_in` for `amount_out`, in basis points.
+    pub fn calculate_price_impact_bps(
+        reserve_in: u64,
+        reserve_out: u64,
+        amount_in: u64,
+        amount_out: u64,
+    ) -> Result<u64> {
+        let new_reserve_in = (reserve_in as u128)
+            .checked_add(amount_in as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let new_reserve_out = (reserve_out as u128)
+            .checked_sub(amount_out as u128)
+            .filter(|reserve| *reserve > 0)
+            .ok_or(ErrorCode::InsufficientLiquidity)?;
+
+        // (new_in / new_out) / (in / out), scaled to bps
+        let numerator = new_reserve_in
+            .checked_mul(reserve_out as u128)
+            .and_then(|n| n.checked_mul(BPS_DENOMINATOR as u128))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let denominator = new_reserve_out
+            .checked_mul(reserve_in as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let price_ratio_bps = numerator
+            .checked_div(denominator)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let impact_bps = price_ratio_bps.saturating_sub(BPS_DENOMINATOR as u128);
+        Ok(u64::try_from(impact_bps).unwrap_or(u64::MAX))
+    }
+
+    /// Tokens and LP shares for depositing `sol_amount`. The first deposit
+    /// takes `max_token_amount` as given and sets the price; later ones must
+    /// match the pool ratio, rounded up in favour of the pool.
+    pub fn calculate_liquidity_deposit(
+        reserves: &PoolReserves,
+        lp_supply: u64,
+        sol_amount: u64,
+        max_token_amount: u64,
+    ) -> Result<(u64, u64)> {
+        if lp_supply == 0 {
+            let shares = integer_sqrt(sol_amount as u128 * max_token_amount as u128);
+            return Ok((max_token_amount, shares as u64));
+        }
+
+        let reserves = reserves.require_liquidity()?;
+        let token_amount = mul_div(sol_amount, reserves.token, reserves.sol, true)?;
+        let shares = mul_div(sol_amount, lp_supply, reserves.sol, false)?;
+        Ok((token_amount, shares))
+    }
+
+    /// SOL and tokens returned for burning `shares`, rounded down.
+    pub fn calculate_liquidity_withdrawal(
+        reserves: &PoolReserves,
+        lp_supply: u64,
+        shares: u64,
+    ) -> Result<(u64, u64)> {
+        require!(
+            shares > 0 && shares <= lp_supply,
+            ErrorCode::InsufficientLiquidity
+        );
+
+        Ok((
+            mul_div(shares, reserves.sol, lp_supply, false)?,
+            mul_div(shares, reserves.token, lp_supply, false)?,
+        ))
+    }
+
+    fn mul_div(value: u64, numerator: u64, denominator: u64, round_up: bool) -> Result<u64> {
+        require!(denominator > 0, ErrorCode::MathOverflow);
+
+        let product = value as u128 * numerator as u128;
+        let quotient = if round_up {
+            product
+                .checked_add(denominator as u128 - 1)
+                .ok_or(ErrorCode::MathOverflow)?
+                / denominator as u128
+        } else {
+            product / denominator as u128
+        };
+
+        u64::try_from(quotient).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    pub fn integer_sqrt(value: u128) -> u128 {
+        if value < 2 {
+            return value;
+        }
+
+        // Newton's method, starting above the root
+        let mut x = value;
+        let mut y = value / 2 + (value & 1);
+        while y < x {
+            x = y;
+            y = (x + value / x) / 2;
+        }
+        x
+    }
+
+    /// Tokens bought at the fixed presale price. Rounds down.
+    pub fn calculate_presale_tokens(sol_amount: u64, tokens_per_sol: u64) -> Result<u64> {
+        let tokens = (sol_amount as u128)
+            .checked_mul(tokens_per_sol as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL as u128;
+
+        u64::try_from(tokens).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Tokens `sol_amount` buys at `price` lamports per PRICE_UNIT. Rounds
+    /// down.
+    pub fn tokens_at_price(sol_amount: u64, price: u64) -> Result<u64> {
+        mul_div(sol_amount, PRICE_UNIT, price, false)
+    }
+
+    /// SOL owed for `token_amount` at `price` lamports per PRICE_UNIT.
+    /// Rounds up.
+    pub fn cost_at_price(token_amount: u64, price: u64) -> Result<u64> {
+        mul_div(token_amount, price, PRICE_UNIT, true)
+    }
+
+    /// Walks `curve` from `tokens_sold`, spending up to `sol_amount`.
+    /// Returns the tokens bought and the SOL they cost; a step is only
+    /// partly bought once the SOL left can't pay for the rest of it. Stops
+    /// at the end of the curve.
+    pub fn calculate_curve_buy(
+        curve: &BondingCurve,
+        tokens_sold: u64,
+        sol_amount: u64,
+    ) -> Result<(u64, u64)> {
+        let mut step = tokens_sold / curve.step_tokens;
+        let mut price = curve.step_price(step)?;
+        let mut sold = tokens_sold;
+        let mut sol_left = sol_amount;
+
+        while step < MAX_CURVE_STEPS {
+            let step_end = (step + 1)
+                .checked_mul(curve.step_tokens)
+                .ok_or(ErrorCode::MathOverflow)?;
+            let step_left = step_end - sold;
+            let step_cost = cost_at_price(step_left, price)?;
+            if step_cost > sol_left {
+                let tokens = tokens_at_price(sol_left, price)?;
+                sold += tokens;
+                sol_left -= cost_at_price(tokens, price)?;
+                break;
+            }
+
+            sold += step_left;
+            sol_left -= step_cost;
+            step += 1;
+            price = curve.next_price(price)?;
+        }
+
+        Ok((sold - tokens_sold, sol_amount - sol_left))
+    }
+
+    /// Lowest acceptable output for `quoted_out` under `tolerance_bps`.
+    pub fn apply_slippage_tolerance(quoted_out: u64, tolerance_bps: u64) -> Result<u64> {
+        let minimum = (quoted_out as u128)
+            .checked_mul(BPS_DENOMINATOR.saturating_sub(tolerance_bps) as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(BPS_DENOMINATOR as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(minimum as u64)
+    }
+}
+
+// Presale allowlist proofs. Leaves are keccak(wallet || allocation LE) and
+// pairs are hashed in sorted order, so proofs carry no left/right flags
+pub mod merkle {
+    use super::*;
+    use anchor_lang::solana_program::keccak;
+
+    pub fn leaf(wallet: &Pubkey, allocation: u64) -> [u8; 32] {
+        keccak::hashv(&[wallet.as_ref(), &allocation.to_le_bytes()]).0
+    }
+
+    pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+        let (first, second) = if a <= b { (a, b) } else { (b, a) };
+        keccak::hashv(&[first, second]).0
+    }
+
+    pub fn verify(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+        proof.iter().fold(leaf, |node, sibling| hash_pair(&node, sibling)) == root
+    }
+}
+
+// Batch-auction price ladder. Tick `t` is `auction_floor_price + t *
+// auction_tick_size` lamports per PRICE_UNIT token base units
+pub mod auction {
+    use super::*;
+
+    pub fn price(config: &LaunchConfig, tick: u8) -> u64 {
+        config
+            .auction_floor_price
+            .saturating_add(config.auction_tick_size.saturating_mul(tick as u64))
+    }
+}
+
+// Venue-agnostic swap interface; `LaunchConfig::dex` picks the implementation
+pub mod dex {
+    use super::*;
+
+    /// Swapper's side of a trade. `sol_account` is wrapped SOL owned by
+    /// `owner`, or a system account for venues that don't wrap SOL.
+    pub struct SwapAccounts<'a, 'info> {
+        pub token_program: &'a AccountInfo<'info>,
+        pub owner: &'a AccountInfo<'info>,
+        pub token_account: &'a AccountInfo<'info>,
+        pub sol_account: &'a AccountInfo<'info>,
+    }
+
+    /// A pool `trade` can quote against and settle through.
+    pub trait DexAdapter<'info> {
+        /// Remaining accounts the pool takes; the swapper's wrapped-SOL
+        /// account comes right after them.
+        fn accounts_len(&self) -> usize;
+
+        /// Whether the SOL side is a wrapped-SOL token account rather than
+        /// plain lamports.
+        fn wraps_sol(&self) -> bool {
+            true
+        }
+
+        /// Reserves the constant-product quote is taken against.
+        fn reserves(&self) -> Result<pricing::PoolReserves>;
+
+        fn fee_bps(&self, config: &LaunchConfig) -> u64;
+
+        /// Exact-input swap, failing on the venue if less than
+        /// `minimum_amount_out` comes back.
+        fn swap(
+            &self,
+            accounts: &SwapAccounts<'_, 'info>,
+            direction: SwapDirection,
+            amount_in: u64,
+            minimum_amount_out: u64,
+            signer_seeds: &[&[&[u8]]],
+        ) -> Result<()>;
+    }
+
+    /// Loads the launch's pool from the front of `remaining_accounts`.
+    pub fn load<'a, 'info>(
+        accounts: &'a Trade<'info>,
+        remaining_accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Box<dyn DexAdapter<'info> + 'a>> {
+        let config = &accounts.launch_config;
+        let manager = &accounts.manager;
+
+        match config.dex {
+            DexKind::RaydiumAmmV4 => Ok(Box::new(raydium::SwapPool::load(
+                remaining_accounts,
+                config,
+                manager,
+            )?)),
+            DexKind::Whirlpool => Ok(Box::new(whirlpool::SwapPool::load(
+                remaining_accounts,
+                config,
+                manager,
+            )?)),
+            DexKind::ConstantProduct => Ok(Box::new(native::SwapPool::load(
+                remaining_accounts,
+                config,
+                manager,
+                &accounts.treasury,
+                accounts.system_program.as_ref(),
+            )?)),
+        }
+    }
+}
+
+// Raydium AMM v4 instruction layouts and CPI helpers
+pub mod raydium {
+    use super::*;
+    use anchor_lang::solana_program::sysvar;
+    use anchor_spl::token::spl_token;
+
+    pub const INITIALIZE2: u8 = 1;
+    pub const SWAP_BASE_IN: u8 = 9;
+    pub const SWAP_BASE_OUT: u8 = 11;
+
+    /// Seed of the AMM authority PDA: `[AUTHORITY_AMM, &[nonce]]`.
+    pub const AUTHORITY_AMM: &[u8] = b"amm authority";
+
+    pub const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum AmmInstruction {
+        Initialize2 {
+            nonce: u8,
+            open_time: u64,
+            init_pc_amount: u64,
+            init_coin_amount: u64,
+        },
+        SwapBaseIn {
+            amount_in: u64,
+            minimum_amount_out: u64,
+        },
+        SwapBaseOut {
+            max_amount_in: u64,
+            amount_out: u64,
+        },
+    }
+
+    impl AmmInstruction {
+        pub fn pack(&self) -> Vec<u8> {
+            match *self {
+                Self::Initialize2 {
+                    nonce,
+                    open_time,
+                    init_pc_amount,
+                    init_coin_amount,
+                } => {
+                    let mut data = Vec::with_capacity(26);
+                    data.push(INITIALIZE2);
+                    data.push(nonce);
+                    data.extend_from_slice(&open_time.to_le_bytes());
+                    data.extend_from_slice(&init_pc_amount.to_le_bytes());
+                    data.extend_from_slice(&init_coin_amount.to_le_bytes());
+                    data
+                }
+                Self::SwapBaseIn {
+                    amount_in,
+                    minimum_amount_out,
+                } => pack_swap(SWAP_BASE_IN, amount_in, minimum_amount_out),
+                Self::SwapBaseOut {
+                    max_amount_in,
+                    amount_out,
+                } => pack_swap(SWAP_BASE_OUT, max_amount_in, amount_out),
+            }
+        }
+
+        pub fn unpack(data: &[u8]) -> Result<Self> {
+            let (&tag, rest) = data
+                .split_first()
+                .ok_or(ProgramError::InvalidInstructionData)?;
+
+            match tag {
+                INITIALIZE2 if rest.len() >= 25 => Ok(Self::Initialize2 {
+                    nonce: rest[0],
+                    open_time: read_u64(&rest[1..])?,
+                    init_pc_amount: read_u64(&rest[9..])?,
+                    init_coin_amount: read_u64(&rest[17..])?,
+                }),
+                SWAP_BASE_IN => Ok(Self::SwapBaseIn {
+                    amount_in: read_u64(rest)?,
+                    minimum_amount_out: read_u64(rest.get(8..).unwrap_or_default())?,
+                }),
+                SWAP_BASE_OUT => Ok(Self::SwapBaseOut {
+                    max_amount_in: read_u64(rest)?,
+                    amount_out: read_u64(rest.get(8..).unwrap_or_default())?,
+                }),
+                _ => Err(ProgramError::InvalidInstructionData.into()),
+            }
+        }
+    }
+
+    fn pack_swap(tag: u8, first: u64, second: u64) -> Vec<u8> {
+        let mut data = Vec::with_capacity(17);
+        data.push(tag);
+        data.extend_from_slice(&first.to_le_bytes());
+        data.extend_from_slice(&second.to_le_bytes());
+        data
+    }
+
+    fn read_u64(data: &[u8]) -> Result<u64> {
+        let bytes = data
+            .get(..8)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// Pool-side accounts of an AMM v4 swap, in instruction order.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct SwapPoolKeys {
+        pub amm: Pubkey,
+        pub amm_authority: Pubkey,
+        pub amm_open_orders: Pubkey,
+        pub amm_target_orders: Pubkey,
+        pub pool_coin_vault: Pubkey,
+        pub pool_pc_vault: Pubkey,
+        pub market_program: Pubkey,
+        pub market: Pubkey,
+        pub market_bids: Pubkey,
+        pub market_asks: Pubkey,
+        pub market_event_queue: Pubkey,
+        pub market_coin_vault: Pubkey,
+        pub market_pc_vault: Pubkey,
+        pub market_vault_signer: Pubkey,
+    }
+
+    impl SwapPoolKeys {
+        pub const LEN: usize = 14;
+
+        fn from_accounts(accounts: &[AccountInfo]) -> Self {
+            Self {
+                amm: accounts[0].key(),
+                amm_authority: accounts[1].key(),
+                amm_open_orders: accounts[2].key(),
+                amm_target_orders: accounts[3].key(),
+                pool_coin_vault: accounts[4].key(),
+                pool_pc_vault: accounts[5].key(),
+                market_program: accounts[6].key(),
+                market: accounts[7].key(),
+                market_bids: accounts[8].key(),
+                market_asks: accounts[9].key(),
+                market_event_queue: accounts[10].key(),
+                market_coin_vault: accounts[11].key(),
+                market_pc_vault: accounts[12].key(),
+                market_vault_signer: accounts[13].key(),
+            }
+        }
+    }
+
+    /// Accounts of an AMM v4 `Initialize2`, minus the fixed program/sysvar ids.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct InitializeKeys {
+        pub amm: Pubkey,
+        pub amm_authority: Pubkey,
+        pub amm_open_orders: Pubkey,
+        pub lp_mint: Pubkey,
+        pub coin_mint: Pubkey,
+        pub pc_mint: Pubkey,
+        pub pool_coin_vault: Pubkey,
+        pub pool_pc_vault: Pubkey,
+        pub pool_target_orders: Pubkey,
+        pub amm_config: Pubkey,
+        pub create_fee_destination: Pubkey,
+        pub market_program: Pubkey,
+        pub market: Pubkey,
+        pub user_wallet: Pubkey,
+        pub user_token_coin: Pubkey,
+        pub user_token_pc: Pubkey,
+        pub user_token_lp: Pubkey,
+    }
+
+    pub fn swap_base_in(
+        program_id: &Pubkey,
+        pool: &SwapPoolKeys,
+        user_source: &Pubkey,
+        user_destination: &Pubkey,
+        user_owner: &Pubkey,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: *program_id,
+            accounts: swap_account_metas(pool, user_source, user_destination, user_owner),
+            data: AmmInstruction::SwapBaseIn {
+                amount_in,
+                minimum_amount_out,
+            }
+            .pack(),
+        }
+    }
+
+    pub fn swap_base_out(
+        program_id: &Pubkey,
+        pool: &SwapPoolKeys,
+        user_source: &Pubkey,
+        user_destination: &Pubkey,
+        user_owner: &Pubkey,
+        max_amount_in: u64,
+        amount_out: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: *program_id,
+            accounts: swap_account_metas(pool, user_source, user_destination, user_owner),
+            data: AmmInstruction::SwapBaseOut {
+                max_amount_in,
+                amount_out,
+            }
+            .pack(),
+        }
+    }
+
+    fn swap_account_metas(
+        pool: &SwapPoolKeys,
+        user_source: &Pubkey,
+        user_destination: &Pubkey,
+        user_owner: &Pubkey,
+    ) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(pool.amm, false),
+            AccountMeta::new_readonly(pool.amm_authority, false),
+            AccountMeta::new(pool.amm_open_orders, false),
+            AccountMeta::new(pool.amm_target_orders, false),
+            AccountMeta::new(pool.pool_coin_vault, false),
+            AccountMeta::new(pool.pool_pc_vault, false),
+            AccountMeta::new_readonly(pool.market_program, false),
+            AccountMeta::new(pool.market, false),
+            AccountMeta::new(pool.market_bids, false),
+            AccountMeta::new(pool.market_asks, false),
+            AccountMeta::new(pool.market_event_queue, false),
+            AccountMeta::new(pool.market_coin_vault, false),
+            AccountMeta::new(pool.market_pc_vault, false),
+            AccountMeta::new_readonly(pool.market_vault_signer, false),
+            AccountMeta::new(*user_source, false),
+            AccountMeta::new(*user_destination, false),
+            AccountMeta::new_readonly(*user_owner, true),
+        ]
+    }
+
+    pub fn initialize2(
+        program_id: &Pubkey,
+        keys: &InitializeKeys,
+        nonce: u8,
+        open_time: u64,
+        init_pc_amount: u64,
+        init_coin_amount: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(
+                    Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID).unwrap(),
+                    false,
+                ),
+                AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new(keys.amm, false),
+                AccountMeta::new_readonly(keys.amm_authority, false),
+                AccountMeta::new(keys.amm_open_orders, false),
+                AccountMeta::new(keys.lp_mint, false),
+                AccountMeta::new_readonly(keys.coin_mint, false),
+                AccountMeta::new_readonly(keys.pc_mint, false),
+                AccountMeta::new(keys.pool_coin_vault, false),
+                AccountMeta::new(keys.pool_pc_vault, false),
+                AccountMeta::new(keys.pool_target_orders, false),
+                AccountMeta::new_readonly(keys.amm_config, false),
+                AccountMeta::new(keys.create_fee_destination, false),
+                AccountMeta::new_readonly(keys.market_program, false),
+                AccountMeta::new(keys.market, false),
+                AccountMeta::new(keys.user_wallet, true),
+                AccountMeta::new(keys.user_token_coin, false),
+                AccountMeta::new(keys.user_token_pc, false),
+                AccountMeta::new(keys.user_token_lp, false),
+            ],
+            data: AmmInstruction::Initialize2 {
+                nonce,
+                open_time,
+                init_pc_amount,
+                init_coin_amount,
+            }
+            .pack(),
+        }
+    }
+
+    /// AMM v4 pool passed to `buy`/`sell` as remaining accounts: the AMM
+    /// program followed by the accounts of `SwapPoolKeys`, in order.
+    pub struct SwapPool<'a, 'info> {
+        pub program: &'a AccountInfo<'info>,
+        pub accounts: &'a [AccountInfo<'info>],
+    }
+
+    impl<'a, 'info> SwapPool<'a, 'info> {
+        pub const ACCOUNTS_LEN: usize = 1 + SwapPoolKeys::LEN;
+
+        pub fn load(
+            remaining_accounts: &'a [AccountInfo<'info>],
+            config: &LaunchConfig,
+            manager: &ABCManager,
+        ) -> Result<Self> {
+            require!(
+                remaining_accounts.len() >= Self::ACCOUNTS_LEN,
+                ErrorCode::MissingPoolAccounts
+            );
+
+            let pool = Self {
+                program: &remaining_accounts[0],
+                accounts: &remaining_accounts[1..Self::ACCOUNTS_LEN],
+            };
+
+            require!(
+                pool.program.key() == config.dex_program && pool.program.executable,
+                ErrorCode::InvalidRaydiumProgram
+            );
+            require!(
+                pool.accounts[0].key() == manager.raydium_pool,
+                ErrorCode::RaydiumPoolNotInitialized
+            );
+
+            let coin_vault = pool.coin_vault()?;
+            require!(coin_vault.mint == manager.mint, ErrorCode::RaydiumPoolNotInitialized);
+            // The SOL side is priced from the pc vault's lamports, so it has to
+            // be wrapped SOL
+            let pc_vault = Account::<TokenAccount>::try_from(&pool.accounts[5])?;
+            require!(
+                pc_vault.mint == spl_token::native_mint::id(),
+                ErrorCode::InvalidPoolAccount
+            );
+
+            Ok(pool)
+        }
+
+        pub fn keys(&self) -> SwapPoolKeys {
+            SwapPoolKeys::from_accounts(self.accounts)
+        }
+
+        fn coin_vault(&self) -> Result<Account<'info, TokenAccount>> {
+            Account::try_from(&self.accounts[4])
+        }
+
+        /// Token side from the coin vault, SOL side from the wrapped-SOL pc
+        /// vault, whose lamports above rent are its balance.
+        pub fn reserves(&self) -> Result<pricing::PoolReserves> {
+            let coin_vault = self.coin_vault()?;
+            pricing::PoolReserves::load(&coin_vault, &self.accounts[5])
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        pub fn swap_base_in(
+            &self,
+            token_program: &AccountInfo<'info>,
+            user_source: &AccountInfo<'info>,
+            user_destination: &AccountInfo<'info>,
+            user_owner: &AccountInfo<'info>,
+            amount_in: u64,
+            minimum_amount_out: u64,
+            signer_seeds: &[&[&[u8]]],
+        ) -> Result<()> {
+            let ix = swap_base_in(
+                self.program.key,
+                &self.keys(),
+                user_source.key,
+                user_destination.key,
+                user_owner.key,
+                amount_in,
+                minimum_amount_out,
+            );
+
+            let mut account_infos = Vec::with_capacity(Self::ACCOUNTS_LEN + 4);
+            account_infos.push(token_program.clone());
+            account_infos.extend(self.accounts.iter().cloned());
+            account_infos.push(user_source.clone());
+            account_infos.push(user_destination.clone());
+            account_infos.push(user_owner.clone());
+            account_infos.push(self.program.clone());
+
+            invoke_signed(&ix, &account_infos, signer_seeds).map_err(Into::into)
+        }
+    }
+
+    impl<'a, 'info> dex::DexAdapter<'info> for SwapPool<'a, 'info> {
+        fn accounts_len(&self) -> usize {
+            Self::ACCOUNTS_LEN
+        }
+
+        fn reserves(&self) -> Result<pricing::PoolReserves> {
+            SwapPool::reserves(self)
+        }
+
+        fn fee_bps(&self, config: &LaunchConfig) -> u64 {
+            config.pool_fee_bps
+        }
+
+        fn swap(
+            &self,
+            accounts: &dex::SwapAccounts<'_, 'info>,
+            direction: SwapDirection,
+            amount_in: u64,
+            minimum_amount_out: u64,
+            signer_seeds: &[&[&[u8]]],
+        ) -> Result<()> {
+            let (source, destination) = match direction {
+                SwapDirection::SolToToken => (accounts.sol_account, accounts.token_account),
+                SwapDirection::TokenToSol => (accounts.token_account, accounts.sol_account),
+            };
+
+            self.swap_base_in(
+                accounts.token_program,
+                source,
+                destination,
+                accounts.owner,
+                amount_in,
+                minimum_amount_out,
+                signer_seeds,
+            )
+        }
+    }
+
+    /// Accounts for `Initialize2` passed to `initialize` as remaining accounts,
+    /// in the order of the fields below.
+    pub struct CreatePool<'a, 'info> {
+        pub program: &'a AccountInfo<'info>,
+        pub associated_token_program: &'a AccountInfo<'info>,
+        pub amm: &'a AccountInfo<'info>,
+        pub amm_authority: &'a AccountInfo<'info>,
+        pub amm_open_orders: &'a AccountInfo<'info>,
+        pub lp_mint: &'a AccountInfo<'info>,
+        pub pc_mint: &'a AccountInfo<'info>,
+        pub pool_coin_vault: &'a AccountInfo<'info>,
+        pub pool_pc_vault: &'a AccountInfo<'info>,
+        pub pool_target_orders: &'a AccountInfo<'info>,
+        pub amm_config: &'a AccountInfo<'info>,
+        pub create_fee_destination: &'a AccountInfo<'info>,
+        pub market_program: &'a AccountInfo<'info>,
+        pub market: &'a AccountInfo<'info>,
+        pub user_token_pc: &'a AccountInfo<'info>,
+        pub user_token_lp: &'a AccountInfo<'info>,
+    }
+
+    impl<'a, 'info> CreatePool<'a, 'info> {
+        pub const ACCOUNTS_LEN: usize = 16;
+
+        pub fn load(remaining_accounts: &'a [AccountInfo<'info>], config: &LaunchConfig) -> Result<Self> {
+            require!(
+                remaining_accounts.len() >= Self::ACCOUNTS_LEN,
+                ErrorCode::MissingPoolAccounts
+            );
+
+            let accounts = &remaining_accounts[..Self::ACCOUNTS_LEN];
+            require!(
+                accounts[0].key() == config.dex_program && accounts[0].executable,
+                ErrorCode::InvalidRaydiumProgram
+            );
+            require!(
+                accounts[1].key() == Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID).unwrap(),
+                ErrorCode::InvalidRaydiumProgram
+            );
+            // Launches are quoted in SOL
+            require!(
+                accounts[6].key() == spl_token::native_mint::id(),
+                ErrorCode::InvalidPoolAccount
+            );
+
+            Ok(Self {
+                program: &accounts[0],
+                associated_token_program: &accounts[1],
+                amm: &accounts[2],
+                amm_authority: &accounts[3],
+                amm_open_orders: &accounts[4],
+                lp_mint: &accounts[5],
+                pc_mint: &accounts[6],
+                pool_coin_vault: &accounts[7],
+                pool_pc_vault: &accounts[8],
+                pool_target_orders: &accounts[9],
+                amm_config: &accounts[10],
+                create_fee_destination: &accounts[11],
+                market_program: &accounts[12],
+                market: &accounts[13],
+                user_token_pc: &accounts[14],
+                user_token_lp: &accounts[15],
+            })
+        }
+
+        /// Creates the pool with `user_wallet` funding both sides.
+        #[allow(clippy::too_many_arguments)]
+        pub fn initialize2(
+            &self,
+            token_program: &AccountInfo<'info>,
+            system_program: &AccountInfo<'info>,
+            rent: &AccountInfo<'info>,
+            coin_mint: &AccountInfo<'info>,
+            user_wallet: &AccountInfo<'info>,
+            user_token_coin: &AccountInfo<'info>,
+            init_pc_amount: u64,
+            init_coin_amount: u64,
+        ) -> Result<()> {
+            let (amm_authority, nonce) =
+                Pubkey::find_program_address(&[AUTHORITY_AMM], self.program.key);
+            require!(
+                amm_authority == self.amm_authority.key(),
+                ErrorCode::InvalidRaydiumProgram
+            );
+
+            let keys = InitializeKeys {
+                amm: self.amm.key(),
+                amm_authority,
+                amm_open_orders: self.amm_open_orders.key(),
+                lp_mint: self.lp_mint.key(),
+                coin_mint: coin_mint.key(),
+                pc_mint: self.pc_mint.key(),
+                pool_coin_vault: self.pool_coin_vault.key(),
+                pool_pc_vault: self.pool_pc_vault.key(),
+                pool_target_orders: self.pool_target_orders.key(),
+                amm_config: self.amm_config.key(),
+                create_fee_destination: self.create_fee_destination.key(),
+                market_program: self.market_program.key(),
+                market: self.market.key(),
+                user_wallet: user_wallet.key(),
+                user_token_coin: user_token_coin.key(),
+                user_token_pc: self.user_token_pc.key(),
+                user_token_lp: self.user_token_lp.key(),
+            };
+
+            let ix = initialize2(self.program.key, &keys, nonce, 0, init_pc_amount, init_coin_amount);
+
+            invoke(
+                &ix,
+                &[
+                    token_program.clone(),
+                    self.associated_token_program.clone(),
+                    system_program.clone(),
+                    rent.clone(),
+                    self.amm.clone(),
+                    self.amm_authority.clone(),
+                    self.amm_open_orders.clone(),
+                    self.lp_mint.clone(),
+                    coin_mint.clone(),
+                    self.pc_mint.clone(),
+                    self.pool_coin_vault.clone(),
+                    self.pool_pc_vault.clone(),
+                    self.pool_target_orders.clone(),
+                    self.amm_config.clone(),
+                    self.create_fee_destination.clone(),
+                    self.market_program.clone(),
+                    self.market.clone(),
+                    user_wallet.clone(),
+                    user_token_coin.clone(),
+                    self.user_token_pc.clone(),
+                    self.user_token_lp.clone(),
+                    self.program.clone(),
+                ],
+            )
+            .map_err(Into::into)
+        }
+    }
+}
+
+// Orca Whirlpool swap layout and CPI helpers
+pub mod whirlpool {
+    use super::*;
+    use anchor_spl::token::spl_token::{self, native_mint};
+
+    /// Anchor discriminator of the `swap` instruction.
+    pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+    /// Anchor discriminator of the `Whirlpool` account.
+    pub const WHIRLPOOL_DISCRIMINATOR: [u8; 8] = [63, 149, 209, 12, 225, 128, 99, 9];
+
+    pub const MIN_SQRT_PRICE_X64: u128 = 4_295_048_016;
+    pub const MAX_SQRT_PRICE_X64: u128 = 79_226_673_515_401_279_992_447_579_055;
+
+    /// `fee_rate` is in hundredths of a basis point.
+    pub const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+    /// Fields of the `Whirlpool` account needed to quote and route a swap.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct WhirlpoolState {
+        pub fee_rate: u16,
+        pub liquidity: u128,
+        pub sqrt_price: u128,
+        pub token_mint_a: Pubkey,
+        pub token_vault_a: Pubkey,
+        pub token_mint_b: Pubkey,
+        pub token_vault_b: Pubkey,
+    }
+
+    impl WhirlpoolState {
+        // Offsets into the account data, discriminator included
+        const FEE_RATE: usize = 45;
+        const LIQUIDITY: usize = 49;
+        const SQRT_PRICE: usize = 65;
+        const TOKEN_MINT_A: usize = 101;
+        const TOKEN_VAULT_A: usize = 133;
+        const TOKEN_MINT_B: usize = 181;
+        const TOKEN_VAULT_B: usize = 213;
+
+        pub const MIN_LEN: usize = Self::TOKEN_VAULT_B + 32;
+
+        pub fn unpack(data: &[u8]) -> Result<Self> {
+            require!(
+                data.len() >= Self::MIN_LEN && data[..8] == WHIRLPOOL_DISCRIMINATOR,
+                ErrorCode::InvalidPoolAccount
+            );
+
+            let pubkey_at = |offset: usize| Pubkey::try_from(&data[offset..offset + 32]).unwrap();
+            let u128_at = |offset: usize| {
+                u128::from_le_bytes(data[offset..offset + 16].try_into().unwrap())
+            };
+
+            Ok(Self {
+                fee_rate: u16::from_le_bytes([data[Self::FEE_RATE], data[Self::FEE_RATE + 1]]),
+                liquidity: u128_at(Self::LIQUIDITY),
+                sqrt_price: u128_at(Self::SQRT_PRICE),
+                token_mint_a: pubkey_at(Self::TOKEN_MINT_A),
+                token_vault_a: pubkey_at(Self::TOKEN_VAULT_A),
+                token_mint_b: pubkey_at(Self::TOKEN_MINT_B),
+                token_vault_b: pubkey_at(Self::TOKEN_VAULT_B),
+            })
+        }
+
+        /// Constant-product reserves equivalent to the active liquidity at the
+        /// current price: `a = L / sqrt(P)`, `b = L * sqrt(P)`. Quotes from
+        /// them hold while the swap stays inside the current tick range.
+        pub fn virtual_reserves(&self) -> (u64, u64) {
+            let reserve_a = match self.liquidity.checked_mul(1 << 64) {
+                Some(scaled) => scaled / self.sqrt_price.max(1),
+                None => (self.liquidity / self.sqrt_price.max(1)).saturating_mul(1 << 64),
+            };
+            let reserve_b = match self.liquidity.checked_mul(self.sqrt_price) {
+                Some(product) => product >> 64,
+                None => (self.liquidity >> 32).saturating_mul(self.sqrt_price >> 32),
+            };
+
+            (
+                u64::try_from(reserve_a).unwrap_or(u64::MAX),
+                u64::try_from(reserve_b).unwrap_or(u64::MAX),
+            )
+        }
+
+        /// Fee rate rounded up to whole basis points.
+        pub fn fee_bps(&self) -> u64 {
+            let per_bps = FEE_RATE_DENOMINATOR / BPS_DENOMINATOR;
+            (self.fee_rate as u64 + per_bps - 1) / per_bps
+        }
+    }
+
+    /// Pool-side accounts of a Whirlpool swap, in instruction order.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct SwapPoolKeys {
+        pub whirlpool: Pubkey,
+        pub token_vault_a: Pubkey,
+        pub token_vault_b: Pubkey,
+        pub tick_array_0: Pubkey,
+        pub tick_array_1: Pubkey,
+        pub tick_array_2: Pubkey,
+        pub oracle: Pubkey,
+    }
+
+    impl SwapPoolKeys {
+        pub const LEN: usize = 7;
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap(
+        program_id: &Pubkey,
+        pool: &SwapPoolKeys,
+        token_authority: &Pubkey,
+        token_owner_account_a: &Pubkey,
+        token_owner_account_b: &Pubkey,
+        amount: u64,
+        other_amount_threshold: u64,
+        amount_specified_is_input: bool,
+        a_to_b: bool,
+    ) -> Instruction {
+        let sqrt_price_limit = if a_to_b {
+            MIN_SQRT_PRICE_X64
+        } else {
+            MAX_SQRT_PRICE_X64
+        };
+
+        let mut data = Vec::with_capacity(42);
+        data.extend_from_slice(&SWAP_DISCRIMINATOR);
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&other_amount_threshold.to_le_bytes());
+        data.extend_from_slice(&sqrt_price_limit.to_le_bytes());
+        data.push(amount_specified_is_input as u8);
+        data.push(a_to_b as u8);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(*token_authority, true),
+                AccountMeta::new(pool.whirlpool, false),
+                AccountMeta::new(*token_owner_account_a, false),
+                AccountMeta::new(pool.token_vault_a, false),
+                AccountMeta::new(*token_owner_account_b, false),
+                AccountMeta::new(pool.token_vault_b, false),
+                AccountMeta::new(pool.tick_array_0, false),
+                AccountMeta::new(pool.tick_array_1, false),
+                AccountMeta::new(pool.tick_array_2, false),
+                AccountMeta::new_readonly(pool.oracle, false),
+            ],
+            data,
+        }
+    }
+
+    /// Checks that `whirlpool` is a pool of `dex_program` pairing `mint` with
+    /// wrapped SOL.
+    pub fn load_state(
+        program: &AccountInfo,
+        whirlpool: &AccountInfo,
+        config: &LaunchConfig,
+        mint: &Pubkey,
+    ) -> Result<WhirlpoolState> {
+        require!(
+            program.key() == config.dex_program && program.executable,
+            ErrorCode::InvalidDexProgram
+        );
+        require!(
+            whirlpool.owner == program.key,
+            ErrorCode::InvalidPoolAccount
+        );
+
+        let state = WhirlpoolState::unpack(&whirlpool.try_borrow_data()?)?;
+        let pairs_with_sol = (state.token_mint_a == *mint && state.token_mint_b == native_mint::id())
+            || (state.token_mint_a == native_mint::id() && state.token_mint_b == *mint);
+        require!(pairs_with_sol, ErrorCode::InvalidPoolAccount);
+
+        Ok(state)
+    }
+
+    /// Whirlpool passed to `buy`/`sell` as remaining accounts: the Whirlpool
+    /// program followed by the accounts of `SwapPoolKeys`, in order.
+    pub struct SwapPool<'a, 'info> {
+        pub program: &'a AccountInfo<'info>,
+        pub accounts: &'a [AccountInfo<'info>],
+        pub state: WhirlpoolState,
+    }
+
+    impl<'a, 'info> SwapPool<'a, 'info> {
+        pub const ACCOUNTS_LEN: usize = 1 + SwapPoolKeys::LEN;
+
+        pub fn load(
+            remaining_accounts: &'a [AccountInfo<'info>],
+            config: &LaunchConfig,
+            manager: &ABCManager,
+        ) -> Result<Self> {
+            require!(
+                remaining_accounts.len() >= Self::ACCOUNTS_LEN,
+                ErrorCode::MissingPoolAccounts
+            );
+
+            let program = &remaining_accounts[0];
+            let accounts = &remaining_accounts[1..Self::ACCOUNTS_LEN];
+            require!(
+                accounts[0].key() == manager.raydium_pool,
+                ErrorCode::InvalidPoolAccount
+            );
+
+            let state = load_state(program, &accounts[0], config, &manager.mint)?;
+            require!(
+                accounts[1].key() == state.token_vault_a && accounts[2].key() == state.token_vault_b,
+                ErrorCode::InvalidPoolAccount
+            );
+
+            Ok(Self {
+                program,
+                accounts,
+                state,
+            })
+        }
+
+        pub fn keys(&self) -> SwapPoolKeys {
+            SwapPoolKeys {
+                whirlpool: self.accounts[0].key(),
+                token_vault_a: self.accounts[1].key(),
+                token_vault_b: self.accounts[2].key(),
+                tick_array_0: self.accounts[3].key(),
+                tick_array_1: self.accounts[4].key(),
+                tick_array_2: self.accounts[5].key(),
+                oracle: self.accounts[6].key(),
+            }
+        }
+
+        fn sol_is_a(&self) -> bool {
+            self.state.token_mint_a == native_mint::id()
+        }
+    }
+
+    impl<'a, 'info> dex::DexAdapter<'info> for SwapPool<'a, 'info> {
+        fn accounts_len(&self) -> usize {
+            Self::ACCOUNTS_LEN
+        }
+
+        fn reserves(&self) -> Result<pricing::PoolReserves> {
+            let (reserve_a, reserve_b) = self.state.virtual_reserves();
+            let reserves = if self.sol_is_a() {
+                pricing::PoolReserves {
+                    sol: reserve_a,
+                    token: reserve_b,
+                }
+            } else {
+                pricing::PoolReserves {
+                    sol: reserve_b,
+                    token: reserve_a,
+                }
+            };
+
+            require!(
+                reserves.sol > 0 && reserves.token > 0,
+                ErrorCode::InsufficientLiquidity
+            );
+
+            Ok(reserves)
+        }
+
+        fn fee_bps(&self, _config: &LaunchConfig) -> u64 {
+            self.state.fee_bps()
+        }
+
+        fn swap(
+            &self,
+            accounts: &dex::SwapAccounts<'_, 'info>,
+            direction: SwapDirection,
+            amount_in: u64,
+            minimum_amount_out: u64,
+            signer_seeds: &[&[&[u8]]],
+        ) -> Result<()> {
+            let (owner_account_a, owner_account_b) = if self.sol_is_a() {
+                (accounts.sol_account, accounts.token_account)
+            } else {
+                (accounts.token_account, accounts.sol_account)
+            };
+            let a_to_b = (direction == SwapDirection::SolToToken) == self.sol_is_a();
+
+            let ix = swap(
+                self.program.key,
+                &self.keys(),
+                accounts.owner.key,
+                owner_account_a.key,
+                owner_account_b.key,
+                amount_in,
+                minimum_amount_out,
+                true,
+                a_to_b,
+            );
+
+            invoke_signed(
+                &ix,
+                &[
+                    accounts.token_program.clone(),
+                    accounts.owner.clone(),
+                    self.accounts[0].clone(),
+                    owner_account_a.clone(),
+                    self.accounts[1].clone(),
+                    owner_account_b.clone(),
+                    self.accounts[2].clone(),
+                    self.accounts[3].clone(),
+                    self.accounts[4].clone(),
+                    self.accounts[5].clone(),
+                    self.accounts[6].clone(),
+                    self.program.clone(),
+                ],
+                signer_seeds,
+            )
+            .map_err(Into::into)
+        }
+    }
+}
+
+// Built-in constant-product pool. Tokens sit in a pool-owned vault and SOL in
+// the treasury PDA next to captured SOL, so reserves are tracked in
+// `NativePool` instead of read from balances.
+pub mod native {
+    use super::*;
+    use anchor_spl::token::{Burn, MintTo};
+    use std::cell::RefCell;
+
+    pub fn pool_address(mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"native_pool", mint.as_ref()], &crate::ID).0
+    }
+
+    pub fn lp_mint_address(mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"lp_mint", mint.as_ref()], &crate::ID).0
+    }
+
+    /// The pool's share of the treasury, or 0 if the pool isn't open yet.
+    pub fn pool_sol_reserve(pool: &AccountInfo, mint: &Pubkey) -> Result<u64> {
+        require!(
+            pool.key() == pool_address(mint),
+            ErrorCode::InvalidPoolAccount
+        );
+        if pool.owner != &crate::ID {
+            return Ok(0);
+        }
+
+        let state = NativePool::try_deserialize(&mut &pool.try_borrow_data()?[..])?;
+        Ok(state.sol_reserve)
+    }
+
+    /// Pool-side accounts a native swap settles through.
+    pub struct PoolAccounts<'a, 'info> {
+        pub pool: &'a AccountInfo<'info>,
+        pub pool_vault: &'a AccountInfo<'info>,
+        pub treasury: &'a AccountInfo<'info>,
+        pub system_program: &'a AccountInfo<'info>,
+    }
+
+    /// Exact-input swap against `state`: settles both legs and moves the
+    /// reserves by the full input and the output. Returns the amount paid out.
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap<'info>(
+        state: &mut NativePool,
+        pool: &PoolAccounts<'_, 'info>,
+        swapper: &dex::SwapAccounts<'_, 'info>,
+        direction: SwapDirection,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        fee_bps: u64,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<u64> {
+        let reserves = state.reserves().require_liquidity()?;
+
+        match direction {
+            SwapDirection::SolToToken => {
+                let amount_out = pricing::calculate_tokens_from_sol(&reserves, amount_in, fee_bps)?;
+                require!(amount_out >= minimum_amount_out, ErrorCode::SlippageExceeded);
+
+                receive_lamports(pool, swapper.sol_account, amount_in, signer_seeds)?;
+                pay_tokens(state, pool, swapper.token_program, swapper.token_account, amount_out)?;
+
+                state.sol_reserve = state
+                    .sol_reserve
+                    .checked_add(amount_in)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                state.token_reserve -= amount_out;
+                Ok(amount_out)
+            }
+            SwapDirection::TokenToSol => {
+                let amount_out = pricing::calculate_sol_from_tokens(&reserves, amount_in, fee_bps)?;
+                require!(amount_out >= minimum_amount_out, ErrorCode::SlippageExceeded);
+
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        swapper.token_program.clone(),
+                        Transfer {
+                            from: swapper.token_account.clone(),
+                            to: pool.pool_vault.clone(),
+                            authority: swapper.owner.clone(),
+                        },
+                        signer_seeds,
+                    ),
+                    amount_in,
+                )?;
+                pay_lamports(state, pool, swapper.sol_account, amount_out)?;
+
+                state.token_reserve = state
+                    .token_reserve
+                    .checked_add(amount_in)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                state.sol_reserve -= amount_out;
+                Ok(amount_out)
+            }
+        }
+    }
+
+    // SOL already in the treasury (a counter-trade's proceeds) stays put; only
+    // the pool's share of it changes
+    fn receive_lamports<'info>(
+        pool: &PoolAccounts<'_, 'info>,
+        from: &AccountInfo<'info>,
+        amount: u64,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        if from.key == pool.treasury.key {
+            return Ok(());
+        }
+
+        invoke_signed(
+            &system_instruction::transfer(from.key, pool.treasury.key, amount),
+            &[from.clone(), pool.treasury.clone(), pool.system_program.clone()],
+            signer_seeds,
+        )
+        .map_err(Into::into)
+    }
+
+    fn pay_lamports<'info>(
+        state: &NativePool,
+        pool: &PoolAccounts<'_, 'info>,
+        to: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        if to.key == pool.treasury.key {
+            return Ok(());
+        }
+
+        invoke_signed(
+            &system_instruction::transfer(pool.treasury.key, to.key, amount),
+            &[pool.treasury.clone(), to.clone(), pool.system_program.clone()],
+            &[&[b"treasury", state.mint.as_ref(), &[state.treasury_bump]]],
+        )
+        .map_err(Into::into)
+    }
+
+    fn pay_tokens<'info>(
+        state: &NativePool,
+        pool: &PoolAccounts<'_, 'info>,
+        token_program: &AccountInfo<'info>,
+        to: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                Transfer {
+                    from: pool.pool_vault.clone(),
+                    to: to.clone(),
+                    authority: pool.pool.clone(),
+                },
+                &[&[b"native_pool", state.mint.as_ref(), &[state.bump]]],
+            ),
+            amount,
+        )
+    }
+
+    pub fn process_add_liquidity(
+        ctx: Context<NativeLiquidity>,
+        sol_amount: u64,
+        max_token_amount: u64,
+        min_lp_out: u64,
+    ) -> Result<()> {
+        let (token_amount, lp_amount) = pricing::calculate_liquidity_deposit(
+            &ctx.accounts.native_pool.reserves(),
+            ctx.accounts.lp_mint.supply,
+            sol_amount,
+            max_token_amount,
+        )?;
+        require!(lp_amount > 0, ErrorCode::TradeTooSmall);
+
+        if token_amount > max_token_amount || lp_amount < min_lp_out {
+            msg!(
+                "Deposit needs {} tokens (max {}) for {} LP (min {})",
+                token_amount,
+                max_token_amount,
+                lp_amount,
+                min_lp_out
+            );
+            return err!(ErrorCode::SlippageExceeded);
+        }
+
+        invoke(
+            &system_instruction::transfer(
+                &ctx.accounts.provider.key(),
+                &ctx.accounts.treasury.key(),
+                sol_amount,
+            ),
+            &[
+                ctx.accounts.provider.to_account_info(),
+                ctx.accounts.treasury.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.provider_token_account.to_account_info(),
+                    to: ctx.accounts.pool_vault.to_account_info(),
+                    authority: ctx.accounts.provider.to_account_info(),
+                },
+            ),
+            token_amount,
+        )?;
+
+        let pool = &ctx.accounts.native_pool;
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: ctx.accounts.provider_lp_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                &[&[b"native_pool", pool.mint.as_ref(), &[pool.bump]]],
+            ),
+            lp_amount,
+        )?;
+
+        let pool = &mut ctx.accounts.native_pool;
+        pool.sol_reserve = pool
+            .sol_reserve
+            .checked_add(sol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        pool.token_reserve = pool
+            .token_reserve
+            .checked_add(token_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(LiquidityAdded {
+            mint: pool.mint,
+            provider: ctx.accounts.provider.key(),
+            sol_amount,
+            token_amount,
+            lp_amount,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    pub fn process_remove_liquidity(
+        ctx: Context<NativeLiquidity>,
+        lp_amount: u64,
+        min_sol_out: u64,
+        min_token_out: u64,
+    ) -> Result<()> {
+        let (sol_amount, token_amount) = pricing::calculate_liquidity_withdrawal(
+            &ctx.accounts.native_pool.reserves(),
+            ctx.accounts.lp_mint.supply,
+            lp_amount,
+        )?;
+
+        if sol_amount < min_sol_out || token_amount < min_token_out {
+            msg!(
+                "Withdrawal returns {} lamports (min {}) and {} tokens (min {})",
+                sol_amount,
+                min_sol_out,
+                token_amount,
+                min_token_out
+            );
+            return err!(ErrorCode::SlippageExceeded);
+        }
+
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    from: ctx.accounts.provider_lp_account.to_account_info(),
+                    authority: ctx.accounts.provider.to_account_info(),
+                },
+            ),
+            lp_amount,
+        )?;
+
+        let pool_accounts = PoolAccounts {
+            pool: ctx.accounts.native_pool.as_ref(),
+            pool_vault: ctx.accounts.pool_vault.as_ref(),
+            treasury: &ctx.accounts.treasury,
+            system_program: ctx.accounts.system_program.as_ref(),
+        };
+        pay_tokens(
+            &ctx.accounts.native_pool,
+            &pool_accounts,
+            ctx.accounts.token_program.as_ref(),
+            ctx.accounts.provider_token_account.as_ref(),
+            token_amount,
+        )?;
+        pay_lamports(
+            &ctx.accounts.native_pool,
+            &pool_accounts,
+            ctx.accounts.provider.as_ref(),
+            sol_amount,
+        )?;
+
+        let pool = &mut ctx.accounts.native_pool;
+        pool.sol_reserve -= sol_amount;
+        pool.token_reserve -= token_amount;
+
+        emit!(LiquidityRemoved {
+            mint: pool.mint,
+            provider: ctx.accounts.provider.key(),
+            sol_amount,
+            token_amount,
+            lp_amount,
+            slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    /// Native pool passed to `buy`/`sell` as remaining accounts: the
+    /// `NativePool` account followed by its token vault.
+    pub struct SwapPool<'a, 'info> {
+        pub accounts: PoolAccounts<'a, 'info>,
+        pub state: RefCell<Account<'info, NativePool>>,
+        pub fee_bps: u64,
+    }
+
+    impl<'a, 'info> SwapPool<'a, 'info> {
+        pub const ACCOUNTS_LEN: usize = 2;
+
+        pub fn load(
+            remaining_accounts: &'a [AccountInfo<'info>],
+            config: &LaunchConfig,
+            manager: &ABCManager,
+            treasury: &'a AccountInfo<'info>,
+            system_program: &'a AccountInfo<'info>,
+        ) -> Result<Self> {
+            require!(
+                remaining_accounts.len() >= Self::ACCOUNTS_LEN,
+                ErrorCode::MissingPoolAccounts
+            );
+
+            let pool = &remaining_accounts[0];
+            let pool_vault = &remaining_accounts[1];
+            require!(
+                pool.key() == pool_address(&manager.mint),
+                ErrorCode::InvalidPoolAccount
+            );
+
+            let state: Account<NativePool> = Account::try_from(pool)?;
+            require!(
+                state.mint == manager.mint && pool_vault.key() == state.token_vault,
+                ErrorCode::InvalidPoolAccount
+            );
+
+            Ok(Self {
+                accounts: PoolAccounts {
+                    pool,
+                    pool_vault,
+                    treasury,
+                    system_program,
+                },
+                state: RefCell::new(state),
+                fee_bps: config.pool_fee_bps,
+            })
+        }
+    }
+
+    impl<'a, 'info> dex::DexAdapter<'info> for SwapPool<'a, 'info> {
+        fn accounts_len(&self) -> usize {
+            Self::ACCOUNTS_LEN
+        }
+
+        fn wraps_sol(&self) -> bool {
+            false
+        }
+
+        fn reserves(&self) -> Result<pricing::PoolReserves> {
+            self.state.borrow().reserves().require_liquidity()
+        }
+
+        fn fee_bps(&self, _config: &LaunchConfig) -> u64 {
+            self.fee_bps
+        }
+
+        fn swap(
+            &self,
+            accounts: &dex::SwapAccounts<'_, 'info>,
+            direction: SwapDirection,
+            amount_in: u64,
+            minimum_amount_out: u64,
+            signer_seeds: &[&[&[u8]]],
+        ) -> Result<()> {
+            let mut state = self.state.borrow_mut();
+            swap(
+                &mut state,
+                &self.accounts,
+                accounts,
+                direction,
+                amount_in,
+                minimum_amount_out,
+                self.fee_bps,
+                signer_seeds,
+            )?;
+
+            // Not one of the instruction's typed accounts, so persist by hand
+            state.exit(&crate::ID)
+        }
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ABCManager::LEN,
+        seeds = [b"abc_manager", mint.key().as_ref()],
+        bump
+    )]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = LaunchConfig::LEN,
+        seeds = [b"launch_config", mint.key().as_ref()],
+        bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        mut,
+        constraint = token_source.mint == mint.key(),
+        constraint = token_source.owner == authority.key()
+    )]
+    pub token_source: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"reserve", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = manager,
+    )]
+    pub reserve_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct Trade<'info> {
+    #[account(mut)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(mut)]
+    pub trader: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = trader_token_account.mint == manager.mint,
+        constraint = trader_token_account.owner == trader.key()
+    )]
+    pub trader_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve", manager.mint.as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", manager.mint.as_ref()],
+        bump
+    )]
+    /// CHECK: Treasury account for SOL
+    pub treasury: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"monitored_purchase", manager.key().as_ref(), trader.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Created and written by monitored buys only
+    pub monitored_purchase: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"flagged_wallet", manager.key().as_ref(), trader.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Created and written by monitored buys only
+    pub flagged_wallet: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"wallet_purchases", manager.key().as_ref(), trader.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Created and written by fair-launch buys only
+    pub wallet_purchases: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct PresaleBuy<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(mut)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        mut,
+        constraint = buyer_token_account.mint == manager.mint,
+        constraint = buyer_token_account.owner == buyer.key()
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve", manager.mint.as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", manager.mint.as_ref()],
+        bump
+    )]
+    /// CHECK: Treasury account for SOL
+    pub treasury: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_purchase", manager.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Created and written by presale buys only
+    pub presale_purchase: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ScheduleLaunch<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub manager: Account<'info, ABCManager>,
+}
+
+#[derive(Accounts)]
+pub struct ClearFlaggedWallet<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority @ ErrorCode::Unauthorized)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        mut,
+        has_one = manager,
+        seeds = [b"flagged_wallet", manager.key().as_ref(), flagged_wallet.wallet.as_ref()],
+        bump = flagged_wallet.bump
+    )]
+    pub flagged_wallet: Account<'info, FlaggedWallet>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(mut)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        mut,
+        close = buyer,
+        has_one = manager,
+        has_one = buyer,
+        seeds = [b"monitored_purchase", manager.key().as_ref(), buyer.key().as_ref()],
+        bump = monitored_purchase.bump
+    )]
+    pub monitored_purchase: Account<'info, MonitoredPurchase>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", manager.mint.as_ref()],
+        bump
+    )]
+    /// CHECK: Treasury account for SOL
+    pub treasury: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitBuy<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(mut)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", manager.mint.as_ref()],
+        bump
+    )]
+    /// CHECK: Treasury account for SOL
+    pub treasury: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = buyer,
+        space = PurchaseCommitment::LEN,
+        seeds = [b"commitment", manager.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub commitment: Account<'info, PurchaseCommitment>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevealBuy<'info> {
+    pub buyer: Signer<'info>,
+
+    #[account(mut)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        mut,
+        has_one = manager,
+        has_one = buyer,
+        seeds = [b"commitment", manager.key().as_ref(), buyer.key().as_ref()],
+        bump = commitment.bump
+    )]
+    pub commitment: Account<'info, PurchaseCommitment>,
+}
+
+#[derive(Accounts)]
+pub struct SettleCommitment<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(mut)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        mut,
+        constraint = buyer_token_account.mint == manager.mint,
+        constraint = buyer_token_account.owner == buyer.key()
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve", manager.mint.as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", manager.mint.as_ref()],
+        bump
+    )]
+    /// CHECK: Treasury account for SOL
+    pub treasury: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = buyer,
+        has_one = manager,
+        has_one = buyer,
+        seeds = [b"commitment", manager.key().as_ref(), buyer.key().as_ref()],
+        bump = commitment.bump
+    )]
+    pub commitment: Account<'info, PurchaseCommitment>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAuction<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority @ ErrorCode::Unauthorized)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Auction::LEN,
+        seeds = [b"auction", manager.mint.as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, Auction>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(mut)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        mut,
+        has_one = manager,
+        seeds = [b"auction", manager.mint.as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", manager.mint.as_ref()],
+        bump
+    )]
+    /// CHECK: Treasury account for SOL
+    pub treasury: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = Bid::LEN,
+        seeds = [b"bid", manager.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bid: Account<'info, Bid>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    #[account(mut)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        mut,
+        has_one = manager,
+        seeds = [b"auction", manager.mint.as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve", manager.mint.as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", manager.mint.as_ref()],
+        bump
+    )]
+    /// CHECK: Treasury account for SOL
+    pub treasury: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CurveBuy<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(mut)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        mut,
+        constraint = buyer_token_account.mint == manager.mint,
+        constraint = buyer_token_account.owner == buyer.key()
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve", manager.mint.as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", manager.mint.as_ref()],
+        bump
+    )]
+    /// CHECK: Treasury account for SOL
+    pub treasury: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Graduate<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut, has_one = mint)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", mint.key().as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve", mint.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = NativePool::LEN,
+        seeds = [b"native_pool", mint.key().as_ref()],
+        bump
+    )]
+    pub native_pool: Account<'info, NativePool>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"pool_vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = native_pool,
+    )]
+    pub pool_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"lp_mint", mint.key().as_ref()],
+        bump,
+        mint::decimals = mint.decimals,
+        mint::authority = native_pool,
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"lp_vault", mint.key().as_ref()],
+        bump,
+        token::mint = lp_mint,
+        token::authority = manager,
+    )]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = LiquidityLock::LEN,
+        seeds = [b"lp_lock", mint.key().as_ref()],
+        bump
+    )]
+    pub lp_lock: Account<'info, LiquidityLock>,
+
+    #[account(
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump
+    )]
+    /// CHECK: SOL side of the pool, already holding the raised SOL
+    pub treasury: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct LockLiquidity<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority @ ErrorCode::Unauthorized, has_one = mint)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", mint.key().as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    pub mint: Account<'info, Mint>,
+
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = lp_source.mint == lp_mint.key(),
+        constraint = lp_source.owner == authority.key()
+    )]
+    pub lp_source: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = LiquidityLock::LEN,
+        seeds = [b"lp_lock", mint.key().as_ref()],
+        bump
+    )]
+    pub lp_lock: Account<'info, LiquidityLock>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"lp_vault", mint.key().as_ref()],
+        bump,
+        token::mint = lp_mint,
+        token::authority = manager,
+    )]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendLock<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority @ ErrorCode::Unauthorized)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        mut,
+        has_one = manager,
+        seeds = [b"lp_lock", manager.mint.as_ref()],
+        bump = lp_lock.bump
+    )]
+    pub lp_lock: Account<'info, LiquidityLock>,
+}
+
+#[derive(Accounts)]
+pub struct UnlockLiquidity<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority @ ErrorCode::Unauthorized)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        mut,
+        has_one = manager,
+        seeds = [b"lp_lock", manager.mint.as_ref()],
+        bump = lp_lock.bump
+    )]
+    pub lp_lock: Account<'info, LiquidityLock>,
+
+    #[account(
+        mut,
+        seeds = [b"lp_vault", manager.mint.as_ref()],
+        bump
+    )]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, constraint = destination.mint == lp_lock.lp_mint)]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateNativePool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = mint
+    )]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", mint.key().as_ref()],
+        bump = launch_config.bump,
+        constraint = launch_config.dex == DexKind::ConstantProduct @ ErrorCode::UnsupportedDex,
+        // Curve launches open their pool at graduation
+        constraint = !launch_config.curve.is_enabled() @ ErrorCode::CurveNotActive
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = NativePool::LEN,
+        seeds = [b"native_pool", mint.key().as_ref()],
+        bump
+    )]
+    pub native_pool: Account<'info, NativePool>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"pool_vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = native_pool,
+    )]
+    pub pool_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"lp_mint", mint.key().as_ref()],
+        bump,
+        mint::decimals = mint.decimals,
+        mint::authority = native_pool,
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump
+    )]
+    /// CHECK: SOL side of the pool, only its bump is recorded here
+    pub treasury: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct NativeLiquidity<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"native_pool", native_pool.mint.as_ref()],
+        bump = native_pool.bump
+    )]
+    pub native_pool: Account<'info, NativePool>,
+
+    #[account(mut, address = native_pool.token_vault)]
+    pub pool_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, address = native_pool.lp_mint)]
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", native_pool.mint.as_ref()],
+        bump = native_pool.treasury_bump
+    )]
+    /// CHECK: Treasury account for SOL
+    pub treasury: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = provider_token_account.mint == native_pool.mint,
+        constraint = provider_token_account.owner == provider.key()
+    )]
+    pub provider_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = provider_lp_account.mint == native_pool.lp_mint,
+        constraint = provider_lp_account.owner == provider.key()
+    )]
+    pub provider_lp_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct NativeSwap<'info> {
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump,
+        constraint = launch_config.dex == DexKind::ConstantProduct @ ErrorCode::UnsupportedDex
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(mut)]
+    pub trader: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = trader_token_account.mint == manager.mint,
+        constraint = trader_token_account.owner == trader.key()
+    )]
+    pub trader_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"native_pool", manager.mint.as_ref()],
+        bump = native_pool.bump
+    )]
+    pub native_pool: Account<'info, NativePool>,
+
+    #[account(mut, address = native_pool.token_vault)]
+    pub pool_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", manager.mint.as_ref()],
+        bump = native_pool.treasury_bump
+    )]
+    /// CHECK: Treasury account for SOL
+    pub treasury: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeConfigUpdate<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority @ ErrorCode::Unauthorized)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = PendingConfigUpdate::LEN,
+        seeds = [b"config_update", manager.mint.as_ref()],
+        bump
+    )]
+    pub pending_update: Account<'info, PendingConfigUpdate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyConfigUpdate<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        mut,
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"config_update", manager.mint.as_ref()],
+        bump = pending_update.bump
+    )]
+    pub pending_update: Account<'info, PendingConfigUpdate>,
+}
+
+#[derive(Accounts)]
+pub struct CancelConfigUpdate<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority @ ErrorCode::Unauthorized)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"config_update", manager.mint.as_ref()],
+        bump = pending_update.bump
+    )]
+    pub pending_update: Account<'info, PendingConfigUpdate>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub manager: Account<'info, ABCManager>,
+}
+
+#[derive(Accounts)]
+pub struct RenounceAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(seeds = [b"config_update", manager.mint.as_ref()], bump)]
+    /// CHECK: Only checked for a queued config update
+    pub pending_update: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", manager.mint.as_ref()],
+        bump
+    )]
+    /// CHECK: Treasury account for SOL
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut)]
+    /// CHECK: Any account may receive SOL; must match the `destination` argument
+    pub destination: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseReserve<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve", manager.mint.as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, constraint = destination.mint == manager.mint)]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVesting<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized, has_one = mint)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", mint.key().as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: Only recorded as the grant's owner and used in its seeds
+    pub beneficiary: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve", mint.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = VestingGrant::LEN,
+        seeds = [b"vesting", manager.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    pub grant: Account<'info, VestingGrant>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vesting_vault", manager.key().as_ref(), beneficiary.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = manager,
+    )]
+    pub grant_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    pub beneficiary: Signer<'info>,
+
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        mut,
+        has_one = manager,
+        has_one = beneficiary @ ErrorCode::Unauthorized,
+        seeds = [b"vesting", manager.key().as_ref(), beneficiary.key().as_ref()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, VestingGrant>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting_vault", manager.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    pub grant_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, constraint = destination.mint == manager.mint)]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        mut,
+        has_one = manager,
+        seeds = [b"vesting", manager.key().as_ref(), grant.beneficiary.as_ref()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, VestingGrant>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting_vault", manager.key().as_ref(), grant.beneficiary.as_ref()],
+        bump
+    )]
+    pub grant_vault: Account<'info, TokenAccount>,
 
     #[account(
         mut,
         seeds = [b"reserve", manager.mint.as_ref()],
-        bump,
-        constraint = token_vault.key() == manager.raydium_pool
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateApproverSet<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ApproverSet::LEN,
+        seeds = [b"approver_set", manager.key().as_ref()],
+        bump
+    )]
+    pub approver_set: Account<'info, ApproverSet>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        mut,
+        has_one = manager,
+        seeds = [b"approver_set", manager.key().as_ref()],
+        bump = approver_set.bump
+    )]
+    pub approver_set: Account<'info, ApproverSet>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::LEN,
+        seeds = [
+            b"proposal",
+            manager.key().as_ref(),
+            &approver_set.proposal_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    pub approver: Signer<'info>,
+
+    #[account(
+        seeds = [b"approver_set", proposal.manager.as_ref()],
+        bump = approver_set.bump
+    )]
+    pub approver_set: Account<'info, ApproverSet>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.manager.as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    pub executor: Signer<'info>,
+
+    #[account(mut)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        mut,
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    #[account(
+        mut,
+        has_one = manager,
+        seeds = [b"approver_set", manager.key().as_ref()],
+        bump = approver_set.bump
+    )]
+    pub approver_set: Account<'info, ApproverSet>,
+
+    #[account(
+        mut,
+        has_one = manager,
+        seeds = [b"proposal", manager.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve", manager.mint.as_ref()],
+        bump
     )]
     pub token_vault: Account<'info, TokenAccount>,
 
-    #[account(
-        mut,
-        seeds = [b"treasury", manager.mint.as_ref()],
-        bump
-    )]
-    /// CHECK: Treasury account for SOL
-    pub treasury: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"treasury", manager.mint.as_ref()],
+        bump
+    )]
+    /// CHECK: Treasury account for SOL
+    pub treasury: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseLaunch<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+}
+
+#[derive(Accounts)]
+pub struct PauseTrading<'info> {
+    /// Authority or guardian.
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = manager.can_pause(&operator.key()) @ ErrorCode::Unauthorized
+    )]
+    pub manager: Account<'info, ABCManager>,
+
+    #[account(
+        seeds = [b"launch_config", manager.mint.as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = manager.pending_authority == new_authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub manager: Account<'info, ABCManager>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateManager<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"abc_manager", mint.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Legacy layout can't deserialize as ABCManager; validated in instruction
+    pub manager: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = LaunchConfig::LEN,
+        seeds = [b"launch_config", mint.key().as_ref()],
+        bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(Default)]
+pub struct ABCManager {
+    pub version: u8,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub launch_slot: u64,
+    /// Takes the byte `is_launched` used to, `true` reading as Open.
+    pub phase: LaunchPhase,
+    pub captured_sol: u64,
+    pub reserve_tokens: u64,
+    pub bump: u8,
+    pub last_blocked_address: Pubkey,
+    pub raydium_pool: Pubkey,
+    pub pending_authority: Pubkey,
+    /// Part of `captured_sol` paid back through `claim_refund`, penalties
+    /// excluded.
+    pub refunded_sol: u64,
+    /// May pause and resume trading alongside the authority.
+    pub guardian: Pubkey,
+    pub paused_slot: u64,
+    /// Slot a pause lifts by itself, or 0 if it only ends on `resume_trading`.
+    pub pause_expires_slot: u64,
+    /// Commit-reveal and auction escrow held in the treasury and not yet
+    /// settled.
+    pub escrowed_sol: u64,
+    /// Total SOL revealed, the denominator of the pro-rata fill.
+    pub revealed_sol: u64,
+    /// Bonding-curve progress: tokens sold from the reserve and SOL taken.
+    pub curve_tokens_sold: u64,
+    pub curve_sol_raised: u64,
+    /// Part of `captured_sol` kept as refund penalties.
+    pub refund_penalty_sol: u64,
+    /// SOL moved out of the treasury through `withdraw_treasury`.
+    pub total_withdrawn: u64,
+    /// M-of-N approvers fund movements and config changes need, if set.
+    pub approver_set: Pubkey,
+    /// Reserve paid out under `LaunchConfig::reserve_vesting`.
+    pub reserve_released: u64,
+    /// Reserve escrowed in vesting grants, less what revocations returned.
+    pub reserve_granted: u64,
+    /// Zeroed padding that new fields are carved out of, so they don't need
+    /// another realloc.
+    pub reserved: [u64; 13],
+}
+
+/// ABCManager layout from before `version` was introduced.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct LegacyABCManager {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub launch_slot: u64,
+    pub is_launched: bool,
+    pub captured_sol: u64,
+    pub reserve_tokens: u64,
+    pub bump: u8,
+    pub last_blocked_address: Pubkey,
+    pub raydium_pool: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+/// Per-mint launch parameters, fixed at `initialize` instead of compiled in.
+#[account]
+#[derive(Default)]
+pub struct LaunchConfig {
+    pub mint: Pubkey,
+    pub monitoring_blocks: u64,
+    pub min_trade_sol: u64,
+    pub max_trade_sol: u64,
+    pub max_price_impact_bps: u64,
+    pub slippage_tolerance_bps: u64,
+    pub dex: DexKind,
+    pub dex_program: Pubkey,
+    pub config_timelock_slots: u64,
+    pub pool_fee_bps: u64,
+    /// Share of a monitored purchase kept by the treasury on refund.
+    pub refund_penalty_bps: u64,
+    /// Fair-launch mode when non-zero: the most SOL one wallet may spend per
+    /// `wallet_cap_window_slots` during the monitoring period.
+    pub wallet_cap_sol: u64,
+    pub wallet_cap_window_slots: u64,
+    /// Allowlist for `presale_buy`; all zeroes skips the presale.
+    pub presale_merkle_root: [u8; 32],
+    /// Presale price in token base units per whole SOL.
+    pub presale_tokens_per_sol: u64,
+    /// Longest a pause lasts before trading resumes by itself; 0 for no limit.
+    pub max_pause_slots: u64,
+    /// Commit-reveal mode when non-zero: tokens split pro-rata between
+    /// buyers who reveal within `reveal_window_slots` of the window closing.
+    pub commit_allocation_tokens: u64,
+    pub reveal_window_slots: u64,
+    /// Batch-auction mode when non-zero: tokens sold at one clearing price
+    /// to bids placed during the monitoring window.
+    pub auction_tokens: u64,
+    /// Lowest price on the bid ladder, in lamports per PRICE_UNIT.
+    pub auction_floor_price: u64,
+    pub auction_tick_size: u64,
+    /// Bonding-curve mode when enabled: the reserve sells along the curve
+    /// until `graduation_sol` is raised, then `graduate` seeds the pool.
+    pub curve: BondingCurve,
+    pub graduation_sol: u64,
+    /// How much of the reserve `release_reserve` can pay out, and when.
+    pub reserve_vesting: VestingSchedule,
+    /// When LP put in `lp_vault` by `lock_liquidity` or at `initialize` can
+    /// be taken out again. Graduation LP is instead locked for
+    /// `lp_lock_duration` from the moment the curve graduates.
+    pub lp_lock_clock: VestingClock,
+    pub lp_lock_until: u64,
+    pub lp_lock_duration: u64,
+    pub bump: u8,
+}
+
+/// Trading limits and pool address waiting out the config timelock.
+#[account]
+#[derive(Default)]
+pub struct PendingConfigUpdate {
+    pub mint: Pubkey,
+    pub update: ConfigUpdate,
+    pub proposed_slot: u64,
+    pub eta_slot: u64,
+    pub bump: u8,
+}
+
+/// SOL a buyer sent during the monitoring window, refundable once it closes.
+#[account]
+#[derive(Default)]
+pub struct MonitoredPurchase {
+    pub manager: Pubkey,
+    pub buyer: Pubkey,
+    pub sol_amount: u64,
+    pub last_slot: u64,
+    pub bump: u8,
+}
+
+/// Every flagged buy by one wallet against one launch.
+#[account]
+#[derive(Default)]
+pub struct FlaggedWallet {
+    pub manager: Pubkey,
+    pub wallet: Pubkey,
+    pub flag_count: u64,
+    pub total_sol: u64,
+    pub first_slot: u64,
+    pub last_slot: u64,
+    /// Set by the authority for a false positive; kept rather than closed.
+    pub cleared: bool,
+    pub cleared_slot: u64,
+    pub bump: u8,
+}
+
+/// M-of-N approvers for a manager's fund movements and config changes.
+#[account]
+#[derive(Default)]
+pub struct ApproverSet {
+    pub manager: Pubkey,
+    pub approvers: Vec<Pubkey>,
+    pub threshold: u8,
+    /// Bumped on every change to the set.
+    pub generation: u64,
+    /// Id of the next proposal.
+    pub proposal_count: u64,
+    pub bump: u8,
+}
+
+/// An action waiting on approvals. Bit `i` of `approvals` is approver `i`
+/// of the set's `generation`.
+#[account]
+#[derive(Default)]
+pub struct Proposal {
+    pub manager: Pubkey,
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    pub generation: u64,
+    pub approvals: u16,
+    pub created_slot: u64,
+    pub expires_slot: u64,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+/// Batch-auction order book: SOL bid at each tick of the price ladder.
+#[account]
+#[derive(Default)]
+pub struct Auction {
+    pub manager: Pubkey,
+    pub demand_sol: [u64; AUCTION_PRICE_TICKS],
+    pub total_bid_sol: u64,
+    pub cleared: bool,
+    pub clearing_tick: u8,
+    /// Tokens shared by the bids on the clearing tick.
+    pub marginal_tokens: u64,
+    pub bump: u8,
+}
+
+/// SOL escrowed by one bidder and the highest tick they accept.
+#[account]
+#[derive(Default)]
+pub struct Bid {
+    pub manager: Pubkey,
+    pub bidder: Pubkey,
+    pub sol_amount: u64,
+    pub tick: u8,
+    pub bump: u8,
+}
+
+/// LP tokens held in the launch's `lp_vault` until `lock_until`.
+#[account]
+#[derive(Default)]
+pub struct LiquidityLock {
+    pub manager: Pubkey,
+    pub lp_mint: Pubkey,
+    /// LP deposited into the lock; 0 once unlocked.
+    pub amount: u64,
+    pub clock: VestingClock,
+    pub lock_until: u64,
+    pub bump: u8,
+}
+
+/// Reserve tokens escrowed in the grant's vault for one beneficiary.
+#[account]
+#[derive(Default)]
+pub struct VestingGrant {
+    pub manager: Pubkey,
+    pub beneficiary: Pubkey,
+    pub schedule: VestingSchedule,
+    pub claimed: u64,
+    pub revocable: bool,
+    /// When the authority revoked the grant, in the schedule's clock.
+    pub revoked_at: Option<u64>,
+    pub bump: u8,
+}
+
+/// A commit-reveal buy: escrowed SOL behind a hash of the amount to spend.
+#[account]
+#[derive(Default)]
+pub struct PurchaseCommitment {
+    pub manager: Pubkey,
+    pub buyer: Pubkey,
+    pub commitment: [u8; 32],
+    pub escrow_sol: u64,
+    /// 0 until revealed.
+    pub revealed_sol: u64,
+    pub bump: u8,
+}
+
+/// SOL an allowlisted wallet has spent in the presale.
+#[account]
+#[derive(Default)]
+pub struct PresalePurchase {
+    pub manager: Pubkey,
+    pub buyer: Pubkey,
+    pub allocation: u64,
+    pub purchased_sol: u64,
+    pub bump: u8,
+}
+
+/// A wallet's fair-launch buys, for enforcing the per-window cap.
+#[account]
+#[derive(Default)]
+pub struct WalletPurchases {
+    pub manager: Pubkey,
+    pub wallet: Pubkey,
+    pub window_start_slot: u64,
+    pub window_sol: u64,
+    pub total_sol: u64,
+    pub bump: u8,
+}
+
+/// Built-in pool state. The reserves are the pool's share of the vault and
+/// treasury balances.
+#[account]
+#[derive(Default)]
+pub struct NativePool {
+    pub mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub token_vault: Pubkey,
+    pub sol_reserve: u64,
+    pub token_reserve: u64,
+    pub bump: u8,
+    pub treasury_bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LaunchConfigParams {
+    pub monitoring_blocks: u64,
+    pub min_trade_sol: u64,
+    pub max_trade_sol: u64,
+    pub max_price_impact_bps: u64,
+    pub slippage_tolerance_bps: u64,
+    pub config_timelock_slots: u64,
+    pub pool_fee_bps: u64,
+    pub refund_penalty_bps: u64,
+    pub wallet_cap_sol: u64,
+    pub wallet_cap_window_slots: u64,
+    pub presale_merkle_root: [u8; 32],
+    pub presale_tokens_per_sol: u64,
+    pub max_pause_slots: u64,
+    pub commit_allocation_tokens: u64,
+    pub reveal_window_slots: u64,
+    pub auction_tokens: u64,
+    pub auction_floor_price: u64,
+    pub auction_tick_size: u64,
+    pub curve: BondingCurve,
+    pub graduation_sol: u64,
+    pub reserve_vesting: VestingSchedule,
+    pub lp_lock_clock: VestingClock,
+    pub lp_lock_until: u64,
+    pub lp_lock_duration: u64,
+    pub dex: DexKind,
+    pub dex_program: Pubkey,
+}
+
+/// Venue a launch trades against. `dex_program` is the matching program id,
+/// or this program for the built-in pool.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DexKind {
+    RaydiumAmmV4,
+    ConstantProduct,
+    Whirlpool,
+}
+
+impl Default for DexKind {
+    fn default() -> Self {
+        DexKind::RaydiumAmmV4
+    }
+}
+
+/// Where a launch is in its lifecycle. Presale only admits allowlisted
+/// `presale_buy` and BondingCurve only `curve_buy`; Monitoring handles early
+/// buys; Open allows everything.
+///
+/// Variant order is the on-chain encoding. Open stays at 1 so managers
+/// written with `is_launched = true` read as open.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LaunchPhase {
+    Created,
+    Open,
+    Scheduled,
+    Presale,
+    Monitoring,
+    Paused,
+    Closed,
+    BondingCurve,
+}
+
+impl Default for LaunchPhase {
+    fn default() -> Self {
+        LaunchPhase::Created
+    }
+}
+
+impl LaunchPhase {
+    pub fn can_transition_to(self, next: LaunchPhase) -> bool {
+        use LaunchPhase::*;
+
+        // A curve only leaves through `graduate`, which moves the SOL its
+        // buyers paid into the pool; closing it would strand that SOL
+        matches!(
+            (self, next),
+            (Created | Presale | Scheduled, Scheduled)
+                | (Scheduled, Monitoring | Open)
+                | (Monitoring | BondingCurve, Open)
+                | (Monitoring | Open, Paused)
+                | (Paused, Monitoring | Open)
+                | (
+                    Created | Scheduled | Presale | Monitoring | Open | Paused,
+                    Closed
+                )
+        )
+    }
+}
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub clock: Sysvar<'info, Clock>,
+/// Shape of a bonding curve. Prices rise per block of `step_tokens` sold:
+/// by `growth` lamports on a linear curve, by `growth` bps on an
+/// exponential one.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveKind {
+    Disabled,
+    Linear,
+    Exponential,
 }
 
-#[account]
-#[derive(Default)]
-pub struct ABCManager {
-    pub authority: Pubkey,
-    pub mint: Pubkey,
-    pub launch_slot: u64,
-    pub is_launched: bool,
-    pub captured_sol: u64,
-    pub reserve_tokens: u64,
-    pub bump: u8,
-    pub last_blocked_address: Pubkey,
+impl Default for CurveKind {
+    fn default() -> Self {
+        CurveKind::Disabled
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BondingCurve {
+    pub kind: CurveKind,
+    /// Price of the first step, in lamports per PRICE_UNIT.
+    pub start_price: u64,
+    pub growth: u64,
+    pub step_tokens: u64,
+}
+
+/// What a vesting schedule's `start`, `cliff` and `duration`, or an LP
+/// lock's expiry, count in.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VestingClock {
+    Slot,
+    UnixTimestamp,
+}
+
+impl Default for VestingClock {
+    fn default() -> Self {
+        VestingClock::Slot
+    }
+}
+
+impl VestingClock {
+    pub fn now(&self, clock: &Clock) -> u64 {
+        match self {
+            VestingClock::Slot => clock.slot,
+            VestingClock::UnixTimestamp => clock.unix_timestamp.max(0) as u64,
+        }
+    }
+}
+
+/// `amount` tokens vesting linearly from `start` over `duration`, none of
+/// them before `start + cliff`. A zero amount releases nothing.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VestingSchedule {
+    pub amount: u64,
+    pub clock: VestingClock,
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64,
+}
+
+impl VestingSchedule {
+    pub const LEN: usize = 8 // amount
+        + 1 // clock
+        + 8 // start
+        + 8 // cliff
+        + 8; // duration
+
+    pub fn validate(&self) -> Result<()> {
+        if self.amount > 0 {
+            require!(
+                self.duration > 0 && self.cliff <= self.duration,
+                ErrorCode::InvalidVestingSchedule
+            );
+        }
+        Ok(())
+    }
+
+    pub fn now(&self, clock: &Clock) -> u64 {
+        self.clock.now(clock)
+    }
+
+    /// Total vested by `now`, released or not.
+    pub fn vested_at(&self, now: u64) -> u64 {
+        let elapsed = now.saturating_sub(self.start);
+        if self.amount == 0 || now < self.start || elapsed < self.cliff {
+            return 0;
+        }
+        if elapsed >= self.duration {
+            return self.amount;
+        }
+
+        (self.amount as u128 * elapsed as u128 / self.duration as u128) as u64
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwapDirection {
+    SolToToken,
+    TokenToSol,
+}
+
+/// What an approved proposal does. `destination` is a wallet for
+/// withdrawals and a token account for reserve releases and LP unlocks.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ProposalAction {
+    WithdrawTreasury { amount: u64, destination: Pubkey },
+    ReleaseReserve { amount: u64, destination: Pubkey },
+    UpdateConfig(ConfigUpdate),
+    SetApprovers { approvers: Vec<Pubkey>, threshold: u8 },
+    CloseLaunch,
+    UnlockLiquidity { destination: Pubkey },
+}
+
+impl Default for ProposalAction {
+    fn default() -> Self {
+        ProposalAction::WithdrawTreasury {
+            amount: 0,
+            destination: Pubkey::default(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfigUpdate {
+    pub min_trade_sol: u64,
+    pub max_trade_sol: u64,
+    pub max_price_impact_bps: u64,
+    pub slippage_tolerance_bps: u64,
     pub raydium_pool: Pubkey,
 }
 
@@ -486,6 +7159,330 @@ pub struct TradeExecuted {
     pub slot: u64,
 }
 
+#[event]
+pub struct ConfigUpdateProposed {
+    pub mint: Pubkey,
+    pub update: ConfigUpdate,
+    pub proposed_slot: u64,
+    pub eta_slot: u64,
+}
+
+#[event]
+pub struct ConfigUpdateApplied {
+    pub mint: Pubkey,
+    pub update: ConfigUpdate,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ConfigUpdateCancelled {
+    pub mint: Pubkey,
+    pub eta_slot: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub mint: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ManagerMigrated {
+    pub mint: Pubkey,
+    pub previous_len: u64,
+    pub version: u8,
+    pub slot: u64,
+}
+
+#[event]
+pub struct PresalePurchased {
+    pub mint: Pubkey,
+    pub buyer: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct GuardianUpdated {
+    pub mint: Pubkey,
+    pub previous_guardian: Pubkey,
+    pub guardian: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct TradingPaused {
+    pub mint: Pubkey,
+    pub operator: Pubkey,
+    pub reason: u8,
+    pub slot: u64,
+    /// 0 when the pause has no time limit.
+    pub expires_slot: u64,
+}
+
+#[event]
+pub struct TradingResumed {
+    pub mint: Pubkey,
+    pub operator: Pubkey,
+    pub reason: u8,
+    pub phase: LaunchPhase,
+    pub slot: u64,
+}
+
+#[event]
+pub struct LaunchScheduled {
+    pub mint: Pubkey,
+    pub from: LaunchPhase,
+    pub start_slot: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct LaunchPhaseChanged {
+    pub mint: Pubkey,
+    pub from: LaunchPhase,
+    pub to: LaunchPhase,
+    pub slot: u64,
+}
+
+#[event]
+pub struct FlaggedWalletCleared {
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+    pub flag_count: u64,
+    pub total_sol: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct TreasuryWithdrawal {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ApproversUpdated {
+    pub mint: Pubkey,
+    pub approvers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub generation: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub mint: Pubkey,
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    pub expires_slot: u64,
+}
+
+#[event]
+pub struct ProposalApproved {
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub approver: Pubkey,
+    pub approvals: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub executor: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ReserveReleased {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub total_released: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct VestingCreated {
+    pub mint: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub start: u64,
+    pub cliff: u64,
+    pub end: u64,
+    pub revocable: bool,
+}
+
+#[event]
+pub struct VestingClaimed {
+    pub mint: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub total_claimed: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct VestingRevoked {
+    pub mint: Pubkey,
+    pub beneficiary: Pubkey,
+    pub returned: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct RefundClaimed {
+    pub mint: Pubkey,
+    pub buyer: Pubkey,
+    pub sol_refunded: u64,
+    pub penalty: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct PurchaseCommitted {
+    pub mint: Pubkey,
+    pub buyer: Pubkey,
+    pub escrow_sol: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct PurchaseRevealed {
+    pub mint: Pubkey,
+    pub buyer: Pubkey,
+    pub sol_amount: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct CommitmentSettled {
+    pub mint: Pubkey,
+    pub buyer: Pubkey,
+    pub sol_paid: u64,
+    pub token_amount: u64,
+    pub sol_refunded: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct BidPlaced {
+    pub mint: Pubkey,
+    pub bidder: Pubkey,
+    pub sol_amount: u64,
+    pub price: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AuctionCleared {
+    pub mint: Pubkey,
+    pub clearing_price: u64,
+    pub total_bid_sol: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct BidSettled {
+    pub mint: Pubkey,
+    pub bidder: Pubkey,
+    pub token_amount: u64,
+    pub sol_paid: u64,
+    pub sol_refunded: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct CurvePurchased {
+    pub mint: Pubkey,
+    pub buyer: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub sol_raised: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct CurveGraduated {
+    pub mint: Pubkey,
+    pub pool: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub lp_amount: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct LiquidityLocked {
+    pub mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub amount: u64,
+    pub lock_until: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct LiquidityLockExtended {
+    pub mint: Pubkey,
+    pub lock_until: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct LiquidityUnlocked {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct NativePoolCreated {
+    pub mint: Pubkey,
+    pub pool: Pubkey,
+    pub lp_mint: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct LiquidityAdded {
+    pub mint: Pubkey,
+    pub provider: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub lp_amount: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct LiquidityRemoved {
+    pub mint: Pubkey,
+    pub provider: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub lp_amount: u64,
+    pub slot: u64,
+}
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("Monitoring period has ended")]
@@ -514,6 +7511,176 @@ pub enum ErrorCode {
 
     #[msg("Slippage tolerance exceeded")]
     SlippageExceeded,
+
+    #[msg("Launch configuration out of bounds")]
+    InvalidLaunchConfig,
+
+    #[msg("Signer is not the manager authority")]
+    Unauthorized,
+
+    #[msg("Timelock has not expired")]
+    TimelockNotExpired,
+
+    #[msg("Invalid authority")]
+    InvalidAuthority,
+
+    #[msg("Account is not an ABCManager for this mint")]
+    InvalidManagerAccount,
+
+    #[msg("Manager account already uses the current layout")]
+    AlreadyMigrated,
+
+    #[msg("Pool has insufficient liquidity")]
+    InsufficientLiquidity,
+
+    #[msg("Price impact exceeds the configured limit")]
+    PriceImpactTooHigh,
+
+    #[msg("Missing pool accounts")]
+    MissingPoolAccounts,
+
+    #[msg("Invalid wrapped SOL account")]
+    InvalidSolAccount,
+
+    #[msg("DEX not supported for this launch")]
+    UnsupportedDex,
+
+    #[msg("Invalid DEX program")]
+    InvalidDexProgram,
+
+    #[msg("Pool account does not match the launch")]
+    InvalidPoolAccount,
+
+    #[msg("Monitoring period is still active")]
+    MonitoringPeriodActive,
+
+    #[msg("Wallet has already been cleared")]
+    WalletAlreadyCleared,
+
+    #[msg("Purchase exceeds the per-wallet cap for this window")]
+    WalletCapExceeded,
+
+    #[msg("Presale is not active")]
+    PresaleNotActive,
+
+    #[msg("Merkle proof does not match the presale allowlist")]
+    InvalidMerkleProof,
+
+    #[msg("Purchase exceeds the presale allocation")]
+    PresaleAllocationExceeded,
+
+    #[msg("Launch phase cannot change this way")]
+    InvalidPhaseTransition,
+
+    #[msg("Launch slot is in the past")]
+    InvalidLaunchSlot,
+
+    #[msg("Trading is paused")]
+    TradingPaused,
+
+    #[msg("Trading is not paused")]
+    TradingNotPaused,
+
+    #[msg("Launch is not in commit-reveal mode")]
+    CommitRevealDisabled,
+
+    #[msg("Commit window is closed")]
+    CommitWindowClosed,
+
+    #[msg("Reveal window is closed")]
+    RevealWindowClosed,
+
+    #[msg("Reveal window has not ended")]
+    RevealWindowActive,
+
+    #[msg("Revealed amount does not match the commitment")]
+    InvalidCommitment,
+
+    #[msg("Commitment has already been revealed")]
+    AlreadyRevealed,
+
+    #[msg("Launch is not in batch-auction mode")]
+    AuctionDisabled,
+
+    #[msg("Auction is not taking bids")]
+    AuctionClosed,
+
+    #[msg("Auction is still taking bids")]
+    AuctionActive,
+
+    #[msg("Bid price is off the auction's price ladder")]
+    InvalidBidPrice,
+
+    #[msg("Bid accounts do not match")]
+    InvalidBidAccount,
+
+    #[msg("Bonding curve is not active")]
+    CurveNotActive,
+
+    #[msg("Bonding curve has reached its graduation target")]
+    CurveComplete,
+
+    #[msg("Bonding curve has not reached its graduation target")]
+    GraduationTargetNotReached,
+
+    #[msg("Withdrawal exceeds the treasury's free balance")]
+    InsufficientTreasuryBalance,
+
+    #[msg("Destination account does not match the withdrawal")]
+    InvalidWithdrawalDestination,
+
+    #[msg("Needs a proposal approved by the approver set")]
+    ApprovalRequired,
+
+    #[msg("Approver set must be distinct keys with a threshold no larger than their count")]
+    InvalidApproverSet,
+
+    #[msg("Signer is not in the approver set")]
+    NotApprover,
+
+    #[msg("Invalid proposal")]
+    InvalidProposal,
+
+    #[msg("Proposal already approved by this approver")]
+    AlreadyApproved,
+
+    #[msg("Proposal does not have enough approvals")]
+    InsufficientApprovals,
+
+    #[msg("Proposal has expired")]
+    ProposalExpired,
+
+    #[msg("Proposal has already been executed")]
+    ProposalAlreadyExecuted,
+
+    #[msg("Approver set changed since the proposal was made")]
+    ProposalStale,
+
+    #[msg("Release exceeds the vested reserve")]
+    ReserveNotVested,
+
+    #[msg("Vesting schedule is invalid")]
+    InvalidVestingSchedule,
+
+    #[msg("Nothing has vested since the last claim")]
+    NothingToClaim,
+
+    #[msg("Vesting grant cannot be revoked")]
+    VestingNotRevocable,
+
+    #[msg("Vesting grant has already been revoked")]
+    VestingAlreadyRevoked,
+
+    #[msg("LP mint does not belong to the launch's pool")]
+    InvalidLpMint,
+
+    #[msg("LP lock must end in the future and can only be extended")]
+    InvalidLiquidityLock,
+
+    #[msg("Liquidity is still locked")]
+    LiquidityStillLocked,
+    #[msg("A config update is still queued")]
+    ConfigUpdatePending,
 }
 
 // Raydium pool state validation
@@ -549,3 +7716,1148 @@ impl<'info> ValidateRaydiumPool<'info> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_launch_config_bounds() {
+        assert!(LaunchConfigParams::default().validate().is_ok());
+
+        let too_long = LaunchConfigParams {
+            monitoring_blocks: MAX_MONITORING_BLOCKS + 1,
+            ..Default::default()
+        };
+        assert!(too_long.validate().is_err());
+
+        let inverted = LaunchConfigParams {
+            min_trade_sol: DEFAULT_MAX_TRADE_SOL + 1,
+            ..Default::default()
+        };
+        assert!(inverted.validate().is_err());
+
+        let no_dex = LaunchConfigParams {
+            dex_program: Pubkey::default(),
+            ..Default::default()
+        };
+        assert!(no_dex.validate().is_err());
+
+        // The built-in pool must point at this program, external venues must not
+        let builtin = LaunchConfigParams {
+            dex: DexKind::ConstantProduct,
+            dex_program: crate::ID,
+            ..Default::default()
+        };
+        assert!(builtin.validate().is_ok());
+
+        let builtin_elsewhere = LaunchConfigParams {
+            dex: DexKind::ConstantProduct,
+            ..Default::default()
+        };
+        assert!(builtin_elsewhere.validate().is_err());
+
+        let whirlpool_on_self = LaunchConfigParams {
+            dex: DexKind::Whirlpool,
+            dex_program: crate::ID,
+            ..Default::default()
+        };
+        assert!(whirlpool_on_self.validate().is_err());
+
+        let no_timelock = LaunchConfigParams {
+            config_timelock_slots: MIN_CONFIG_TIMELOCK_SLOTS - 1,
+            ..Default::default()
+        };
+        assert!(no_timelock.validate().is_err());
+
+        let commit_reveal = LaunchConfigParams {
+            commit_allocation_tokens: 1_000_000,
+            reveal_window_slots: 20,
+            ..Default::default()
+        };
+        assert!(commit_reveal.validate().is_ok());
+
+        let commit_reveal_with_caps = LaunchConfigParams {
+            wallet_cap_sol: DEFAULT_MAX_TRADE_SOL,
+            wallet_cap_window_slots: 2,
+            ..commit_reveal.clone()
+        };
+        assert!(commit_reveal_with_caps.validate().is_err());
+
+        let no_reveal_window = LaunchConfigParams {
+            reveal_window_slots: 0,
+            ..commit_reveal
+        };
+        assert!(no_reveal_window.validate().is_err());
+
+        let auction = LaunchConfigParams {
+            auction_tokens: 1_000_000,
+            auction_floor_price: 1_000,
+            auction_tick_size: 100,
+            ..Default::default()
+        };
+        assert!(auction.validate().is_ok());
+
+        let auction_and_commit_reveal = LaunchConfigParams {
+            commit_allocation_tokens: 1_000_000,
+            reveal_window_slots: 20,
+            ..auction.clone()
+        };
+        assert!(auction_and_commit_reveal.validate().is_err());
+
+        let free_auction = LaunchConfigParams {
+            auction_floor_price: 0,
+            ..auction
+        };
+        assert!(free_auction.validate().is_err());
+
+        let curve = LaunchConfigParams {
+            curve: BondingCurve {
+                kind: CurveKind::Linear,
+                start_price: 100_000_000,
+                growth: 100_000_000,
+                step_tokens: 10 * PRICE_UNIT,
+            },
+            graduation_sol: 10_000_000_000,
+            dex: DexKind::ConstantProduct,
+            dex_program: crate::ID,
+            ..Default::default()
+        };
+        assert!(curve.validate().is_ok());
+
+        // The whole linear curve only raises 32,896 SOL
+        let unreachable_graduation = LaunchConfigParams {
+            graduation_sol: 40_000_000_000_000,
+            ..curve.clone()
+        };
+        assert!(unreachable_graduation.validate().is_err());
+
+        let curve_on_raydium = LaunchConfigParams {
+            dex: DexKind::RaydiumAmmV4,
+            dex_program: Pubkey::from_str(RAYDIUM_PROGRAM_ID).unwrap(),
+            ..curve.clone()
+        };
+        assert!(curve_on_raydium.validate().is_err());
+
+        let steep_curve = LaunchConfigParams {
+            curve: BondingCurve {
+                kind: CurveKind::Exponential,
+                growth: MAX_CURVE_GROWTH_BPS + 1,
+                ..curve.curve
+            },
+            ..curve.clone()
+        };
+        assert!(steep_curve.validate().is_err());
+
+        // Steps this small cost next to nothing, so walking the curve runs
+        // its doubling price past u64 long before the last step
+        let overflowing_curve = LaunchConfigParams {
+            curve: BondingCurve {
+                kind: CurveKind::Exponential,
+                growth: MAX_CURVE_GROWTH_BPS,
+                step_tokens: 1,
+                ..curve.curve
+            },
+            graduation_sol: 1,
+            ..curve.clone()
+        };
+        assert!(overflowing_curve.validate().is_err());
+
+        let long_pause = LaunchConfigParams {
+            max_pause_slots: MAX_PAUSE_SLOTS + 1,
+            ..Default::default()
+        };
+        assert!(long_pause.validate().is_err());
+
+        let fair_launch = LaunchConfigParams {
+            wallet_cap_sol: DEFAULT_MAX_TRADE_SOL,
+            wallet_cap_window_slots: 2,
+            ..Default::default()
+        };
+        assert!(fair_launch.validate().is_ok());
+
+        let no_window = LaunchConfigParams {
+            wallet_cap_window_slots: 0,
+            ..fair_launch.clone()
+        };
+        assert!(no_window.validate().is_err());
+
+        let cap_below_min = LaunchConfigParams {
+            wallet_cap_sol: DEFAULT_MIN_TRADE_SOL - 1,
+            ..fair_launch
+        };
+        assert!(cap_below_min.validate().is_err());
+
+        assert_eq!(LaunchConfigParams::default().initial_phase(), LaunchPhase::Created);
+        let presale = LaunchConfigParams {
+            presale_merkle_root: [1; 32],
+            presale_tokens_per_sol: 1_000,
+            ..Default::default()
+        };
+        assert!(presale.validate().is_ok());
+        assert_eq!(presale.initial_phase(), LaunchPhase::Presale);
+
+        let unpriced_presale = LaunchConfigParams {
+            presale_tokens_per_sol: 0,
+            ..presale
+        };
+        assert!(unpriced_presale.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_update_bounds() {
+        let update = ConfigUpdate {
+            min_trade_sol: DEFAULT_MIN_TRADE_SOL,
+            max_trade_sol: DEFAULT_MAX_TRADE_SOL * 2,
+            max_price_impact_bps: DEFAULT_MAX_PRICE_IMPACT_BPS,
+            slippage_tolerance_bps: DEFAULT_SLIPPAGE_TOLERANCE_BPS,
+            raydium_pool: Pubkey::new_unique(),
+        };
+        assert!(update.validate().is_ok());
+
+        let no_pool = ConfigUpdate {
+            raydium_pool: Pubkey::default(),
+            ..update.clone()
+        };
+        assert!(no_pool.validate().is_err());
+
+        let no_impact_limit = ConfigUpdate {
+            max_price_impact_bps: 0,
+            ..update.clone()
+        };
+        assert!(no_impact_limit.validate().is_err());
+
+        // The built-in pool can't be repointed, external venues can
+        let mint = Pubkey::new_unique();
+        let manager = ABCManager {
+            mint,
+            raydium_pool: native::pool_address(&mint),
+            ..Default::default()
+        };
+        let native_config = LaunchConfig {
+            dex: DexKind::ConstantProduct,
+            ..Default::default()
+        };
+        let same_pool = ConfigUpdate {
+            raydium_pool: manager.raydium_pool,
+            ..update.clone()
+        };
+        assert!(native_config.validate_update(&same_pool, &manager).is_ok());
+        assert!(native_config.validate_update(&update, &manager).is_err());
+        let raydium_config = LaunchConfig {
+            dex: DexKind::RaydiumAmmV4,
+            ..Default::default()
+        };
+        assert!(raydium_config.validate_update(&update, &manager).is_ok());
+
+        // A minimum trade above the wallet cap would block every early buy
+        let capped_config = LaunchConfig {
+            wallet_cap_sol: DEFAULT_MIN_TRADE_SOL,
+            ..raydium_config
+        };
+        assert!(capped_config.validate_update(&update, &manager).is_ok());
+        let above_cap = ConfigUpdate {
+            min_trade_sol: DEFAULT_MIN_TRADE_SOL + 1,
+            ..update
+        };
+        assert!(capped_config.validate_update(&above_cap, &manager).is_err());
+    }
+
+    #[test]
+    fn test_transfer_authority_clears_pending() {
+        let original = Pubkey::new_unique();
+        let successor = Pubkey::new_unique();
+        let mut manager = ABCManager {
+            authority: original,
+            pending_authority: successor,
+            ..Default::default()
+        };
+
+        assert_eq!(manager.transfer_authority(successor), original);
+        assert_eq!(manager.authority, successor);
+        assert_eq!(manager.pending_authority, Pubkey::default());
+    }
+
+    #[test]
+    fn test_account_lengths_match_layout() {
+        let manager = ABCManager::default().try_to_vec().unwrap();
+        assert_eq!(8 + manager.len(), ABCManager::LEN);
+
+        let config = LaunchConfig::default().try_to_vec().unwrap();
+        assert_eq!(8 + config.len(), LaunchConfig::LEN);
+
+        let pending = PendingConfigUpdate::default().try_to_vec().unwrap();
+        assert_eq!(8 + pending.len(), PendingConfigUpdate::LEN);
+
+        let purchase = MonitoredPurchase::default().try_to_vec().unwrap();
+        assert_eq!(8 + purchase.len(), MonitoredPurchase::LEN);
+
+        let flagged = FlaggedWallet::default().try_to_vec().unwrap();
+        assert_eq!(8 + flagged.len(), FlaggedWallet::LEN);
+
+        let auction = Auction::default().try_to_vec().unwrap();
+        assert_eq!(8 + auction.len(), Auction::LEN);
+
+        let bid = Bid::default().try_to_vec().unwrap();
+        assert_eq!(8 + bid.len(), Bid::LEN);
+
+        let commitment = PurchaseCommitment::default().try_to_vec().unwrap();
+        assert_eq!(8 + commitment.len(), PurchaseCommitment::LEN);
+
+        // Variable-length accounts are sized for a full approver set
+        let full_set = vec![Pubkey::new_unique(); MAX_APPROVERS];
+        let approver_set = ApproverSet {
+            approvers: full_set.clone(),
+            ..Default::default()
+        };
+        assert_eq!(8 + approver_set.try_to_vec().unwrap().len(), ApproverSet::LEN);
+
+        let proposal = Proposal {
+            action: ProposalAction::SetApprovers {
+                approvers: full_set,
+                threshold: 1,
+            },
+            ..Default::default()
+        };
+        assert_eq!(8 + proposal.try_to_vec().unwrap().len(), Proposal::LEN);
+
+        let presale = PresalePurchase::default().try_to_vec().unwrap();
+        assert_eq!(8 + presale.len(), PresalePurchase::LEN);
+
+        let purchases = WalletPurchases::default().try_to_vec().unwrap();
+        assert_eq!(8 + purchases.len(), WalletPurchases::LEN);
+
+        let native_pool = NativePool::default().try_to_vec().unwrap();
+        assert_eq!(8 + native_pool.len(), NativePool::LEN);
+
+        let legacy = LegacyABCManager::default().try_to_vec().unwrap();
+        assert_eq!(legacy.len(), LegacyABCManager::LEN);
+    }
+
+    #[test]
+    fn test_refund_penalty() {
+        let purchase = MonitoredPurchase {
+            sol_amount: 1_000_000_001,
+            ..Default::default()
+        };
+
+        assert_eq!(purchase.refund_amounts(0).unwrap(), (1_000_000_001, 0));
+        assert_eq!(purchase.refund_amounts(250).unwrap(), (975_000_001, 25_000_000));
+        assert_eq!(
+            purchase.refund_amounts(MAX_REFUND_PENALTY_BPS).unwrap(),
+            (500_000_001, 500_000_000)
+        );
+
+        let punitive = LaunchConfigParams {
+            refund_penalty_bps: MAX_REFUND_PENALTY_BPS + 1,
+            ..Default::default()
+        };
+        assert!(punitive.validate().is_err());
+    }
+
+    #[test]
+    fn test_committed_treasury_sol() {
+        // 5 SOL captured, 3 of it claimed back less a 0.1 SOL penalty
+        let mut manager = ABCManager {
+            captured_sol: 5_000_000_000,
+            refunded_sol: 2_900_000_000,
+            refund_penalty_sol: 100_000_000,
+            escrowed_sol: 1_000_000_000,
+            curve_sol_raised: 4_000_000_000,
+            phase: LaunchPhase::Open,
+            ..Default::default()
+        };
+        assert_eq!(manager.committed_treasury_sol().unwrap(), 3_000_000_000);
+
+        // Curve SOL is spoken for until it seeds the pool
+        manager.phase = LaunchPhase::BondingCurve;
+        assert_eq!(manager.committed_treasury_sol().unwrap(), 7_000_000_000);
+    }
+
+    #[test]
+    fn test_approver_set_proposals() {
+        let approvers: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        assert!(ApproverSet::validate(&approvers, 2).is_ok());
+        assert!(ApproverSet::validate(&approvers, 0).is_err());
+        assert!(ApproverSet::validate(&approvers, 4).is_err());
+        assert!(ApproverSet::validate(&[], 1).is_err());
+        assert!(ApproverSet::validate(&[approvers[0], approvers[0]], 1).is_err());
+        assert!(ApproverSet::validate(&vec![Pubkey::new_unique(); MAX_APPROVERS + 1], 1).is_err());
+
+        let mut approver_set = ApproverSet {
+            approvers: approvers.clone(),
+            threshold: 2,
+            ..Default::default()
+        };
+        assert_eq!(approver_set.index_of(&approvers[2]), Some(2));
+        assert_eq!(approver_set.index_of(&Pubkey::new_unique()), None);
+
+        let mut proposal = Proposal {
+            expires_slot: 100,
+            ..Default::default()
+        };
+        proposal.approve(0).unwrap();
+        assert!(proposal.approve(0).is_err());
+        proposal.approve(2).unwrap();
+        assert_eq!(proposal.approval_count(), 2);
+
+        assert!(proposal.require_open(&approver_set, 99).is_ok());
+        assert!(proposal.require_open(&approver_set, 100).is_err());
+
+        // Changing the set voids what was approved under the old one
+        approver_set.replace(approvers[..2].to_vec(), 1).unwrap();
+        assert_eq!(approver_set.generation, 1);
+        assert!(proposal.require_open(&approver_set, 99).is_err());
+    }
+
+    #[test]
+    fn test_reserve_vesting() {
+        let vesting = VestingSchedule {
+            amount: 1_000,
+            clock: VestingClock::Slot,
+            start: 100,
+            cliff: 25,
+            duration: 100,
+        };
+        assert!(vesting.validate().is_ok());
+
+        assert_eq!(vesting.vested_at(0), 0);
+        assert_eq!(vesting.vested_at(124), 0);
+        // The cliff releases everything accrued up to it
+        assert_eq!(vesting.vested_at(125), 250);
+        assert_eq!(vesting.vested_at(150), 500);
+        assert_eq!(vesting.vested_at(200), 1_000);
+        assert_eq!(vesting.vested_at(u64::MAX), 1_000);
+
+        assert_eq!(VestingSchedule::default().vested_at(u64::MAX), 0);
+
+        let cliff_past_end = VestingSchedule {
+            cliff: 101,
+            ..vesting
+        };
+        assert!(cliff_past_end.validate().is_err());
+
+        let params = LaunchConfigParams {
+            reserve_vesting: VestingSchedule {
+                duration: 0,
+                cliff: 0,
+                ..vesting
+            },
+            ..Default::default()
+        };
+        assert!(params.validate().is_err());
+    }
+
+    #[test]
+    fn test_revoked_vesting_grant() {
+        let mut grant = VestingGrant {
+            schedule: VestingSchedule {
+                amount: 1_000,
+                clock: VestingClock::Slot,
+                start: 0,
+                cliff: 0,
+                duration: 100,
+            },
+            revocable: true,
+            ..Default::default()
+        };
+        let clock = Clock {
+            slot: 80,
+            ..Default::default()
+        };
+        assert_eq!(grant.vested(&clock), 800);
+
+        // Vesting stops where it was revoked
+        grant.revoked_at = Some(40);
+        assert_eq!(grant.vested(&clock), 400);
+    }
+
+    #[test]
+    fn test_liquidity_lock_expiry() {
+        let lp_lock = LiquidityLock {
+            clock: VestingClock::UnixTimestamp,
+            lock_until: 1_700_000_000,
+            ..Default::default()
+        };
+        let mut clock = Clock {
+            slot: 2_000_000_000,
+            unix_timestamp: 1_699_999_999,
+            ..Default::default()
+        };
+        // Measured in the lock's own clock, not slots
+        assert!(!lp_lock.is_expired(&clock));
+        clock.unix_timestamp = 1_700_000_000;
+        assert!(lp_lock.is_expired(&clock));
+    }
+
+    #[test]
+    fn test_lp_lock_terms() {
+        let clock = Clock {
+            slot: 1_000,
+            unix_timestamp: 1_700_000_000,
+            ..Default::default()
+        };
+
+        // Raydium launches lock their pool's LP at initialize
+        let unlocked = LaunchConfigParams::default();
+        assert!(unlocked.validate_lp_lock(&clock).is_err());
+        let short_lock = LaunchConfigParams {
+            lp_lock_until: 1_000 + MIN_LP_LOCK_SLOTS - 1,
+            ..Default::default()
+        };
+        assert!(short_lock.validate_lp_lock(&clock).is_err());
+        let locked = LaunchConfigParams {
+            lp_lock_until: 1_000 + MIN_LP_LOCK_SLOTS,
+            ..Default::default()
+        };
+        assert!(locked.validate_lp_lock(&clock).is_ok());
+        let timestamp_lock = LaunchConfigParams {
+            lp_lock_clock: VestingClock::UnixTimestamp,
+            lp_lock_until: 1_700_000_000 + MIN_LP_LOCK_SECONDS,
+            ..Default::default()
+        };
+        assert!(timestamp_lock.validate_lp_lock(&clock).is_ok());
+
+        // The built-in pool locks LP by hand through `lock_liquidity`
+        let native = LaunchConfigParams {
+            dex: DexKind::ConstantProduct,
+            dex_program: crate::ID,
+            ..Default::default()
+        };
+        assert!(native.validate_lp_lock(&clock).is_ok());
+        let mint = Pubkey::new_unique();
+        let native_config = LaunchConfig {
+            mint,
+            dex: DexKind::ConstantProduct,
+            ..Default::default()
+        };
+        assert_eq!(
+            native_config.lockable_lp_mint(),
+            Some(native::lp_mint_address(&mint))
+        );
+        let raydium_config = LaunchConfig {
+            dex: DexKind::RaydiumAmmV4,
+            ..native_config
+        };
+        assert_eq!(raydium_config.lockable_lp_mint(), None);
+
+        // Curves lock graduation LP for a duration, whenever they graduate
+        let curve = BondingCurve {
+            kind: CurveKind::Linear,
+            start_price: 1_000,
+            growth: 0,
+            step_tokens: 1_000,
+        };
+        let short_duration = LaunchConfigParams {
+            curve,
+            dex: DexKind::ConstantProduct,
+            dex_program: crate::ID,
+            lp_lock_duration: MIN_LP_LOCK_SLOTS - 1,
+            ..Default::default()
+        };
+        assert!(short_duration.validate_lp_lock(&clock).is_err());
+        let curve_locked = LaunchConfigParams {
+            lp_lock_duration: MIN_LP_LOCK_SLOTS,
+            ..short_duration
+        };
+        assert!(curve_locked.validate_lp_lock(&clock).is_ok());
+    }
+
+    #[test]
+    fn test_reserve_accounting() {
+        let mut manager = ABCManager {
+            reserve_tokens: 1_000,
+            ..Default::default()
+        };
+
+        // The vault's balance wins over stale books
+        manager.reconcile_reserve(900);
+        assert_eq!(manager.reserve_tokens, 900);
+
+        let config = LaunchConfig::default();
+        assert!(manager.debit_reserve(901, &config).is_err());
+        assert_eq!(manager.reserve_tokens, 900);
+        manager.debit_reserve(900, &config).unwrap();
+        assert_eq!(manager.reserve_tokens, 0);
+
+        manager.credit_reserve(250).unwrap();
+        assert_eq!(manager.reserve_tokens, 250);
+
+        // Unreleased vesting is ring-fenced from every other outflow
+        let config = LaunchConfig {
+            reserve_vesting: VestingSchedule {
+                amount: 200,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        manager.reserve_released = 50;
+        assert_eq!(manager.spendable_reserve(&config), 100);
+        assert!(manager.debit_reserve(101, &config).is_err());
+        manager.reserve_released = 200;
+        manager.debit_reserve(250, &config).unwrap();
+    }
+
+    #[test]
+    fn test_flagged_wallet_history() {
+        let mut flagged = FlaggedWallet {
+            first_slot: 10,
+            ..Default::default()
+        };
+
+        flagged.record(500, 10).unwrap();
+        flagged.record(700, 12).unwrap();
+
+        assert_eq!(flagged.flag_count, 2);
+        assert_eq!(flagged.total_sol, 1_200);
+        assert_eq!((flagged.first_slot, flagged.last_slot), (10, 12));
+    }
+
+    #[test]
+    fn test_wallet_cap_windows() {
+        let manager = ABCManager {
+            launch_slot: 100,
+            ..Default::default()
+        };
+        let mut config = LaunchConfig {
+            monitoring_blocks: 10,
+            ..Default::default()
+        };
+        assert_eq!(manager.wallet_cap_window(&config, 100), None);
+
+        config.wallet_cap_sol = 1_000;
+        config.wallet_cap_window_slots = 4;
+        assert_eq!(manager.wallet_cap_window(&config, 100), Some(100));
+        assert_eq!(manager.wallet_cap_window(&config, 103), Some(100));
+        assert_eq!(manager.wallet_cap_window(&config, 104), Some(104));
+        assert_eq!(manager.wallet_cap_window(&config, 111), None);
+
+        let mut purchases = WalletPurchases::default();
+        purchases.record(600, 100, 1_000).unwrap();
+        assert!(purchases.record(500, 100, 1_000).is_err());
+        purchases.record(400, 100, 1_000).unwrap();
+
+        // A new window starts from zero but keeps the running total
+        purchases.record(1_000, 104, 1_000).unwrap();
+        assert_eq!(purchases.window_sol, 1_000);
+        assert_eq!(purchases.total_sol, 2_000);
+    }
+
+    #[test]
+    fn test_presale_allowlist() {
+        let wallets: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = wallets
+            .iter()
+            .map(|wallet| merkle::leaf(wallet, 2_000_000_000))
+            .collect();
+
+        // Three leaves: the odd one is paired with the hash of the other two
+        let left = merkle::hash_pair(&leaves[0], &leaves[1]);
+        let root = merkle::hash_pair(&left, &leaves[2]);
+
+        assert!(merkle::verify(&[leaves[1], leaves[2]], root, leaves[0]));
+        assert!(merkle::verify(&[left], root, leaves[2]));
+        assert!(!merkle::verify(&[left], root, leaves[0]));
+        assert!(!merkle::verify(
+            &[leaves[1], leaves[2]],
+            root,
+            merkle::leaf(&wallets[0], 3_000_000_000)
+        ));
+
+        assert_eq!(
+            pricing::calculate_presale_tokens(500_000_000, 1_000_000).unwrap(),
+            500_000
+        );
+
+        let mut purchase = PresalePurchase::default();
+        purchase.record(1_500_000_000, 2_000_000_000).unwrap();
+        assert!(purchase.record(600_000_000, 2_000_000_000).is_err());
+        purchase.record(500_000_000, 2_000_000_000).unwrap();
+        assert_eq!(purchase.purchased_sol, 2_000_000_000);
+    }
+
+    #[test]
+    fn test_launch_phase_schedule() {
+        let config = LaunchConfig {
+            monitoring_blocks: 5,
+            ..Default::default()
+        };
+        let mut manager = ABCManager::default();
+        assert_eq!(manager.current_phase(&config, 10), LaunchPhase::Created);
+
+        assert!(manager.schedule_launch(9, 10).is_err());
+        assert_eq!(manager.schedule_launch(20, 10).unwrap(), LaunchPhase::Created);
+        // Can move the start until it arrives
+        manager.schedule_launch(30, 15).unwrap();
+        assert_eq!(manager.launch_slot, 30);
+
+        assert_eq!(manager.current_phase(&config, 29), LaunchPhase::Scheduled);
+        assert_eq!(manager.current_phase(&config, 30), LaunchPhase::Monitoring);
+        assert_eq!(manager.current_phase(&config, 35), LaunchPhase::Monitoring);
+        assert_eq!(manager.current_phase(&config, 36), LaunchPhase::Open);
+        assert!(manager.schedule_launch(40, 31).is_err());
+
+        // Stored phase catches up in steps, never backwards
+        manager.set_phase(LaunchPhase::Monitoring).unwrap();
+        manager.set_phase(LaunchPhase::Open).unwrap();
+        assert!(manager.set_phase(LaunchPhase::Monitoring).is_err());
+        assert!(manager.set_phase(LaunchPhase::Scheduled).is_err());
+
+        manager.set_phase(LaunchPhase::Closed).unwrap();
+        assert!(manager.set_phase(LaunchPhase::Open).is_err());
+        assert_eq!(manager.current_phase(&config, 100), LaunchPhase::Closed);
+
+        // An ungraduated curve can't be closed out from under its buyers
+        let mut curve = ABCManager {
+            phase: LaunchPhase::BondingCurve,
+            curve_sol_raised: 1_000,
+            ..Default::default()
+        };
+        assert!(curve.set_phase(LaunchPhase::Closed).is_err());
+        assert_eq!(curve.committed_treasury_sol().unwrap(), 1_000);
+    }
+
+    #[test]
+    fn test_pause_expires() {
+        let mut config = LaunchConfig {
+            monitoring_blocks: 5,
+            max_pause_slots: 100,
+            ..Default::default()
+        };
+        let authority = Pubkey::new_unique();
+        let guardian = Pubkey::new_unique();
+        let mut manager = ABCManager {
+            authority,
+            ..Default::default()
+        };
+        assert!(!manager.can_pause(&guardian));
+        manager.guardian = guardian;
+        assert!(manager.can_pause(&guardian) && manager.can_pause(&authority));
+        assert!(!manager.can_pause(&Pubkey::new_unique()));
+
+        // Nothing to pause before trading starts
+        manager.schedule_launch(10, 0).unwrap();
+        assert!(manager.pause(&config, 5).is_err());
+
+        manager.pause(&config, 12).unwrap();
+        assert_eq!(manager.pause_expires_slot, 112);
+        assert_eq!(manager.current_phase(&config, 111), LaunchPhase::Paused);
+        assert_eq!(manager.current_phase(&config, 112), LaunchPhase::Open);
+        assert!(manager.pause(&config, 50).is_err());
+
+        assert_eq!(manager.resume(&config, 13).unwrap(), LaunchPhase::Monitoring);
+        assert!(manager.resume(&config, 14).is_err());
+
+        // Without a limit only resume_trading ends it
+        config.max_pause_slots = 0;
+        manager.pause(&config, 20).unwrap();
+        assert_eq!(manager.current_phase(&config, u64::MAX), LaunchPhase::Paused);
+        assert_eq!(manager.resume(&config, 30).unwrap(), LaunchPhase::Open);
+    }
+
+    #[test]
+    fn test_commit_reveal_fill() {
+        let buyer = Pubkey::new_unique();
+        let salt = [7; 32];
+        let mut commitment = PurchaseCommitment {
+            buyer,
+            commitment: PurchaseCommitment::hash(&buyer, 300, &salt),
+            escrow_sol: 500,
+            ..Default::default()
+        };
+
+        // Unrevealed commitments get everything back
+        assert_eq!(commitment.fill(1_000, 900).unwrap(), (0, 500));
+
+        assert!(commitment.reveal(301, &salt, 100).is_err());
+        assert!(commitment.reveal(300, &[8; 32], 100).is_err());
+        commitment.reveal(300, &salt, 100).unwrap();
+        assert!(commitment.reveal(300, &salt, 100).is_err());
+
+        // A third of everything revealed buys a third of the allocation
+        assert_eq!(commitment.fill(1_000, 900).unwrap(), (333, 200));
+
+        let mut over_escrow = PurchaseCommitment {
+            buyer,
+            commitment: PurchaseCommitment::hash(&buyer, 600, &salt),
+            escrow_sol: 500,
+            ..Default::default()
+        };
+        assert!(over_escrow.reveal(600, &salt, 100).is_err());
+
+        let manager = ABCManager {
+            launch_slot: 100,
+            ..Default::default()
+        };
+        let config = LaunchConfig {
+            monitoring_blocks: 5,
+            reveal_window_slots: 10,
+            ..Default::default()
+        };
+        assert!(!manager.is_reveal_window(&config, 105));
+        assert!(manager.is_reveal_window(&config, 106));
+        assert!(manager.is_reveal_window(&config, 115));
+        assert!(!manager.is_reveal_window(&config, 116));
+    }
+
+    #[test]
+    fn test_auction_clearing() {
+        // Prices 1, 2, 3, ... SOL per PRICE_UNIT tokens
+        let config = LaunchConfig {
+            auction_tokens: 10 * PRICE_UNIT,
+            auction_floor_price: 1_000_000_000,
+            auction_tick_size: 1_000_000_000,
+            ..Default::default()
+        };
+        let bid = |sol_amount: u64, tick: u8| Bid {
+            sol_amount,
+            tick,
+            ..Default::default()
+        };
+
+        // 12 SOL at 3 and 2 SOL at 2 covers 10 units at 2: 12 SOL buys 6
+        // units, leaving 4 for the 2 SOL bid on the clearing tick
+        let mut auction = Auction::default();
+        auction.add_bid(12_000_000_000, 2).unwrap();
+        auction.add_bid(8_000_000_000, 1).unwrap();
+        auction.add_bid(1_000_000_000, 1).unwrap();
+        auction.add_bid(5_000_000_000, 0).unwrap();
+        assert!(auction.fill(&config, &bid(1, 0)).is_err());
+
+        auction.clear(&config).unwrap();
+        assert_eq!(auction.clearing_tick, 1);
+        assert_eq!(auction.marginal_tokens, 4 * PRICE_UNIT);
+
+        assert_eq!(
+            auction.fill(&config, &bid(12_000_000_000, 2)).unwrap(),
+            (6 * PRICE_UNIT, 0)
+        );
+        // The clearing tick's 4 units are split 8:1 and paid at 2 SOL each
+        let (tokens, refund) = auction.fill(&config, &bid(8_000_000_000, 1)).unwrap();
+        assert_eq!(tokens, 3_555_555_555);
+        assert_eq!(refund, 8_000_000_000 - 7_111_111_110);
+        assert_eq!(
+            auction.fill(&config, &bid(5_000_000_000, 0)).unwrap(),
+            (0, 5_000_000_000)
+        );
+
+        // 12 SOL at 3 already buys 6 units at 2, more than a 5 unit supply,
+        // so tick 2 clears and fills completely
+        let small = LaunchConfig {
+            auction_tokens: 5 * PRICE_UNIT,
+            ..config.clone()
+        };
+        let mut gapped = Auction::default();
+        gapped.add_bid(12_000_000_000, 2).unwrap();
+        gapped.add_bid(1_000_000_000, 1).unwrap();
+        gapped.clear(&small).unwrap();
+        assert_eq!(gapped.clearing_tick, 2);
+        assert_eq!(
+            gapped.fill(&small, &bid(12_000_000_000, 2)).unwrap(),
+            (4 * PRICE_UNIT, 0)
+        );
+        assert_eq!(
+            gapped.fill(&small, &bid(1_000_000_000, 1)).unwrap(),
+            (0, 1_000_000_000)
+        );
+
+        // Undersubscribed: everyone fills at the floor
+        let mut thin = Auction::default();
+        thin.add_bid(3_000_000_000, 5).unwrap();
+        thin.add_bid(2_000_000_000, 0).unwrap();
+        thin.clear(&config).unwrap();
+        assert_eq!(thin.clearing_tick, 0);
+        assert_eq!(
+            thin.fill(&config, &bid(3_000_000_000, 5)).unwrap(),
+            (3 * PRICE_UNIT, 0)
+        );
+        assert_eq!(
+            thin.fill(&config, &bid(2_000_000_000, 0)).unwrap(),
+            (2 * PRICE_UNIT, 0)
+        );
+    }
+
+    #[test]
+    fn test_bonding_curve_buy() {
+        // 10 tokens per step, starting at 0.1 SOL a token
+        let linear = BondingCurve {
+            kind: CurveKind::Linear,
+            start_price: 100_000_000,
+            growth: 100_000_000,
+            step_tokens: 10 * PRICE_UNIT,
+        };
+
+        assert_eq!(
+            pricing::calculate_curve_buy(&linear, 0, 1_000_000_000).unwrap(),
+            (10 * PRICE_UNIT, 1_000_000_000)
+        );
+        // The first step costs 1 SOL; the next 0.5 SOL buys 2.5 at 0.2
+        assert_eq!(
+            pricing::calculate_curve_buy(&linear, 0, 1_500_000_000).unwrap(),
+            (12_500_000_000, 1_500_000_000)
+        );
+        // Picks up partway through a step
+        assert_eq!(
+            pricing::calculate_curve_buy(&linear, 12_500_000_000, 1_500_000_000).unwrap(),
+            (7_500_000_000, 1_500_000_000)
+        );
+        // Nothing is left past the last step
+        assert_eq!(
+            pricing::calculate_curve_buy(&linear, MAX_CURVE_STEPS * linear.step_tokens, 1_000)
+                .unwrap(),
+            (0, 0)
+        );
+
+        // Doubling every step
+        let exponential = BondingCurve {
+            kind: CurveKind::Exponential,
+            growth: BPS_DENOMINATOR,
+            ..linear
+        };
+        assert_eq!(exponential.step_price(3).unwrap(), 800_000_000);
+        let unbounded = BondingCurve {
+            growth: u64::MAX,
+            ..exponential
+        };
+        assert!(unbounded.step_price(1).is_err());
+        assert_eq!(
+            pricing::calculate_curve_buy(&exponential, 0, 3_000_000_000).unwrap(),
+            (20 * PRICE_UNIT, 3_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_legacy_manager_upgrade() {
+        let legacy = LegacyABCManager {
+            authority: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            launch_slot: 42,
+            is_launched: true,
+            captured_sol: 7,
+            reserve_tokens: 1_000,
+            bump: 254,
+            ..Default::default()
+        };
+
+        let manager = ABCManager::from(legacy.clone());
+        assert_eq!(manager.version, MANAGER_VERSION);
+        assert_eq!(manager.authority, legacy.authority);
+        assert_eq!(manager.launch_slot, legacy.launch_slot);
+        assert_eq!(manager.phase, LaunchPhase::Open);
+        assert_eq!(manager.reserve_tokens, legacy.reserve_tokens);
+        assert_eq!(manager.bump, legacy.bump);
+    }
+
+    #[test]
+    fn test_swap_output_uses_reserves_and_fee() {
+        let reserves = pricing::PoolReserves {
+            sol: 10_000_000_000,
+            token: 1_000_000_000_000,
+        };
+
+        // Fee-free quote is plain x * y = k
+        let no_fee = pricing::calculate_tokens_from_sol(&reserves, 1_000_000_000, 0).unwrap();
+        assert_eq!(no_fee, 90_909_090_909);
+
+        let with_fee = pricing::calculate_tokens_from_sol(&reserves, 1_000_000_000, 25).unwrap();
+        assert!(with_fee < no_fee);
+        assert_eq!(with_fee, 90_702_432_370);
+
+        let sol_back = pricing::calculate_sol_from_tokens(&reserves, with_fee, 25).unwrap();
+        assert!(sol_back < 1_000_000_000);
+    }
+
+    #[test]
+    fn test_liquidity_deposit_and_withdrawal() {
+        let empty = pricing::PoolReserves { sol: 0, token: 0 };
+        let (tokens, shares) =
+            pricing::calculate_liquidity_deposit(&empty, 0, 4_000, 1_000_000).unwrap();
+        assert_eq!((tokens, shares), (1_000_000, 63_245));
+
+        // Later deposits follow the pool ratio, rounding the token side up
+        let reserves = pricing::PoolReserves {
+            sol: 3_000,
+            token: 1_000_000,
+        };
+        let (tokens, shares) =
+            pricing::calculate_liquidity_deposit(&reserves, 60_000, 1_000, u64::MAX).unwrap();
+        assert_eq!((tokens, shares), (333_334, 20_000));
+
+        let (sol, tokens) = pricing::calculate_liquidity_withdrawal(&reserves, 60_000, 20_000).unwrap();
+        assert_eq!((sol, tokens), (1_000, 333_333));
+        assert!(pricing::calculate_liquidity_withdrawal(&reserves, 60_000, 60_001).is_err());
+
+        assert_eq!(pricing::integer_sqrt(0), 0);
+        assert_eq!(pricing::integer_sqrt(15), 3);
+        assert_eq!(pricing::integer_sqrt(16), 4);
+        assert_eq!(pricing::integer_sqrt(u128::MAX), u64::MAX as u128);
+    }
+
+    #[test]
+    fn test_slippage_tolerance() {
+        assert_eq!(pricing::apply_slippage_tolerance(1_000_000, 100).unwrap(), 990_000);
+        assert_eq!(pricing::apply_slippage_tolerance(1_000_000, 0).unwrap(), 1_000_000);
+        assert_eq!(pricing::apply_slippage_tolerance(u64::MAX, 5000).unwrap(), u64::MAX / 2);
+    }
+
+    #[test]
+    fn test_price_impact() {
+        let sol_reserve = 10_000_000_000;
+        let token_reserve = 1_000_000_000_000;
+
+        // 1% of the SOL side moves price by ~2% on a fee-free pool
+        let tokens_out =
+            pricing::calculate_swap_output(100_000_000, sol_reserve as u128, token_reserve as u128, 0)
+                .unwrap();
+        let impact = pricing::calculate_price_impact_bps(
+            sol_reserve,
+            token_reserve,
+            100_000_000,
+            tokens_out,
+        )
+        .unwrap();
+        assert_eq!(impact, 200);
+
+        // No trade, no impact
+        assert_eq!(
+            pricing::calculate_price_impact_bps(sol_reserve, token_reserve, 0, 0).unwrap(),
+            0
+        );
+
+        // Draining the output side is rejected outright
+        assert!(pricing::calculate_price_impact_bps(
+            sol_reserve,
+            token_reserve,
+            1,
+            token_reserve
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_raydium_instruction_layouts() {
+        let swap_in = raydium::AmmInstruction::SwapBaseIn {
+            amount_in: 5,
+            minimum_amount_out: 7,
+        };
+        let packed = swap_in.pack();
+        assert_eq!(packed.len(), 17);
+        assert_eq!(packed[0], raydium::SWAP_BASE_IN);
+        assert_eq!(packed[1..9], 5u64.to_le_bytes());
+        assert_eq!(packed[9..17], 7u64.to_le_bytes());
+        assert_eq!(raydium::AmmInstruction::unpack(&packed).unwrap(), swap_in);
+
+        let swap_out = raydium::AmmInstruction::SwapBaseOut {
+            max_amount_in: 11,
+            amount_out: 3,
+        };
+        let packed = swap_out.pack();
+        assert_eq!(packed[0], raydium::SWAP_BASE_OUT);
+        assert_eq!(raydium::AmmInstruction::unpack(&packed).unwrap(), swap_out);
+
+        let init = raydium::AmmInstruction::Initialize2 {
+            nonce: 254,
+            open_time: 0,
+            init_pc_amount: 1,
+            init_coin_amount: 2,
+        };
+        let packed = init.pack();
+        assert_eq!(packed.len(), 26);
+        assert_eq!(raydium::AmmInstruction::unpack(&packed).unwrap(), init);
+
+        assert!(raydium::AmmInstruction::unpack(&[raydium::SWAP_BASE_IN, 1, 2]).is_err());
+        assert!(raydium::AmmInstruction::unpack(&[2, 0, 0, 0, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_raydium_swap_account_order() {
+        let pool = raydium::SwapPoolKeys {
+            amm: Pubkey::new_unique(),
+            pool_coin_vault: Pubkey::new_unique(),
+            pool_pc_vault: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let (source, destination, owner) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        let ix = raydium::swap_base_in(&Pubkey::new_unique(), &pool, &source, &destination, &owner, 1, 0);
+
+        assert_eq!(ix.accounts.len(), 18);
+        assert_eq!(ix.accounts[0].pubkey, anchor_spl::token::ID);
+        assert_eq!(ix.accounts[1].pubkey, pool.amm);
+        assert_eq!(ix.accounts[5].pubkey, pool.pool_coin_vault);
+        assert_eq!(ix.accounts[6].pubkey, pool.pool_pc_vault);
+        assert_eq!(ix.accounts[15].pubkey, source);
+        assert_eq!(ix.accounts[16].pubkey, destination);
+        assert_eq!(ix.accounts[17].pubkey, owner);
+        assert!(ix.accounts[17].is_signer);
+        assert_eq!(ix.accounts.iter().filter(|meta| meta.is_signer).count(), 1);
+    }
+
+    #[test]
+    fn test_whirlpool_state_and_virtual_reserves() {
+        let mint = Pubkey::new_unique();
+        let vault_a = Pubkey::new_unique();
+        let vault_b = Pubkey::new_unique();
+
+        // SOL as token A at a price of 4 tokens per SOL: sqrt_price = 2.0 in Q64.64
+        let mut data = vec![0u8; whirlpool::WhirlpoolState::MIN_LEN];
+        data[..8].copy_from_slice(&whirlpool::WHIRLPOOL_DISCRIMINATOR);
+        data[45..47].copy_from_slice(&3000u16.to_le_bytes());
+        data[49..65].copy_from_slice(&1_000_000_000u128.to_le_bytes());
+        data[65..81].copy_from_slice(&(2u128 << 64).to_le_bytes());
+        data[101..133].copy_from_slice(anchor_spl::token::spl_token::native_mint::id().as_ref());
+        data[133..165].copy_from_slice(vault_a.as_ref());
+        data[181..213].copy_from_slice(mint.as_ref());
+        data[213..245].copy_from_slice(vault_b.as_ref());
+
+        let state = whirlpool::WhirlpoolState::unpack(&data).unwrap();
+        assert_eq!(state.token_mint_b, mint);
+        assert_eq!(state.token_vault_a, vault_a);
+        assert_eq!(state.token_vault_b, vault_b);
+        assert_eq!(state.fee_bps(), 30);
+        assert_eq!(state.virtual_reserves(), (500_000_000, 2_000_000_000));
+
+        data[0] ^= 1;
+        assert!(whirlpool::WhirlpoolState::unpack(&data).is_err());
+    }
+
+    #[test]
+    fn test_whirlpool_swap_layout() {
+        let pool = whirlpool::SwapPoolKeys {
+            whirlpool: Pubkey::new_unique(),
+            token_vault_a: Pubkey::new_unique(),
+            token_vault_b: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let (authority, account_a, account_b) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        let ix = whirlpool::swap(
+            &Pubkey::new_unique(),
+            &pool,
+            &authority,
+            &account_a,
+            &account_b,
+            500,
+            450,
+            true,
+            true,
+        );
+
+        assert_eq!(ix.data.len(), 42);
+        assert_eq!(ix.data[..8], whirlpool::SWAP_DISCRIMINATOR);
+        assert_eq!(ix.data[8..16], 500u64.to_le_bytes());
+        assert_eq!(ix.data[16..24], 450u64.to_le_bytes());
+        assert_eq!(ix.data[24..40], whirlpool::MIN_SQRT_PRICE_X64.to_le_bytes());
+        assert_eq!(ix.data[40..], [1, 1]);
+
+        assert_eq!(ix.accounts.len(), 11);
+        assert_eq!(ix.accounts[1].pubkey, authority);
+        assert!(ix.accounts[1].is_signer);
+        assert_eq!(ix.accounts[3].pubkey, account_a);
+        assert_eq!(ix.accounts[4].pubkey, pool.token_vault_a);
+        assert_eq!(ix.accounts[5].pubkey, account_b);
+        assert_eq!(ix.accounts[6].pubkey, pool.token_vault_b);
+    }
+}
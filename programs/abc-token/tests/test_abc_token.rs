@@ -1,7 +1,9 @@
- use anchor_lang::{
+use abc_token::raydium;
+use anchor_lang::{
     prelude::*,
     solana_program::{
         instruction::Instruction,
+        program::{invoke, invoke_signed},
         program_error::ProgramError,
         program_pack::Pack,
         system_instruction, system_program,
@@ -10,17 +12,20 @@
 };
 use solana_program_test::*;
 use solana_sdk::{
+    account::{Account as SolanaAccount, AccountSharedData},
     entrypoint::ProgramResult,
     signature::{Keypair, Signer},
     transaction::Transaction,
     transport::TransportError,
 };
+use std::str::FromStr;
 
 // Custom error type for our tests
 #[derive(Debug)]
 enum TestError {
     BanksClientError(BanksClientError),
     ProgramError(ProgramError),
+    AnchorError(anchor_lang::error::Error),
     TransportError(TransportError),
     PackError(String),
 }
@@ -37,13 +42,19 @@ impl From<ProgramError> for TestError {
     }
 }
 
+impl From<anchor_lang::error::Error> for TestError {
+    fn from(e: anchor_lang::error::Error) -> Self {
+        Self::AnchorError(e)
+    }
+}
+
 impl From<TransportError> for TestError {
     fn from(e: TransportError) -> Self {
         Self::TransportError(e)
     }
 }
 
-type TestResult<T> = Result<T, TestError>;
+type TestResult<T> = std::result::Result<T, TestError>;
 
 // Helper function for token account unpacking errors
 fn handle_token_error<T: std::fmt::Display>(e: T) -> TestError {
@@ -156,6 +167,101 @@ async fn mint_tokens(
     Ok(())
 }
 
+/// Keys of the mock AMM v4 pool. Only the vaults and the authority are real
+/// accounts; the Serum side is never read by the mock.
+struct MockPool {
+    program_id: Pubkey,
+    amm: Pubkey,
+    amm_authority: Pubkey,
+    amm_open_orders: Pubkey,
+    amm_target_orders: Pubkey,
+    lp_mint: Pubkey,
+    pool_coin_vault: Pubkey,
+    pool_pc_vault: Pubkey,
+    amm_config: Pubkey,
+    create_fee_destination: Pubkey,
+    market_program: Pubkey,
+    market: Pubkey,
+    market_bids: Pubkey,
+    market_asks: Pubkey,
+    market_event_queue: Pubkey,
+    market_coin_vault: Pubkey,
+    market_pc_vault: Pubkey,
+    market_vault_signer: Pubkey,
+}
+
+impl MockPool {
+    fn swap_keys(&self) -> raydium::SwapPoolKeys {
+        raydium::SwapPoolKeys {
+            amm: self.amm,
+            amm_authority: self.amm_authority,
+            amm_open_orders: self.amm_open_orders,
+            amm_target_orders: self.amm_target_orders,
+            pool_coin_vault: self.pool_coin_vault,
+            pool_pc_vault: self.pool_pc_vault,
+            market_program: self.market_program,
+            market: self.market,
+            market_bids: self.market_bids,
+            market_asks: self.market_asks,
+            market_event_queue: self.market_event_queue,
+            market_coin_vault: self.market_coin_vault,
+            market_pc_vault: self.market_pc_vault,
+            market_vault_signer: self.market_vault_signer,
+        }
+    }
+
+    // Remaining accounts for `buy`/`sell`: program, pool accounts, then the
+    // wrapped-SOL account of whoever is on the other side of the swap
+    fn trade_accounts(&self, sol_account: &Pubkey) -> Vec<AccountMeta> {
+        let keys = self.swap_keys();
+        vec![
+            AccountMeta::new_readonly(self.program_id, false),
+            AccountMeta::new(keys.amm, false),
+            AccountMeta::new_readonly(keys.amm_authority, false),
+            AccountMeta::new(keys.amm_open_orders, false),
+            AccountMeta::new(keys.amm_target_orders, false),
+            AccountMeta::new(keys.pool_coin_vault, false),
+            AccountMeta::new(keys.pool_pc_vault, false),
+            AccountMeta::new_readonly(keys.market_program, false),
+            AccountMeta::new(keys.market, false),
+            AccountMeta::new(keys.market_bids, false),
+            AccountMeta::new(keys.market_asks, false),
+            AccountMeta::new(keys.market_event_queue, false),
+            AccountMeta::new(keys.market_coin_vault, false),
+            AccountMeta::new(keys.market_pc_vault, false),
+            AccountMeta::new_readonly(keys.market_vault_signer, false),
+            AccountMeta::new(*sol_account, false),
+        ]
+    }
+
+    // Remaining accounts for `initialize`, in `raydium::CreatePool` order
+    fn create_accounts(&self, user_token_pc: &Pubkey, user_token_lp: &Pubkey) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(self.program_id, false),
+            AccountMeta::new_readonly(
+                Pubkey::from_str(raydium::ASSOCIATED_TOKEN_PROGRAM_ID).unwrap(),
+                false,
+            ),
+            AccountMeta::new(self.amm, false),
+            AccountMeta::new_readonly(self.amm_authority, false),
+            AccountMeta::new(self.amm_open_orders, false),
+            AccountMeta::new(self.lp_mint, false),
+            AccountMeta::new_readonly(spl_token::native_mint::id(), false),
+            AccountMeta::new(self.pool_coin_vault, false),
+            AccountMeta::new(self.pool_pc_vault, false),
+            AccountMeta::new(self.amm_target_orders, false),
+            AccountMeta::new_readonly(self.amm_config, false),
+            AccountMeta::new(self.create_fee_destination, false),
+            AccountMeta::new_readonly(self.market_program, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(*user_token_pc, false),
+            AccountMeta::new(*user_token_lp, false),
+        ]
+    }
+}
+
+const POOL_FEE_BPS: u128 = 25;
+
 #[tokio::test]
 async fn test_full_flow() -> TestResult<()> {
     let program_id = abc_token::id();
@@ -171,16 +277,30 @@ async fn test_full_flow() -> TestResult<()> {
     );
 
     // Start the test context
-    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+    let mut context = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
 
     // Setup mint
     let mint_keypair = Keypair::new();
     let mint_authority = Keypair::new();
-    setup_mint(&mut banks_client, &payer, &mint_keypair, &mint_authority).await?;
+    setup_mint(
+        &mut context.banks_client,
+        &payer,
+        &mint_keypair,
+        &mint_authority,
+    )
+    .await?;
+    fund_account(
+        &mut context.banks_client,
+        &payer,
+        &mint_authority.pubkey(),
+        10_000_000_000,
+    )
+    .await?;
 
     // Setup token accounts
     let token_source = create_token_account(
-        &mut banks_client,
+        &mut context.banks_client,
         &payer,
         &mint_keypair.pubkey(),
         &mint_authority.pubkey(),
@@ -189,7 +309,7 @@ async fn test_full_flow() -> TestResult<()> {
 
     // Mint initial supply
     mint_tokens(
-        &mut banks_client,
+        &mut context.banks_client,
         &payer,
         &mint_keypair.pubkey(),
         &token_source,
@@ -199,84 +319,192 @@ async fn test_full_flow() -> TestResult<()> {
     .await?;
 
     // Setup Raydium accounts
-    let (pool_account, token_vault, sol_vault) =
-        setup_raydium_accounts(&mut banks_client, &payer, &mint_keypair.pubkey()).await?;
+    let pool = setup_raydium_accounts(
+        &mut context.banks_client,
+        &payer,
+        &mint_keypair.pubkey(),
+        &raydium_program_id,
+    )
+    .await?;
 
     // Initialize ABC token program
     let (manager, reserve_account) = initialize_abc_token(
-        &mut banks_client,
+        &mut context.banks_client,
         &payer,
         &mint_authority,
         &mint_keypair.pubkey(),
         &token_source,
-        &pool_account,
-        &token_vault,
-        &sol_vault,
-        &raydium_program_id,
+        &pool,
+    )
+    .await?;
+
+    // Liquidity from other LPs so trades stay inside the price impact limit
+    wrap_sol(
+        &mut context.banks_client,
+        &payer,
+        &pool.pool_pc_vault,
+        20_000_000_000,
     )
     .await?;
 
     // Test bot detection
-    test_bot_detection(
-        &mut banks_client,
+    let bot_trader = test_bot_detection(
+        &mut context.banks_client,
         &payer,
         &manager,
-        &pool_account,
-        &token_vault,
-        &sol_vault,
+        &reserve_account,
+        &pool,
+        &mint_keypair.pubkey(),
+    )
+    .await?;
+
+    // Advance past the monitoring period
+    let slot = context.banks_client.get_root_slot().await?;
+    context.warp_to_slot(slot + 10).unwrap();
+
+    test_refund(
+        &mut context.banks_client,
+        &payer,
+        &manager,
+        &bot_trader,
         &mint_keypair.pubkey(),
-        &raydium_program_id,
+    )
+    .await?;
+
+    test_clear_flagged_wallet(
+        &mut context.banks_client,
+        &payer,
+        &manager,
+        &mint_authority,
+        &bot_trader,
     )
     .await?;
 
     // Test normal trading
     test_normal_trading(
-        &mut banks_client,
+        &mut context.banks_client,
         &payer,
         &manager,
-        &pool_account,
-        &token_vault,
-        &sol_vault,
+        &reserve_account,
+        &pool,
         &mint_keypair.pubkey(),
-        &raydium_program_id,
     )
     .await?;
 
     Ok(())
 }
 
-async fn setup_raydium_accounts(
+async fn fund_account(
     banks_client: &mut BanksClient,
     payer: &Keypair,
-    mint: &Pubkey,
-) -> TestResult<(Pubkey, Pubkey, Pubkey)> {
-    let pool_account = Keypair::new();
-    let token_vault =
-        create_token_account(banks_client, payer, mint, &pool_account.pubkey()).await?;
+    recipient: &Pubkey,
+    lamports: u64,
+) -> TestResult<()> {
+    let recent_blockhash = banks_client.get_latest_blockhash().await?;
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            recipient,
+            lamports,
+        )],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
 
-    let sol_vault = Keypair::new();
-    let rent = banks_client.get_rent().await?;
-    let sol_vault_rent = rent.minimum_balance(0);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
 
-    let create_sol_vault_ix = system_instruction::create_account(
-        &payer.pubkey(),
-        &sol_vault.pubkey(),
-        sol_vault_rent,
-        0,
-        &system_program::id(),
+async fn wrap_sol(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    sol_account: &Pubkey,
+    lamports: u64,
+) -> TestResult<()> {
+    let recent_blockhash = banks_client.get_latest_blockhash().await?;
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            system_instruction::transfer(&payer.pubkey(), sol_account, lamports),
+            spl_token::instruction::sync_native(&spl_token::id(), sol_account)?,
+        ],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
     );
 
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn setup_raydium_accounts(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    mint: &Pubkey,
+    raydium_program_id: &Pubkey,
+) -> TestResult<MockPool> {
+    let (amm_authority, _) =
+        Pubkey::find_program_address(&[raydium::AUTHORITY_AMM], raydium_program_id);
+
+    // The LP mint is real so the mock can mint the pool's initial LP
+    let lp_mint = Keypair::new();
+    let mint_rent = banks_client
+        .get_rent()
+        .await?
+        .minimum_balance(spl_token::state::Mint::LEN);
     let recent_blockhash = banks_client.get_latest_blockhash().await?;
     let transaction = Transaction::new_signed_with_payer(
-        &[create_sol_vault_ix],
+        &[
+            system_instruction::create_account(
+                &payer.pubkey(),
+                &lp_mint.pubkey(),
+                mint_rent,
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint(
+                &spl_token::id(),
+                &lp_mint.pubkey(),
+                &amm_authority,
+                None,
+                9,
+            )?,
+        ],
         Some(&payer.pubkey()),
-        &[payer, &sol_vault],
+        &[payer, &lp_mint],
         recent_blockhash,
     );
-
     banks_client.process_transaction(transaction).await?;
 
-    Ok((pool_account.pubkey(), token_vault, sol_vault.pubkey()))
+    let pool_coin_vault = create_token_account(banks_client, payer, mint, &amm_authority).await?;
+    let pool_pc_vault = create_token_account(
+        banks_client,
+        payer,
+        &spl_token::native_mint::id(),
+        &amm_authority,
+    )
+    .await?;
+
+    Ok(MockPool {
+        program_id: *raydium_program_id,
+        amm: Pubkey::new_unique(),
+        amm_authority,
+        amm_open_orders: Pubkey::new_unique(),
+        amm_target_orders: Pubkey::new_unique(),
+        lp_mint: lp_mint.pubkey(),
+        pool_coin_vault,
+        pool_pc_vault,
+        amm_config: Pubkey::new_unique(),
+        create_fee_destination: Pubkey::new_unique(),
+        market_program: Pubkey::new_unique(),
+        market: Pubkey::new_unique(),
+        market_bids: Pubkey::new_unique(),
+        market_asks: Pubkey::new_unique(),
+        market_event_queue: Pubkey::new_unique(),
+        market_coin_vault: Pubkey::new_unique(),
+        market_pc_vault: Pubkey::new_unique(),
+        market_vault_signer: Pubkey::new_unique(),
+    })
 }
 
 async fn initialize_abc_token(
@@ -285,43 +513,57 @@ async fn initialize_abc_token(
     authority: &Keypair,
     mint: &Pubkey,
     token_source: &Pubkey,
-    pool_account: &Pubkey,
-    token_vault: &Pubkey,
-    sol_vault: &Pubkey,
-    raydium_program_id: &Pubkey,
+    pool: &MockPool,
 ) -> TestResult<(Pubkey, Pubkey)> {
     let (manager, _) =
         Pubkey::find_program_address(&[b"abc_manager", mint.as_ref()], &abc_token::id());
 
+    let (launch_config, _) =
+        Pubkey::find_program_address(&[b"launch_config", mint.as_ref()], &abc_token::id());
+
     let (reserve_account, _) =
         Pubkey::find_program_address(&[b"reserve", mint.as_ref()], &abc_token::id());
 
-    let accounts = vec![
+    // Authority's wrapped SOL for the pc side of the new pool
+    let authority_sol_account = create_token_account(
+        banks_client,
+        payer,
+        &spl_token::native_mint::id(),
+        &authority.pubkey(),
+    )
+    .await?;
+    wrap_sol(banks_client, payer, &authority_sol_account, 1_000_000_000).await?;
+
+    let mut accounts = vec![
         AccountMeta::new(authority.pubkey(), true),
         AccountMeta::new_readonly(*mint, false),
         AccountMeta::new(manager, false),
+        AccountMeta::new(launch_config, false),
         AccountMeta::new(*token_source, false),
         AccountMeta::new(reserve_account, false),
-        AccountMeta::new(*pool_account, false),
-        AccountMeta::new(*token_vault, false),
-        AccountMeta::new(*sol_vault, false),
-        AccountMeta::new_readonly(*raydium_program_id, false),
         AccountMeta::new_readonly(spl_token::id(), false),
         AccountMeta::new_readonly(system_program::id(), false),
         AccountMeta::new_readonly(sysvar::rent::id(), false),
         AccountMeta::new_readonly(sysvar::clock::id(), false),
     ];
+    let authority_lp_account =
+        create_token_account(banks_client, payer, &pool.lp_mint, &authority.pubkey()).await?;
+    accounts.extend(pool.create_accounts(&authority_sol_account, &authority_lp_account));
+    let lp_lock = mint_pda(b"lp_lock", mint);
+    let lp_vault = mint_pda(b"lp_vault", mint);
+    accounts.push(AccountMeta::new(lp_lock, false));
+    accounts.push(AccountMeta::new(lp_vault, false));
 
-    let mut init_data = vec![0; 8 + 8]; // 8 bytes discriminator + 8 bytes for u64
-    init_data[0..8].copy_from_slice(&[103, 133, 90, 210, 225, 25, 126, 37]); // Initialize discriminator
-    init_data[8..16].copy_from_slice(&1_000_000_000_000u64.to_le_bytes());
-
-    let init_ix = Instruction {
-        program_id: abc_token::id(),
-        accounts,
-        data: init_data,
+    let params = abc_token::LaunchConfigParams {
+        dex_program: pool.program_id,
+        refund_penalty_bps: 1_000,
+        lp_lock_until: abc_token::constants::MIN_LP_LOCK_SLOTS + 1_000,
+        ..Default::default()
     };
 
+    let reserve_amount = 400_000_000_000u64;
+    let init_ix = initialize_instruction(accounts, reserve_amount, &params);
+
     let recent_blockhash = banks_client.get_latest_blockhash().await?;
     let transaction = Transaction::new_signed_with_payer(
         &[init_ix],
@@ -338,62 +580,166 @@ async fn initialize_abc_token(
 
     assert_eq!(manager_data.authority, authority.pubkey());
     assert_eq!(manager_data.mint, *mint);
-    assert!(manager_data.is_launched);
+    assert_eq!(manager_data.raydium_pool, pool.amm);
+    // Nothing trades until the launch is scheduled
+    assert_eq!(manager_data.phase, abc_token::LaunchPhase::Created);
+
+    let config_account = banks_client.get_account(launch_config).await?.unwrap();
+    let config_data = abc_token::LaunchConfig::try_deserialize(&mut &config_account.data[..])?;
+
+    assert_eq!(config_data.mint, *mint);
+    assert_eq!(config_data.dex_program, pool.program_id);
+    assert_eq!(config_data.monitoring_blocks, params.monitoring_blocks);
+
+    // Reserve funded, half of it seeded into the pool from the source account
+    assert_eq!(
+        get_token_balance(banks_client, &reserve_account).await?,
+        reserve_amount
+    );
+    assert_eq!(
+        get_token_balance(banks_client, &pool.pool_coin_vault).await?,
+        reserve_amount / 2
+    );
+    assert_eq!(
+        get_token_balance(banks_client, &pool.pool_pc_vault).await?,
+        config_data.min_trade_sol
+    );
+
+    // The pool's LP went straight under the launch's lock
+    let lp_amount =
+        ((config_data.min_trade_sol as f64) * ((reserve_amount / 2) as f64)).sqrt() as u64;
+    assert_eq!(get_token_balance(banks_client, &lp_vault).await?, lp_amount);
+    assert_eq!(
+        get_token_balance(banks_client, &authority_lp_account).await?,
+        0
+    );
+    let lock_account = banks_client.get_account(lp_lock).await?.unwrap();
+    let lock_data = abc_token::LiquidityLock::try_deserialize(&mut &lock_account.data[..])?;
+    assert_eq!(lock_data.lp_mint, pool.lp_mint);
+    assert_eq!(lock_data.amount, lp_amount);
+    assert_eq!(lock_data.lock_until, params.lp_lock_until);
+
+    schedule_launch(banks_client, payer, authority, &manager).await?;
 
     Ok((manager, reserve_account))
 }
 
-async fn test_bot_detection(
+/// Schedules trading to open at the current slot, returning it
+async fn schedule_launch(
     banks_client: &mut BanksClient,
     payer: &Keypair,
+    authority: &Keypair,
     manager: &Pubkey,
-    pool_account: &Pubkey,
-    token_vault: &Pubkey,
-    sol_vault: &Pubkey,
-    mint: &Pubkey,
-    raydium_program_id: &Pubkey,
-) -> TestResult<()> {
-    let bot_trader = Keypair::new();
-    let bot_token_account =
-        create_token_account(banks_client, payer, mint, &bot_trader.pubkey()).await?;
+) -> TestResult<u64> {
+    let start_slot = banks_client.get_sysvar::<Clock>().await?.slot;
+    let schedule_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(authority.pubkey(), true),
+            AccountMeta::new(*manager, false),
+        ],
+        data: instruction_data(SCHEDULE_LAUNCH_DISCRIMINATOR, &[start_slot]),
+    };
+    process_instruction(banks_client, payer, schedule_ix, &[authority]).await?;
 
-    // Fund bot trader
-    let recent_blockhash = banks_client.get_latest_blockhash().await?;
-    let fund_tx = Transaction::new_signed_with_payer(
-        &[system_instruction::transfer(
-            &payer.pubkey(),
-            &bot_trader.pubkey(),
-            5_000_000_000, // 5 SOL
-        )],
-        Some(&payer.pubkey()),
-        &[payer],
-        recent_blockhash,
-    );
-    banks_client.process_transaction(fund_tx).await?;
+    Ok(start_slot)
+}
 
-    // Execute trade during monitoring period
-    let accounts = vec![
+fn initialize_instruction(
+    accounts: Vec<AccountMeta>,
+    reserve_amount: u64,
+    params: &abc_token::LaunchConfigParams,
+) -> Instruction {
+    let mut init_data = vec![0; 8 + 8]; // 8 bytes discriminator + 8 bytes for u64
+    init_data[0..8].copy_from_slice(&[175, 175, 109, 31, 13, 152, 155, 237]); // Initialize discriminator
+    init_data[8..16].copy_from_slice(&reserve_amount.to_le_bytes());
+    init_data.extend_from_slice(&params.try_to_vec().unwrap());
+
+    Instruction {
+        program_id: abc_token::id(),
+        accounts,
+        data: init_data,
+    }
+}
+
+fn trade_instruction(
+    discriminator: [u8; 8],
+    amount: u64,
+    min_out: u64,
+    manager: &Pubkey,
+    trader: &Pubkey,
+    trader_token_account: &Pubkey,
+    reserve_account: &Pubkey,
+    mint: &Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let (treasury, _) =
+        Pubkey::find_program_address(&[b"treasury", mint.as_ref()], &abc_token::id());
+
+    let mut accounts = vec![
         AccountMeta::new(*manager, false),
-        AccountMeta::new(bot_trader.pubkey(), true),
-        AccountMeta::new(bot_token_account, false),
-        AccountMeta::new(*pool_account, false),
-        AccountMeta::new(*token_vault, false),
-        AccountMeta::new(*sol_vault, false),
-        AccountMeta::new_readonly(*raydium_program_id, false),
+        AccountMeta::new_readonly(launch_config_address(mint), false),
+        AccountMeta::new(*trader, true),
+        AccountMeta::new(*trader_token_account, false),
+        AccountMeta::new(*reserve_account, false),
+        AccountMeta::new(treasury, false),
+        AccountMeta::new(monitored_purchase_address(manager, trader), false),
+        AccountMeta::new(flagged_wallet_address(manager, trader), false),
+        AccountMeta::new(wallet_purchases_address(manager, trader), false),
         AccountMeta::new_readonly(spl_token::id(), false),
         AccountMeta::new_readonly(system_program::id(), false),
         AccountMeta::new_readonly(sysvar::clock::id(), false),
     ];
+    accounts.extend(remaining_accounts);
 
-    let mut buy_data = vec![0; 8 + 8];
-    buy_data[0..8].copy_from_slice(&[242, 35, 198, 137, 82, 225, 242, 182]); // Buy discriminator
-    buy_data[8..16].copy_from_slice(&1_000_000_000u64.to_le_bytes());
+    let mut data = vec![0; 8 + 8 + 8];
+    data[0..8].copy_from_slice(&discriminator);
+    data[8..16].copy_from_slice(&amount.to_le_bytes());
+    data[16..24].copy_from_slice(&min_out.to_le_bytes());
 
-    let buy_ix = Instruction {
+    Instruction {
         program_id: abc_token::id(),
         accounts,
-        data: buy_data,
-    };
+        data,
+    }
+}
+
+const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+async fn test_bot_detection(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    manager: &Pubkey,
+    reserve_account: &Pubkey,
+    pool: &MockPool,
+    mint: &Pubkey,
+) -> TestResult<Keypair> {
+    let bot_trader = Keypair::new();
+    let bot_token_account =
+        create_token_account(banks_client, payer, mint, &bot_trader.pubkey()).await?;
+
+    // Wrapped SOL the manager receives from the counter-trade
+    let manager_sol_account =
+        create_token_account(banks_client, payer, &spl_token::native_mint::id(), manager).await?;
+
+    // Fund bot trader
+    fund_account(banks_client, payer, &bot_trader.pubkey(), 5_000_000_000).await?;
+
+    let reserve_before = get_token_balance(banks_client, reserve_account).await?;
+
+    // Execute trade during monitoring period
+    let buy_ix = trade_instruction(
+        BUY_DISCRIMINATOR,
+        1_000_000_000,
+        0,
+        manager,
+        &bot_trader.pubkey(),
+        &bot_token_account,
+        reserve_account,
+        mint,
+        pool.trade_accounts(&manager_sol_account),
+    );
 
     let recent_blockhash = banks_client.get_latest_blockhash().await?;
     let transaction = Transaction::new_signed_with_payer(
@@ -412,80 +758,208 @@ async fn test_bot_detection(
     assert_eq!(manager_data.last_blocked_address, bot_trader.pubkey());
     assert_eq!(manager_data.captured_sol, 1_000_000_000);
 
-    // Check token balances to verify counter-trade
-    let bot_token_balance = get_token_balance(banks_client, &bot_token_account).await?;
-    let pool_token_balance = get_token_balance(banks_client, token_vault).await?;
+    // The bot gets nothing; the counter-trade sold reserve tokens for wrapped SOL
+    assert_eq!(
+        get_token_balance(banks_client, &bot_token_account).await?,
+        0
+    );
+    assert!(get_token_balance(banks_client, reserve_account).await? < reserve_before);
+    assert!(get_token_balance(banks_client, &manager_sol_account).await? > 0);
+
+    // The purchase is on record, but can't be refunded while the window is open
+    let purchase_account = banks_client
+        .get_account(monitored_purchase_address(manager, &bot_trader.pubkey()))
+        .await?
+        .unwrap();
+    let purchase = abc_token::MonitoredPurchase::try_deserialize(&mut &purchase_account.data[..])?;
+    assert_eq!(purchase.buyer, bot_trader.pubkey());
+    assert_eq!(purchase.sol_amount, 1_000_000_000);
+
+    let flagged_account = banks_client
+        .get_account(flagged_wallet_address(manager, &bot_trader.pubkey()))
+        .await?
+        .unwrap();
+    let flagged = abc_token::FlaggedWallet::try_deserialize(&mut &flagged_account.data[..])?;
+    assert_eq!(flagged.wallet, bot_trader.pubkey());
+    assert_eq!(flagged.flag_count, 1);
+    assert_eq!(flagged.total_sol, 1_000_000_000);
+    assert_eq!(flagged.first_slot, flagged.last_slot);
+    assert!(!flagged.cleared);
+
+    let early_claim = claim_refund_instruction(manager, &bot_trader.pubkey(), mint);
+    assert!(
+        process_instruction(banks_client, payer, early_claim, &[&bot_trader])
+            .await
+            .is_err()
+    );
+
+    Ok(bot_trader)
+}
+
+fn claim_refund_instruction(manager: &Pubkey, buyer: &Pubkey, mint: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(*buyer, true),
+            AccountMeta::new(*manager, false),
+            AccountMeta::new_readonly(launch_config_address(mint), false),
+            AccountMeta::new(monitored_purchase_address(manager, buyer), false),
+            AccountMeta::new(mint_pda(b"treasury", mint), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: CLAIM_REFUND_DISCRIMINATOR.to_vec(),
+    }
+}
+
+fn clear_flagged_wallet_instruction(
+    authority: &Pubkey,
+    manager: &Pubkey,
+    wallet: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(*manager, false),
+            AccountMeta::new(flagged_wallet_address(manager, wallet), false),
+        ],
+        data: CLEAR_FLAGGED_WALLET_DISCRIMINATOR.to_vec(),
+    }
+}
+
+async fn test_clear_flagged_wallet(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    manager: &Pubkey,
+    authority: &Keypair,
+    bot_trader: &Keypair,
+) -> TestResult<()> {
+    let wallet = bot_trader.pubkey();
+
+    // The flagged wallet can't clear itself
+    let self_clear = clear_flagged_wallet_instruction(&wallet, manager, &wallet);
+    assert!(
+        process_instruction(banks_client, payer, self_clear, &[bot_trader])
+            .await
+            .is_err()
+    );
 
-    // Bot should have received tokens, but counter-trade should have neutralized price impact
-    assert!(bot_token_balance > 0);
-    assert!(pool_token_balance > 0);
+    let clear_ix = clear_flagged_wallet_instruction(&authority.pubkey(), manager, &wallet);
+    process_instruction(banks_client, payer, clear_ix, &[authority]).await?;
+
+    // History is kept after clearing
+    let flagged_account = banks_client
+        .get_account(flagged_wallet_address(manager, &wallet))
+        .await?
+        .unwrap();
+    let flagged = abc_token::FlaggedWallet::try_deserialize(&mut &flagged_account.data[..])?;
+    assert!(flagged.cleared);
+    assert_eq!(flagged.flag_count, 1);
+    assert_eq!(flagged.total_sol, 1_000_000_000);
 
     Ok(())
 }
 
-async fn test_normal_trading(
+async fn test_refund(
     banks_client: &mut BanksClient,
     payer: &Keypair,
     manager: &Pubkey,
-    pool_account: &Pubkey,
-    token_vault: &Pubkey,
-    sol_vault: &Pubkey,
+    bot_trader: &Keypair,
     mint: &Pubkey,
-    raydium_program_id: &Pubkey,
 ) -> TestResult<()> {
-    // Advance clock past monitoring period by processing empty transactions
-    for _ in 0..6 {
-        let recent_blockhash = banks_client.get_latest_blockhash().await?;
-        let tx = Transaction::new_signed_with_payer(
-            &[],
-            Some(&payer.pubkey()),
-            &[payer],
-            recent_blockhash,
-        );
-        banks_client.process_transaction(tx).await?;
-    }
+    let purchase_address = monitored_purchase_address(manager, &bot_trader.pubkey());
+    let purchase_rent = banks_client
+        .get_account(purchase_address)
+        .await?
+        .unwrap()
+        .lamports;
+    let lamports_before = banks_client.get_balance(bot_trader.pubkey()).await?;
+
+    let claim_ix = claim_refund_instruction(manager, &bot_trader.pubkey(), mint);
+    process_instruction(banks_client, payer, claim_ix, &[bot_trader]).await?;
+
+    // 10% penalty stays in the treasury; the record is closed back to the buyer
+    assert_eq!(
+        banks_client.get_balance(bot_trader.pubkey()).await?,
+        lamports_before + 900_000_000 + purchase_rent
+    );
+    assert!(banks_client.get_account(purchase_address).await?.is_none());
+
+    let manager_account = banks_client.get_account(*manager).await?.unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.refunded_sol, 900_000_000);
+
+    // Nothing left to claim (bot pays the fee so the transaction isn't a duplicate)
+    let second_claim = claim_refund_instruction(manager, &bot_trader.pubkey(), mint);
+    assert!(
+        process_instruction(banks_client, bot_trader, second_claim, &[])
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
 
+async fn test_normal_trading(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    manager: &Pubkey,
+    reserve_account: &Pubkey,
+    pool: &MockPool,
+    mint: &Pubkey,
+) -> TestResult<()> {
     let normal_trader = Keypair::new();
     let normal_token_account =
         create_token_account(banks_client, payer, mint, &normal_trader.pubkey()).await?;
+    let normal_sol_account = create_token_account(
+        banks_client,
+        payer,
+        &spl_token::native_mint::id(),
+        &normal_trader.pubkey(),
+    )
+    .await?;
 
     // Fund normal trader
-    let recent_blockhash = banks_client.get_latest_blockhash().await?;
-    let fund_tx = Transaction::new_signed_with_payer(
-        &[system_instruction::transfer(
-            &payer.pubkey(),
-            &normal_trader.pubkey(),
-            5_000_000_000, // 5 SOL
-        )],
-        Some(&payer.pubkey()),
-        &[payer],
-        recent_blockhash,
-    );
-    banks_client.process_transaction(fund_tx).await?;
+    fund_account(banks_client, payer, &normal_trader.pubkey(), 1_000_000_000).await?;
+    wrap_sol(banks_client, payer, &normal_sol_account, 2_000_000_000).await?;
 
-    // Execute normal buy
-    let buy_accounts = vec![
-        AccountMeta::new(*manager, false),
-        AccountMeta::new(normal_trader.pubkey(), true),
-        AccountMeta::new(normal_token_account, false),
-        AccountMeta::new(*pool_account, false),
-        AccountMeta::new(*token_vault, false),
-        AccountMeta::new(*sol_vault, false),
-        AccountMeta::new_readonly(*raydium_program_id, false),
-        AccountMeta::new_readonly(spl_token::id(), false),
-        AccountMeta::new_readonly(system_program::id(), false),
-        AccountMeta::new_readonly(sysvar::clock::id(), false),
-    ];
+    // Quote the buy the same way the program does
+    let coin_reserve = get_token_balance(banks_client, &pool.pool_coin_vault).await?;
+    let pc_reserve = get_token_balance(banks_client, &pool.pool_pc_vault).await?;
+    let expected_tokens = swap_output(500_000_000, pc_reserve, coin_reserve);
 
-    let mut buy_data = vec![0; 8 + 8];
-    buy_data[0..8].copy_from_slice(&[242, 35, 198, 137, 82, 225, 242, 182]); // Buy discriminator
-    buy_data[8..16].copy_from_slice(&500_000_000u64.to_le_bytes());
+    // A pool whose quote side isn't wrapped SOL can't be priced in SOL
+    let mut wrong_quote_accounts = pool.trade_accounts(&normal_sol_account);
+    wrong_quote_accounts[6].pubkey = pool.pool_coin_vault;
+    let wrong_quote_ix = trade_instruction(
+        BUY_DISCRIMINATOR,
+        500_000_000,
+        expected_tokens,
+        manager,
+        &normal_trader.pubkey(),
+        &normal_token_account,
+        reserve_account,
+        mint,
+        wrong_quote_accounts,
+    );
+    assert!(
+        process_instruction(banks_client, payer, wrong_quote_ix, &[&normal_trader])
+            .await
+            .is_err()
+    );
 
-    let buy_ix = Instruction {
-        program_id: abc_token::id(),
-        accounts: buy_accounts,
-        data: buy_data,
-    };
+    // Execute normal buy
+    let buy_ix = trade_instruction(
+        BUY_DISCRIMINATOR,
+        500_000_000,
+        expected_tokens,
+        manager,
+        &normal_trader.pubkey(),
+        &normal_token_account,
+        reserve_account,
+        mint,
+        pool.trade_accounts(&normal_sol_account),
+    );
 
     let recent_blockhash = banks_client.get_latest_blockhash().await?;
     let buy_tx = Transaction::new_signed_with_payer(
@@ -505,31 +979,46 @@ async fn test_normal_trading(
 
     // Check token balance after buy
     let token_balance_after_buy = get_token_balance(banks_client, &normal_token_account).await?;
-    assert!(token_balance_after_buy > 0);
+    assert_eq!(token_balance_after_buy, expected_tokens);
+    assert_eq!(
+        get_token_balance(banks_client, &normal_sol_account).await?,
+        1_500_000_000
+    );
 
-    // Test selling
-    let sell_accounts = vec![
-        AccountMeta::new(*manager, false),
-        AccountMeta::new(normal_trader.pubkey(), true),
-        AccountMeta::new(normal_token_account, false),
-        AccountMeta::new(*pool_account, false),
-        AccountMeta::new(*token_vault, false),
-        AccountMeta::new(*sol_vault, false),
-        AccountMeta::new_readonly(*raydium_program_id, false),
-        AccountMeta::new_readonly(spl_token::id(), false),
-        AccountMeta::new_readonly(system_program::id(), false),
-        AccountMeta::new_readonly(sysvar::clock::id(), false),
-    ];
+    // A minimum above the quote is rejected before reaching the pool
+    let greedy_ix = trade_instruction(
+        SELL_DISCRIMINATOR,
+        token_balance_after_buy / 2,
+        u64::MAX,
+        manager,
+        &normal_trader.pubkey(),
+        &normal_token_account,
+        reserve_account,
+        mint,
+        pool.trade_accounts(&normal_sol_account),
+    );
 
-    let mut sell_data = vec![0; 8 + 8];
-    sell_data[0..8].copy_from_slice(&[183, 18, 70, 156, 148, 109, 161, 34]); // Sell discriminator
-    sell_data[8..16].copy_from_slice(&(token_balance_after_buy / 2).to_le_bytes());
+    let recent_blockhash = banks_client.get_latest_blockhash().await?;
+    let greedy_tx = Transaction::new_signed_with_payer(
+        &[greedy_ix],
+        Some(&payer.pubkey()),
+        &[payer, &normal_trader],
+        recent_blockhash,
+    );
+    assert!(banks_client.process_transaction(greedy_tx).await.is_err());
 
-    let sell_ix = Instruction {
-        program_id: abc_token::id(),
-        accounts: sell_accounts,
-        data: sell_data,
-    };
+    // Test selling
+    let sell_ix = trade_instruction(
+        SELL_DISCRIMINATOR,
+        token_balance_after_buy / 2,
+        1,
+        manager,
+        &normal_trader.pubkey(),
+        &normal_token_account,
+        reserve_account,
+        mint,
+        pool.trade_accounts(&normal_sol_account),
+    );
 
     let recent_blockhash = banks_client.get_latest_blockhash().await?;
     let sell_tx = Transaction::new_signed_with_payer(
@@ -543,10 +1032,2960 @@ async fn test_normal_trading(
 
     // Verify token balance after sell
     let final_token_balance = get_token_balance(banks_client, &normal_token_account).await?;
-    assert_eq!(final_token_balance, token_balance_after_buy / 2);
+    assert_eq!(
+        final_token_balance,
+        token_balance_after_buy - token_balance_after_buy / 2
+    );
+    assert!(get_token_balance(banks_client, &normal_sol_account).await? > 1_500_000_000);
 
-    Ok(())
-}
+    // Exact-output swaps go straight to the pool through the same interface
+    let sol_before = get_token_balance(banks_client, &normal_sol_account).await?;
+    let swap_out_ix = raydium::swap_base_out(
+        &pool.program_id,
+        &pool.swap_keys(),
+        &normal_sol_account,
+        &normal_token_account,
+        &normal_trader.pubkey(),
+        100_000_000,
+        1_000,
+    );
+
+    let recent_blockhash = banks_client.get_latest_blockhash().await?;
+    let swap_out_tx = Transaction::new_signed_with_payer(
+        &[swap_out_ix],
+        Some(&payer.pubkey()),
+        &[payer, &normal_trader],
+        recent_blockhash,
+    );
+
+    banks_client.process_transaction(swap_out_tx).await?;
+
+    assert_eq!(
+        get_token_balance(banks_client, &normal_token_account).await?,
+        final_token_balance + 1_000
+    );
+    assert!(get_token_balance(banks_client, &normal_sol_account).await? < sol_before);
+
+    Ok(())
+}
+
+fn launch_config_address(mint: &Pubkey) -> Pubkey {
+    mint_pda(b"launch_config", mint)
+}
+
+fn monitored_purchase_address(manager: &Pubkey, buyer: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"monitored_purchase", manager.as_ref(), buyer.as_ref()],
+        &abc_token::id(),
+    )
+    .0
+}
+
+fn flagged_wallet_address(manager: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"flagged_wallet", manager.as_ref(), wallet.as_ref()],
+        &abc_token::id(),
+    )
+    .0
+}
+
+fn wallet_purchases_address(manager: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"wallet_purchases", manager.as_ref(), wallet.as_ref()],
+        &abc_token::id(),
+    )
+    .0
+}
+
+fn mint_pda(seed: &[u8], mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[seed, mint.as_ref()], &abc_token::id()).0
+}
+
+async fn process_instruction(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> TestResult<()> {
+    let mut all_signers = vec![payer];
+    all_signers.extend_from_slice(signers);
+
+    let recent_blockhash = banks_client.get_latest_blockhash().await?;
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &all_signers,
+        recent_blockhash,
+    );
+
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn get_native_pool(
+    banks_client: &mut BanksClient,
+    pool: &Pubkey,
+) -> TestResult<abc_token::NativePool> {
+    let account = banks_client.get_account(*pool).await?.unwrap();
+    Ok(abc_token::NativePool::try_deserialize(
+        &mut &account.data[..],
+    )?)
+}
+
+/// A launch on the built-in pool, seeded with 20 SOL and 200e9 tokens
+struct NativeLaunch {
+    authority: Keypair,
+    mint: Pubkey,
+    manager: Pubkey,
+    reserve_account: Pubkey,
+    native_pool: Pubkey,
+    pool_vault: Pubkey,
+    treasury: Pubkey,
+    authority_lp_account: Pubkey,
+    liquidity_accounts: Vec<AccountMeta>,
+    lp_balance: u64,
+}
+
+fn native_launch_params() -> abc_token::LaunchConfigParams {
+    abc_token::LaunchConfigParams {
+        dex: abc_token::DexKind::ConstantProduct,
+        dex_program: abc_token::id(),
+        ..Default::default()
+    }
+}
+
+async fn setup_native_launch(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    params: abc_token::LaunchConfigParams,
+) -> TestResult<NativeLaunch> {
+    let mint_keypair = Keypair::new();
+    let authority = Keypair::new();
+    let mint = mint_keypair.pubkey();
+    setup_mint(banks_client, payer, &mint_keypair, &authority).await?;
+    fund_account(banks_client, payer, &authority.pubkey(), 30_000_000_000).await?;
+
+    let token_source =
+        create_token_account(banks_client, payer, &mint, &authority.pubkey()).await?;
+    mint_tokens(
+        banks_client,
+        payer,
+        &mint,
+        &token_source,
+        &authority,
+        1_000_000_000_000,
+    )
+    .await?;
+
+    let manager = mint_pda(b"abc_manager", &mint);
+    let reserve_account = mint_pda(b"reserve", &mint);
+    let native_pool = mint_pda(b"native_pool", &mint);
+    let pool_vault = mint_pda(b"pool_vault", &mint);
+    let lp_mint = mint_pda(b"lp_mint", &mint);
+    let treasury = mint_pda(b"treasury", &mint);
+
+    // Initialize without any pool accounts
+    let init_ix = initialize_instruction(
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(manager, false),
+            AccountMeta::new(launch_config_address(&mint), false),
+            AccountMeta::new(token_source, false),
+            AccountMeta::new(reserve_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ],
+        400_000_000_000,
+        &params,
+    );
+    process_instruction(banks_client, payer, init_ix, &[&authority]).await?;
+
+    let manager_account = banks_client.get_account(manager).await?.unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.raydium_pool, native_pool);
+
+    let create_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new_readonly(manager, false),
+            AccountMeta::new_readonly(launch_config_address(&mint), false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(native_pool, false),
+            AccountMeta::new(pool_vault, false),
+            AccountMeta::new(lp_mint, false),
+            AccountMeta::new_readonly(treasury, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: CREATE_NATIVE_POOL_DISCRIMINATOR.to_vec(),
+    };
+    process_instruction(banks_client, payer, create_ix, &[&authority]).await?;
+
+    // Seed the pool at 10 tokens per lamport
+    let authority_lp_account =
+        create_token_account(banks_client, payer, &lp_mint, &authority.pubkey()).await?;
+    let liquidity_accounts = vec![
+        AccountMeta::new(authority.pubkey(), true),
+        AccountMeta::new(native_pool, false),
+        AccountMeta::new(pool_vault, false),
+        AccountMeta::new(lp_mint, false),
+        AccountMeta::new(treasury, false),
+        AccountMeta::new(token_source, false),
+        AccountMeta::new(authority_lp_account, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    let add_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: liquidity_accounts.clone(),
+        data: instruction_data(
+            ADD_LIQUIDITY_DISCRIMINATOR,
+            &[20_000_000_000, 200_000_000_000, 1],
+        ),
+    };
+    process_instruction(banks_client, payer, add_ix, &[&authority]).await?;
+
+    let pool_state = get_native_pool(banks_client, &native_pool).await?;
+    assert_eq!(pool_state.sol_reserve, 20_000_000_000);
+    assert_eq!(pool_state.token_reserve, 200_000_000_000);
+    let lp_balance = get_token_balance(banks_client, &authority_lp_account).await?;
+    assert_eq!(lp_balance, 63_245_553_203); // sqrt(20e9 * 200e9)
+
+    // Presale and auction launches are scheduled by their tests
+    if !params.has_presale() && params.auction_tokens == 0 {
+        schedule_launch(banks_client, payer, &authority, &manager).await?;
+    }
+
+    Ok(NativeLaunch {
+        authority,
+        mint,
+        manager,
+        reserve_account,
+        native_pool,
+        pool_vault,
+        treasury,
+        authority_lp_account,
+        liquidity_accounts,
+        lp_balance,
+    })
+}
+
+// Launch on the built-in pool: no external program, LPs fund it directly and
+// buy/sell/swap settle against the pool vault and treasury
+#[tokio::test]
+async fn test_native_pool_flow() -> TestResult<()> {
+    let program_test = ProgramTest::new("abc_token", abc_token::id(), processor!(abc_token::entry));
+    let mut context = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    let NativeLaunch {
+        authority,
+        mint,
+        manager,
+        reserve_account,
+        native_pool,
+        pool_vault,
+        treasury,
+        authority_lp_account,
+        liquidity_accounts,
+        lp_balance,
+    } = setup_native_launch(&mut context.banks_client, &payer, native_launch_params()).await?;
+
+    // A flagged buy goes to the treasury and the counter-trade sells reserve
+    // tokens into the pool, leaving its SOL proceeds in the treasury
+    let bot = Keypair::new();
+    let bot_token_account =
+        create_token_account(&mut context.banks_client, &payer, &mint, &bot.pubkey()).await?;
+    fund_account(
+        &mut context.banks_client,
+        &payer,
+        &bot.pubkey(),
+        2_000_000_000,
+    )
+    .await?;
+
+    let pool_remaining_accounts = vec![
+        AccountMeta::new(native_pool, false),
+        AccountMeta::new(pool_vault, false),
+    ];
+    let bot_buy_ix = trade_instruction(
+        BUY_DISCRIMINATOR,
+        1_000_000_000,
+        0,
+        &manager,
+        &bot.pubkey(),
+        &bot_token_account,
+        &reserve_account,
+        &mint,
+        pool_remaining_accounts.clone(),
+    );
+    process_instruction(&mut context.banks_client, &payer, bot_buy_ix, &[&bot]).await?;
+
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &bot_token_account).await?,
+        0
+    );
+    let counter_traded = swap_output(1_000_000_000, 20_000_000_000, 200_000_000_000);
+    let sol_from_counter_trade = swap_output(counter_traded, 200_000_000_000, 20_000_000_000);
+    let pool_state = get_native_pool(&mut context.banks_client, &native_pool).await?;
+    assert_eq!(pool_state.token_reserve, 200_000_000_000 + counter_traded);
+    assert_eq!(
+        pool_state.sol_reserve,
+        20_000_000_000 - sol_from_counter_trade
+    );
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &pool_vault).await?,
+        pool_state.token_reserve
+    );
+    assert_eq!(
+        context.banks_client.get_balance(treasury).await?,
+        21_000_000_000
+    );
+
+    // Swapping straight against the pool is closed during the window too
+    let trader = Keypair::new();
+    let trader_token_account =
+        create_token_account(&mut context.banks_client, &payer, &mint, &trader.pubkey()).await?;
+    fund_account(
+        &mut context.banks_client,
+        &payer,
+        &trader.pubkey(),
+        2_000_000_000,
+    )
+    .await?;
+
+    let swap_accounts = vec![
+        AccountMeta::new_readonly(manager, false),
+        AccountMeta::new_readonly(launch_config_address(&mint), false),
+        AccountMeta::new(trader.pubkey(), true),
+        AccountMeta::new(trader_token_account, false),
+        AccountMeta::new(native_pool, false),
+        AccountMeta::new(pool_vault, false),
+        AccountMeta::new(treasury, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    let early_swap_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: swap_accounts.clone(),
+        data: swap_data(0, 500_000_000, 1),
+    };
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, early_swap_ix, &[&trader])
+            .await
+            .is_err()
+    );
+
+    let slot = context.banks_client.get_root_slot().await?;
+    context.warp_to_slot(slot + 10).unwrap();
+
+    // A guardian can halt trading and lift the halt again
+    let guardian = Keypair::new();
+    let set_guardian_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(authority.pubkey(), true),
+            AccountMeta::new(manager, false),
+        ],
+        data: [
+            SET_GUARDIAN_DISCRIMINATOR.as_ref(),
+            guardian.pubkey().as_ref(),
+        ]
+        .concat(),
+    };
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        set_guardian_ix,
+        &[&authority],
+    )
+    .await?;
+
+    let pause_accounts = vec![
+        AccountMeta::new_readonly(guardian.pubkey(), true),
+        AccountMeta::new(manager, false),
+        AccountMeta::new_readonly(launch_config_address(&mint), false),
+    ];
+    let pause_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: pause_accounts.clone(),
+        data: [PAUSE_TRADING_DISCRIMINATOR.as_ref(), &[1]].concat(),
+    };
+    process_instruction(&mut context.banks_client, &payer, pause_ix, &[&guardian]).await?;
+
+    let paused_buy_ix = trade_instruction(
+        BUY_DISCRIMINATOR,
+        400_000_000,
+        1,
+        &manager,
+        &trader.pubkey(),
+        &trader_token_account,
+        &reserve_account,
+        &mint,
+        pool_remaining_accounts.clone(),
+    );
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, paused_buy_ix, &[&trader])
+            .await
+            .is_err()
+    );
+
+    let resume_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: pause_accounts,
+        data: [RESUME_TRADING_DISCRIMINATOR.as_ref(), &[1]].concat(),
+    };
+    process_instruction(&mut context.banks_client, &payer, resume_ix, &[&guardian]).await?;
+
+    // Regular buy settles in plain SOL, no wrapped-SOL account needed
+    let expected_tokens = swap_output(
+        500_000_000,
+        pool_state.sol_reserve,
+        pool_state.token_reserve,
+    );
+    let buy_ix = trade_instruction(
+        BUY_DISCRIMINATOR,
+        500_000_000,
+        expected_tokens,
+        &manager,
+        &trader.pubkey(),
+        &trader_token_account,
+        &reserve_account,
+        &mint,
+        pool_remaining_accounts,
+    );
+    process_instruction(&mut context.banks_client, &payer, buy_ix, &[&trader]).await?;
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &trader_token_account).await?,
+        expected_tokens
+    );
+
+    // Sell half back through `swap`
+    let lamports_before = context.banks_client.get_balance(trader.pubkey()).await?;
+    let pool_state = get_native_pool(&mut context.banks_client, &native_pool).await?;
+    let expected_sol = swap_output(
+        expected_tokens / 2,
+        pool_state.token_reserve,
+        pool_state.sol_reserve,
+    );
+    let swap_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: swap_accounts.clone(),
+        data: swap_data(1, expected_tokens / 2, expected_sol),
+    };
+    process_instruction(&mut context.banks_client, &payer, swap_ix, &[&trader]).await?;
+    assert_eq!(
+        context.banks_client.get_balance(trader.pubkey()).await?,
+        lamports_before + expected_sol
+    );
+
+    // Closing shuts trading for good, but liquidity can still come out
+    let close_ix = close_launch_instruction(&trader.pubkey(), &mint);
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, close_ix, &[&trader])
+            .await
+            .is_err()
+    );
+    let close_ix = close_launch_instruction(&authority.pubkey(), &mint);
+    process_instruction(&mut context.banks_client, &payer, close_ix, &[&authority]).await?;
+    let manager_account = context.banks_client.get_account(manager).await?.unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.phase, abc_token::LaunchPhase::Closed);
+
+    let swap_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: swap_accounts,
+        data: swap_data(1, expected_tokens / 4, 1),
+    };
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, swap_ix, &[&trader])
+            .await
+            .is_err()
+    );
+
+    // Full withdrawal empties the pool but leaves the captured SOL behind
+    let pool_state = get_native_pool(&mut context.banks_client, &native_pool).await?;
+    let treasury_before = context.banks_client.get_balance(treasury).await?;
+    let remove_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: liquidity_accounts,
+        data: instruction_data(
+            REMOVE_LIQUIDITY_DISCRIMINATOR,
+            &[lp_balance, pool_state.sol_reserve, pool_state.token_reserve],
+        ),
+    };
+    process_instruction(&mut context.banks_client, &payer, remove_ix, &[&authority]).await?;
+
+    let emptied = get_native_pool(&mut context.banks_client, &native_pool).await?;
+    assert_eq!((emptied.sol_reserve, emptied.token_reserve), (0, 0));
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &authority_lp_account).await?,
+        0
+    );
+    assert_eq!(
+        context.banks_client.get_balance(treasury).await?,
+        treasury_before - pool_state.sol_reserve
+    );
+
+    // Renouncing waits until a queued config update is dealt with
+    let pending_update = mint_pda(b"config_update", &mint);
+    let update = abc_token::ConfigUpdate {
+        min_trade_sol: abc_token::constants::DEFAULT_MIN_TRADE_SOL,
+        max_trade_sol: abc_token::constants::DEFAULT_MAX_TRADE_SOL,
+        max_price_impact_bps: abc_token::constants::DEFAULT_MAX_PRICE_IMPACT_BPS,
+        slippage_tolerance_bps: abc_token::constants::DEFAULT_SLIPPAGE_TOLERANCE_BPS,
+        raydium_pool: native_pool,
+    };
+    let propose_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new_readonly(manager, false),
+            AccountMeta::new_readonly(launch_config_address(&mint), false),
+            AccountMeta::new(pending_update, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: [
+            PROPOSE_CONFIG_UPDATE_DISCRIMINATOR.as_ref(),
+            &update.try_to_vec().unwrap(),
+        ]
+        .concat(),
+    };
+    process_instruction(&mut context.banks_client, &payer, propose_ix, &[&authority]).await?;
+
+    let renounce_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(authority.pubkey(), true),
+            AccountMeta::new(manager, false),
+            AccountMeta::new_readonly(pending_update, false),
+        ],
+        data: RENOUNCE_AUTHORITY_DISCRIMINATOR.to_vec(),
+    };
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        renounce_ix.clone(),
+        &[&authority]
+    )
+    .await
+    .is_err());
+
+    let cancel_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new_readonly(manager, false),
+            AccountMeta::new(pending_update, false),
+        ],
+        data: CANCEL_CONFIG_UPDATE_DISCRIMINATOR.to_vec(),
+    };
+    process_instruction(&mut context.banks_client, &payer, cancel_ix, &[&authority]).await?;
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        renounce_ix,
+        &[&authority],
+    )
+    .await?;
+    let manager_account = context.banks_client.get_account(manager).await?.unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.authority, Pubkey::default());
+
+    Ok(())
+}
+
+// Fair-launch mode: early buys go through at market but each wallet is
+// capped per slot window instead of being counter-traded
+#[tokio::test]
+async fn test_fair_launch_wallet_caps() -> TestResult<()> {
+    let program_test = ProgramTest::new("abc_token", abc_token::id(), processor!(abc_token::entry));
+    let mut context = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    let params = abc_token::LaunchConfigParams {
+        monitoring_blocks: 20,
+        wallet_cap_sol: 1_000_000_000,
+        wallet_cap_window_slots: 4,
+        ..native_launch_params()
+    };
+    let launch = setup_native_launch(&mut context.banks_client, &payer, params).await?;
+
+    let trader = Keypair::new();
+    let trader_token_account = create_token_account(
+        &mut context.banks_client,
+        &payer,
+        &launch.mint,
+        &trader.pubkey(),
+    )
+    .await?;
+    fund_account(
+        &mut context.banks_client,
+        &payer,
+        &trader.pubkey(),
+        3_000_000_000,
+    )
+    .await?;
+
+    let buy_ix = |sol_amount: u64| {
+        trade_instruction(
+            BUY_DISCRIMINATOR,
+            sol_amount,
+            1,
+            &launch.manager,
+            &trader.pubkey(),
+            &trader_token_account,
+            &launch.reserve_account,
+            &launch.mint,
+            vec![
+                AccountMeta::new(launch.native_pool, false),
+                AccountMeta::new(launch.pool_vault, false),
+            ],
+        )
+    };
+
+    // Inside the window the buy fills instead of being counter-traded
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        buy_ix(600_000_000),
+        &[&trader],
+    )
+    .await?;
+    assert!(get_token_balance(&mut context.banks_client, &trader_token_account).await? > 0);
+    assert!(context
+        .banks_client
+        .get_account(flagged_wallet_address(&launch.manager, &trader.pubkey()))
+        .await?
+        .is_none());
+
+    // 0.6 + 0.5 SOL is over the 1 SOL cap
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        buy_ix(500_000_000),
+        &[&trader],
+    )
+    .await
+    .is_err());
+
+    // The next window starts from zero
+    let manager_account = context
+        .banks_client
+        .get_account(launch.manager)
+        .await?
+        .unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    context.warp_to_slot(manager_data.launch_slot + 4).unwrap();
+
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        buy_ix(500_000_000),
+        &[&trader],
+    )
+    .await?;
+
+    let purchases_account = context
+        .banks_client
+        .get_account(wallet_purchases_address(&launch.manager, &trader.pubkey()))
+        .await?
+        .unwrap();
+    let purchases = abc_token::WalletPurchases::try_deserialize(&mut &purchases_account.data[..])?;
+    assert_eq!(purchases.window_start_slot, manager_data.launch_slot + 4);
+    assert_eq!(purchases.window_sol, 500_000_000);
+    assert_eq!(purchases.total_sol, 1_100_000_000);
+
+    Ok(())
+}
+
+fn presale_buy_instruction(
+    launch: &NativeLaunch,
+    buyer: &Pubkey,
+    buyer_token_account: &Pubkey,
+    sol_amount: u64,
+    allocation: u64,
+    proof: &[[u8; 32]],
+) -> Instruction {
+    let mut data = instruction_data(PRESALE_BUY_DISCRIMINATOR, &[sol_amount, allocation]);
+    data.extend_from_slice(&(proof.len() as u32).to_le_bytes());
+    for node in proof {
+        data.extend_from_slice(node);
+    }
+
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(*buyer, true),
+            AccountMeta::new(launch.manager, false),
+            AccountMeta::new_readonly(launch_config_address(&launch.mint), false),
+            AccountMeta::new(*buyer_token_account, false),
+            AccountMeta::new(launch.reserve_account, false),
+            AccountMeta::new(launch.treasury, false),
+            AccountMeta::new(
+                Pubkey::find_program_address(
+                    &[b"presale_purchase", launch.manager.as_ref(), buyer.as_ref()],
+                    &abc_token::id(),
+                )
+                .0,
+                false,
+            ),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    }
+}
+
+// Allowlisted wallets buy at the fixed presale price with a Merkle proof,
+// then the authority schedules public trading
+#[tokio::test]
+async fn test_presale_flow() -> TestResult<()> {
+    let program_test = ProgramTest::new("abc_token", abc_token::id(), processor!(abc_token::entry));
+    let mut context = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    let buyer = Keypair::new();
+    let other = Keypair::new();
+    let buyer_leaf = abc_token::merkle::leaf(&buyer.pubkey(), 2_000_000_000);
+    let other_leaf = abc_token::merkle::leaf(&other.pubkey(), 1_000_000_000);
+    let root = abc_token::merkle::hash_pair(&buyer_leaf, &other_leaf);
+
+    let params = abc_token::LaunchConfigParams {
+        presale_merkle_root: root,
+        presale_tokens_per_sol: 1_000_000_000,
+        ..native_launch_params()
+    };
+    // The pool can be seeded while the presale runs
+    let launch = setup_native_launch(&mut context.banks_client, &payer, params).await?;
+
+    let manager_account = context
+        .banks_client
+        .get_account(launch.manager)
+        .await?
+        .unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.phase, abc_token::LaunchPhase::Presale);
+
+    let buyer_token_account = create_token_account(
+        &mut context.banks_client,
+        &payer,
+        &launch.mint,
+        &buyer.pubkey(),
+    )
+    .await?;
+    fund_account(
+        &mut context.banks_client,
+        &payer,
+        &buyer.pubkey(),
+        3_000_000_000,
+    )
+    .await?;
+
+    let treasury_before = context.banks_client.get_balance(launch.treasury).await?;
+    let presale_ix = presale_buy_instruction(
+        &launch,
+        &buyer.pubkey(),
+        &buyer_token_account,
+        1_500_000_000,
+        2_000_000_000,
+        &[other_leaf],
+    );
+    process_instruction(&mut context.banks_client, &payer, presale_ix, &[&buyer]).await?;
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &buyer_token_account).await?,
+        1_500_000_000
+    );
+    assert_eq!(
+        context.banks_client.get_balance(launch.treasury).await?,
+        treasury_before + 1_500_000_000
+    );
+
+    // Over the allocation, or claiming a bigger one than the leaf holds
+    let over_allocation = presale_buy_instruction(
+        &launch,
+        &buyer.pubkey(),
+        &buyer_token_account,
+        600_000_000,
+        2_000_000_000,
+        &[other_leaf],
+    );
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        over_allocation,
+        &[&buyer]
+    )
+    .await
+    .is_err());
+    let inflated_allocation = presale_buy_instruction(
+        &launch,
+        &buyer.pubkey(),
+        &buyer_token_account,
+        600_000_000,
+        3_000_000_000,
+        &[other_leaf],
+    );
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        inflated_allocation,
+        &[&buyer]
+    )
+    .await
+    .is_err());
+
+    // Public buys wait for the presale to end
+    let early_buy = trade_instruction(
+        BUY_DISCRIMINATOR,
+        500_000_000,
+        1,
+        &launch.manager,
+        &buyer.pubkey(),
+        &buyer_token_account,
+        &launch.reserve_account,
+        &launch.mint,
+        vec![
+            AccountMeta::new(launch.native_pool, false),
+            AccountMeta::new(launch.pool_vault, false),
+        ],
+    );
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, early_buy, &[&buyer])
+            .await
+            .is_err()
+    );
+
+    let slot = context.banks_client.get_root_slot().await?;
+    context.warp_to_slot(slot + 10).unwrap();
+
+    let start_slot = schedule_launch(
+        &mut context.banks_client,
+        &payer,
+        &launch.authority,
+        &launch.manager,
+    )
+    .await?;
+
+    // Scheduling ends the presale and restarts the launch window
+    let manager_account = context
+        .banks_client
+        .get_account(launch.manager)
+        .await?
+        .unwrap();
+    let scheduled = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(scheduled.phase, abc_token::LaunchPhase::Scheduled);
+    assert_eq!(scheduled.launch_slot, start_slot);
+    assert!(start_slot > manager_data.launch_slot);
+
+    let late_presale = presale_buy_instruction(
+        &launch,
+        &buyer.pubkey(),
+        &buyer_token_account,
+        500_000_000,
+        2_000_000_000,
+        &[other_leaf],
+    );
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, late_presale, &[&buyer])
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+fn commitment_address(manager: &Pubkey, buyer: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"commitment", manager.as_ref(), buyer.as_ref()],
+        &abc_token::id(),
+    )
+    .0
+}
+
+// Commit-reveal mode: buyers escrow SOL behind a hash during the window,
+// reveal after it, and split a fixed allocation pro-rata
+#[tokio::test]
+async fn test_commit_reveal_flow() -> TestResult<()> {
+    let program_test = ProgramTest::new("abc_token", abc_token::id(), processor!(abc_token::entry));
+    let mut context = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    let params = abc_token::LaunchConfigParams {
+        commit_allocation_tokens: 90_000_000_000,
+        reveal_window_slots: 20,
+        ..native_launch_params()
+    };
+    let launch = setup_native_launch(&mut context.banks_client, &payer, params).await?;
+    let manager = launch.manager;
+
+    // (escrow, amount revealed); the last buyer never reveals
+    let orders = [
+        (2_000_000_000, Some(1_000_000_000)),
+        (2_000_000_000, Some(2_000_000_000)),
+        (1_000_000_000, None),
+    ];
+    let salt = [9; 32];
+
+    let mut buyers = Vec::new();
+    for (escrow, amount) in orders {
+        let buyer = Keypair::new();
+        let token_account = create_token_account(
+            &mut context.banks_client,
+            &payer,
+            &launch.mint,
+            &buyer.pubkey(),
+        )
+        .await?;
+        fund_account(
+            &mut context.banks_client,
+            &payer,
+            &buyer.pubkey(),
+            3_000_000_000,
+        )
+        .await?;
+
+        let hash = abc_token::PurchaseCommitment::hash(&buyer.pubkey(), amount.unwrap_or(0), &salt);
+        let commit_ix = Instruction {
+            program_id: abc_token::id(),
+            accounts: vec![
+                AccountMeta::new(buyer.pubkey(), true),
+                AccountMeta::new(manager, false),
+                AccountMeta::new_readonly(launch_config_address(&launch.mint), false),
+                AccountMeta::new(launch.treasury, false),
+                AccountMeta::new(commitment_address(&manager, &buyer.pubkey()), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: [
+                COMMIT_BUY_DISCRIMINATOR.as_ref(),
+                &hash,
+                &u64::to_le_bytes(escrow),
+            ]
+            .concat(),
+        };
+        process_instruction(&mut context.banks_client, &payer, commit_ix, &[&buyer]).await?;
+
+        buyers.push((buyer, token_account));
+    }
+
+    // Plain buys are closed while commitments are taken
+    let (first_buyer, first_token_account) = &buyers[0];
+    let buy_ix = trade_instruction(
+        BUY_DISCRIMINATOR,
+        500_000_000,
+        1,
+        &manager,
+        &first_buyer.pubkey(),
+        first_token_account,
+        &launch.reserve_account,
+        &launch.mint,
+        vec![
+            AccountMeta::new(launch.native_pool, false),
+            AccountMeta::new(launch.pool_vault, false),
+        ],
+    );
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, buy_ix, &[first_buyer])
+            .await
+            .is_err()
+    );
+
+    let manager_account = context.banks_client.get_account(manager).await?.unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.escrowed_sol, 5_000_000_000);
+
+    context.warp_to_slot(manager_data.launch_slot + 6).unwrap();
+
+    for ((buyer, _), (_, amount)) in buyers.iter().zip(orders) {
+        let Some(amount) = amount else { continue };
+        let reveal_ix = Instruction {
+            program_id: abc_token::id(),
+            accounts: vec![
+                AccountMeta::new_readonly(buyer.pubkey(), true),
+                AccountMeta::new(manager, false),
+                AccountMeta::new_readonly(launch_config_address(&launch.mint), false),
+                AccountMeta::new(commitment_address(&manager, &buyer.pubkey()), false),
+            ],
+            data: [
+                REVEAL_BUY_DISCRIMINATOR.as_ref(),
+                &u64::to_le_bytes(amount),
+                &salt,
+            ]
+            .concat(),
+        };
+        process_instruction(&mut context.banks_client, &payer, reveal_ix, &[buyer]).await?;
+    }
+
+    context.warp_to_slot(manager_data.launch_slot + 30).unwrap();
+
+    // Revealed 1 and 2 SOL of 3: a third and two thirds of the allocation
+    let expected = [
+        (30_000_000_000, 1_000_000_000),
+        (60_000_000_000, 0),
+        (0, 1_000_000_000),
+    ];
+    for ((buyer, token_account), (tokens, refund)) in buyers.iter().zip(expected) {
+        let commitment = commitment_address(&manager, &buyer.pubkey());
+        let rent = context.banks_client.get_balance(commitment).await?;
+        let lamports_before = context.banks_client.get_balance(buyer.pubkey()).await?;
+
+        let settle_ix = Instruction {
+            program_id: abc_token::id(),
+            accounts: vec![
+                AccountMeta::new(buyer.pubkey(), true),
+                AccountMeta::new(manager, false),
+                AccountMeta::new_readonly(launch_config_address(&launch.mint), false),
+                AccountMeta::new(*token_account, false),
+                AccountMeta::new(launch.reserve_account, false),
+                AccountMeta::new(launch.treasury, false),
+                AccountMeta::new(commitment, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: SETTLE_COMMITMENT_DISCRIMINATOR.to_vec(),
+        };
+        process_instruction(&mut context.banks_client, &payer, settle_ix, &[buyer]).await?;
+
+        assert_eq!(
+            get_token_balance(&mut context.banks_client, token_account).await?,
+            tokens
+        );
+        assert_eq!(
+            context.banks_client.get_balance(buyer.pubkey()).await?,
+            lamports_before + refund + rent
+        );
+        assert!(context
+            .banks_client
+            .get_account(commitment)
+            .await?
+            .is_none());
+    }
+
+    let manager_account = context.banks_client.get_account(manager).await?.unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.escrowed_sol, 0);
+    assert_eq!(manager_data.revealed_sol, 3_000_000_000);
+
+    Ok(())
+}
+
+fn auction_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"auction", mint.as_ref()], &abc_token::id()).0
+}
+
+fn bid_address(manager: &Pubkey, bidder: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"bid", manager.as_ref(), bidder.as_ref()],
+        &abc_token::id(),
+    )
+    .0
+}
+
+fn withdraw_treasury_instruction(
+    launch: &NativeLaunch,
+    authority: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(launch.manager, false),
+            AccountMeta::new_readonly(launch_config_address(&launch.mint), false),
+            AccountMeta::new(launch.treasury, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(launch.native_pool, false),
+        ],
+        data: [
+            WITHDRAW_TREASURY_DISCRIMINATOR.as_ref(),
+            &u64::to_le_bytes(amount),
+            destination.as_ref(),
+        ]
+        .concat(),
+    }
+}
+
+fn place_bid_instruction(
+    launch: &NativeLaunch,
+    bidder: &Pubkey,
+    sol_amount: u64,
+    tick: u8,
+) -> Instruction {
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(*bidder, true),
+            AccountMeta::new(launch.manager, false),
+            AccountMeta::new_readonly(launch_config_address(&launch.mint), false),
+            AccountMeta::new(auction_address(&launch.mint), false),
+            AccountMeta::new(launch.treasury, false),
+            AccountMeta::new(bid_address(&launch.manager, bidder), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: [
+            PLACE_BID_DISCRIMINATOR.as_ref(),
+            &u64::to_le_bytes(sol_amount),
+            &[tick],
+        ]
+        .concat(),
+    }
+}
+
+// Batch auction: bids placed during the window clear at one price and are
+// settled by a permissionless crank
+#[tokio::test]
+async fn test_batch_auction_flow() -> TestResult<()> {
+    let program_test = ProgramTest::new("abc_token", abc_token::id(), processor!(abc_token::entry));
+    let mut context = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    // 10 tokens on a ladder of 0.1, 0.2, 0.3, ... SOL per token
+    let params = abc_token::LaunchConfigParams {
+        auction_tokens: 10_000_000_000,
+        auction_floor_price: 100_000_000,
+        auction_tick_size: 100_000_000,
+        ..native_launch_params()
+    };
+    let launch = setup_native_launch(&mut context.banks_client, &payer, params).await?;
+    let manager = launch.manager;
+
+    let create_auction_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(launch.authority.pubkey(), true),
+            AccountMeta::new_readonly(manager, false),
+            AccountMeta::new_readonly(launch_config_address(&launch.mint), false),
+            AccountMeta::new(auction_address(&launch.mint), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: CREATE_AUCTION_DISCRIMINATOR.to_vec(),
+    };
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        create_auction_ix,
+        &[&launch.authority],
+    )
+    .await?;
+
+    // Before the launch is scheduled there's no window to clear, however
+    // long ago `initialize` ran
+    let slot = context.banks_client.get_root_slot().await?;
+    context.warp_to_slot(slot + 30).unwrap();
+    let early_settle_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(manager, false),
+            AccountMeta::new_readonly(launch_config_address(&launch.mint), false),
+            AccountMeta::new(auction_address(&launch.mint), false),
+            AccountMeta::new(launch.reserve_account, false),
+            AccountMeta::new(launch.treasury, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: SETTLE_AUCTION_DISCRIMINATOR.to_vec(),
+    };
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, early_settle_ix, &[])
+            .await
+            .is_err()
+    );
+    let auction_account = context
+        .banks_client
+        .get_account(auction_address(&launch.mint))
+        .await?
+        .unwrap();
+    let auction = abc_token::Auction::try_deserialize(&mut &auction_account.data[..])?;
+    assert!(!auction.cleared);
+    schedule_launch(
+        &mut context.banks_client,
+        &payer,
+        &launch.authority,
+        &manager,
+    )
+    .await?;
+
+    // (SOL bid, tick)
+    let bids = [(1_500_000_000, 2), (2_000_000_000, 1), (1_000_000_000, 0)];
+    let mut bidders = Vec::new();
+    for (sol_amount, tick) in bids {
+        let bidder = Keypair::new();
+        let token_account = create_token_account(
+            &mut context.banks_client,
+            &payer,
+            &launch.mint,
+            &bidder.pubkey(),
+        )
+        .await?;
+        fund_account(
+            &mut context.banks_client,
+            &payer,
+            &bidder.pubkey(),
+            3_000_000_000,
+        )
+        .await?;
+
+        let bid_ix = place_bid_instruction(&launch, &bidder.pubkey(), sol_amount, tick);
+        process_instruction(&mut context.banks_client, &payer, bid_ix, &[&bidder]).await?;
+
+        bidders.push((bidder, token_account));
+    }
+
+    // No plain buys and no settling while bids are taken
+    let (first_bidder, first_token_account) = &bidders[0];
+    let buy_ix = trade_instruction(
+        BUY_DISCRIMINATOR,
+        500_000_000,
+        1,
+        &manager,
+        &first_bidder.pubkey(),
+        first_token_account,
+        &launch.reserve_account,
+        &launch.mint,
+        vec![
+            AccountMeta::new(launch.native_pool, false),
+            AccountMeta::new(launch.pool_vault, false),
+        ],
+    );
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, buy_ix, &[first_bidder])
+            .await
+            .is_err()
+    );
+
+    let mut settle_accounts = vec![
+        AccountMeta::new(manager, false),
+        AccountMeta::new_readonly(launch_config_address(&launch.mint), false),
+        AccountMeta::new(auction_address(&launch.mint), false),
+        AccountMeta::new(launch.reserve_account, false),
+        AccountMeta::new(launch.treasury, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    for (bidder, token_account) in &bidders {
+        settle_accounts.push(AccountMeta::new(
+            bid_address(&manager, &bidder.pubkey()),
+            false,
+        ));
+        settle_accounts.push(AccountMeta::new(bidder.pubkey(), false));
+        settle_accounts.push(AccountMeta::new(*token_account, false));
+    }
+    let settle_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: settle_accounts,
+        data: SETTLE_AUCTION_DISCRIMINATOR.to_vec(),
+    };
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, settle_ix.clone(), &[])
+            .await
+            .is_err()
+    );
+
+    let manager_account = context.banks_client.get_account(manager).await?.unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.escrowed_sol, 4_500_000_000);
+
+    // Escrowed bids can't be withdrawn
+    let destination = Keypair::new().pubkey();
+    let withdraw_ix = withdraw_treasury_instruction(
+        &launch,
+        &launch.authority.pubkey(),
+        &destination,
+        1_000_000_000,
+    );
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        withdraw_ix,
+        &[&launch.authority]
+    )
+    .await
+    .is_err());
+
+    context.warp_to_slot(manager_data.launch_slot + 30).unwrap();
+
+    let late_bidder = Keypair::new();
+    fund_account(
+        &mut context.banks_client,
+        &payer,
+        &late_bidder.pubkey(),
+        1_000_000_000,
+    )
+    .await?;
+    let late_bid_ix = place_bid_instruction(&launch, &late_bidder.pubkey(), 500_000_000, 3);
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        late_bid_ix,
+        &[&late_bidder]
+    )
+    .await
+    .is_err());
+
+    let mut balances_before = Vec::new();
+    for (bidder, _) in &bidders {
+        let rent = context
+            .banks_client
+            .get_balance(bid_address(&manager, &bidder.pubkey()))
+            .await?;
+        let lamports = context.banks_client.get_balance(bidder.pubkey()).await?;
+        balances_before.push(lamports + rent);
+    }
+
+    process_instruction(&mut context.banks_client, &payer, settle_ix, &[]).await?;
+
+    // Tick 1 (0.2 SOL) clears: 1.5 SOL above it buys 7.5 tokens and the
+    // 2 SOL bid on it gets the remaining 2.5, refunded 1.5 SOL
+    let expected = [
+        (7_500_000_000, 0),
+        (2_500_000_000, 1_500_000_000),
+        (0, 1_000_000_000),
+    ];
+    for (((bidder, token_account), (tokens, refund)), before) in
+        bidders.iter().zip(expected).zip(balances_before)
+    {
+        assert_eq!(
+            get_token_balance(&mut context.banks_client, token_account).await?,
+            tokens
+        );
+        assert_eq!(
+            context.banks_client.get_balance(bidder.pubkey()).await?,
+            before + refund
+        );
+        assert!(context
+            .banks_client
+            .get_account(bid_address(&manager, &bidder.pubkey()))
+            .await?
+            .is_none());
+    }
+
+    let manager_account = context.banks_client.get_account(manager).await?.unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.escrowed_sol, 0);
+
+    // The 2 SOL paid is free to withdraw, less the treasury's rent-exempt
+    // minimum; the pool's 20 SOL stays
+    let treasury_balance = context.banks_client.get_balance(launch.treasury).await?;
+    assert_eq!(treasury_balance, 22_000_000_000);
+    let rent = context.banks_client.get_rent().await?.minimum_balance(0);
+
+    let withdraw_ix = withdraw_treasury_instruction(
+        &launch,
+        &launch.authority.pubkey(),
+        &destination,
+        2_000_000_000 - rent + 1,
+    );
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        withdraw_ix,
+        &[&launch.authority]
+    )
+    .await
+    .is_err());
+
+    let stranger = Keypair::new();
+    let withdraw_ix = withdraw_treasury_instruction(
+        &launch,
+        &stranger.pubkey(),
+        &stranger.pubkey(),
+        1_000_000_000,
+    );
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, withdraw_ix, &[&stranger])
+            .await
+            .is_err()
+    );
+
+    let withdraw_ix = withdraw_treasury_instruction(
+        &launch,
+        &launch.authority.pubkey(),
+        &destination,
+        2_000_000_000 - rent,
+    );
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        withdraw_ix,
+        &[&launch.authority],
+    )
+    .await?;
+    assert_eq!(
+        context.banks_client.get_balance(destination).await?,
+        2_000_000_000 - rent
+    );
+    assert_eq!(
+        context.banks_client.get_balance(launch.treasury).await?,
+        20_000_000_000 + rent
+    );
+
+    let manager_account = context.banks_client.get_account(manager).await?.unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.total_withdrawn, 2_000_000_000 - rent);
+
+    Ok(())
+}
+
+fn curve_buy_instruction(
+    mint: &Pubkey,
+    buyer: &Pubkey,
+    buyer_token_account: &Pubkey,
+    sol_amount: u64,
+    min_tokens_out: u64,
+) -> Instruction {
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(*buyer, true),
+            AccountMeta::new(mint_pda(b"abc_manager", mint), false),
+            AccountMeta::new_readonly(launch_config_address(mint), false),
+            AccountMeta::new(*buyer_token_account, false),
+            AccountMeta::new(mint_pda(b"reserve", mint), false),
+            AccountMeta::new(mint_pda(b"treasury", mint), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: instruction_data(CURVE_BUY_DISCRIMINATOR, &[sol_amount, min_tokens_out]),
+    }
+}
+
+// Bonding-curve launch: the reserve sells along the curve until the SOL
+// target, then anyone can graduate it into the built-in pool
+#[tokio::test]
+async fn test_bonding_curve_flow() -> TestResult<()> {
+    let program_test = ProgramTest::new("abc_token", abc_token::id(), processor!(abc_token::entry));
+    let mut context = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    let mint_keypair = Keypair::new();
+    let authority = Keypair::new();
+    let mint = mint_keypair.pubkey();
+    setup_mint(&mut context.banks_client, &payer, &mint_keypair, &authority).await?;
+    fund_account(
+        &mut context.banks_client,
+        &payer,
+        &authority.pubkey(),
+        10_000_000_000,
+    )
+    .await?;
+    let token_source = create_token_account(
+        &mut context.banks_client,
+        &payer,
+        &mint,
+        &authority.pubkey(),
+    )
+    .await?;
+    mint_tokens(
+        &mut context.banks_client,
+        &payer,
+        &mint,
+        &token_source,
+        &authority,
+        400_000_000_000,
+    )
+    .await?;
+
+    let manager = mint_pda(b"abc_manager", &mint);
+    let reserve_account = mint_pda(b"reserve", &mint);
+    let native_pool = mint_pda(b"native_pool", &mint);
+    let pool_vault = mint_pda(b"pool_vault", &mint);
+    let lp_mint = mint_pda(b"lp_mint", &mint);
+    let lp_vault = mint_pda(b"lp_vault", &mint);
+    let treasury = mint_pda(b"treasury", &mint);
+
+    // 10 tokens a step from 0.1 SOL each, graduating at 3 SOL
+    let params = abc_token::LaunchConfigParams {
+        curve: abc_token::BondingCurve {
+            kind: abc_token::CurveKind::Linear,
+            start_price: 100_000_000,
+            growth: 100_000_000,
+            step_tokens: 10_000_000_000,
+        },
+        graduation_sol: 3_000_000_000,
+        lp_lock_duration: abc_token::constants::MIN_LP_LOCK_SLOTS,
+        ..native_launch_params()
+    };
+    let init_ix = initialize_instruction(
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(manager, false),
+            AccountMeta::new(launch_config_address(&mint), false),
+            AccountMeta::new(token_source, false),
+            AccountMeta::new(reserve_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ],
+        400_000_000_000,
+        &params,
+    );
+    process_instruction(&mut context.banks_client, &payer, init_ix, &[&authority]).await?;
+
+    let manager_account = context.banks_client.get_account(manager).await?.unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.phase, abc_token::LaunchPhase::BondingCurve);
+
+    // The pool only opens at graduation
+    let create_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new_readonly(manager, false),
+            AccountMeta::new_readonly(launch_config_address(&mint), false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(native_pool, false),
+            AccountMeta::new(pool_vault, false),
+            AccountMeta::new(lp_mint, false),
+            AccountMeta::new_readonly(treasury, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: CREATE_NATIVE_POOL_DISCRIMINATOR.to_vec(),
+    };
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, create_ix, &[&authority])
+            .await
+            .is_err()
+    );
+
+    let mut buyers = Vec::new();
+    for _ in 0..2 {
+        let buyer = Keypair::new();
+        let token_account =
+            create_token_account(&mut context.banks_client, &payer, &mint, &buyer.pubkey()).await?;
+        fund_account(
+            &mut context.banks_client,
+            &payer,
+            &buyer.pubkey(),
+            3_000_000_000,
+        )
+        .await?;
+        buyers.push((buyer, token_account));
+    }
+
+    // 1 SOL clears the first step, 0.5 SOL more buys 2.5 tokens at 0.2
+    let (first_buyer, first_token_account) = &buyers[0];
+    let buy_ix = curve_buy_instruction(
+        &mint,
+        &first_buyer.pubkey(),
+        first_token_account,
+        1_500_000_000,
+        12_500_000_000,
+    );
+    process_instruction(&mut context.banks_client, &payer, buy_ix, &[first_buyer]).await?;
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, first_token_account).await?,
+        12_500_000_000
+    );
+
+    let graduate_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new(manager, false),
+            AccountMeta::new_readonly(launch_config_address(&mint), false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(reserve_account, false),
+            AccountMeta::new(native_pool, false),
+            AccountMeta::new(pool_vault, false),
+            AccountMeta::new(lp_mint, false),
+            AccountMeta::new(lp_vault, false),
+            AccountMeta::new(mint_pda(b"lp_lock", &mint), false),
+            AccountMeta::new_readonly(treasury, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: GRADUATE_DISCRIMINATOR.to_vec(),
+    };
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, graduate_ix.clone(), &[])
+            .await
+            .is_err()
+    );
+
+    // A buy past the target is cut down to the 1.5 SOL still needed
+    let (second_buyer, second_token_account) = &buyers[1];
+    let lamports_before = context
+        .banks_client
+        .get_balance(second_buyer.pubkey())
+        .await?;
+    let buy_ix = curve_buy_instruction(
+        &mint,
+        &second_buyer.pubkey(),
+        second_token_account,
+        2_000_000_000,
+        1,
+    );
+    process_instruction(&mut context.banks_client, &payer, buy_ix, &[second_buyer]).await?;
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, second_token_account).await?,
+        7_500_000_000
+    );
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(second_buyer.pubkey())
+            .await?,
+        lamports_before - 1_500_000_000
+    );
+
+    let buy_ix = curve_buy_instruction(
+        &mint,
+        &second_buyer.pubkey(),
+        second_token_account,
+        1_000_000_000,
+        1,
+    );
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, buy_ix, &[second_buyer])
+            .await
+            .is_err()
+    );
+
+    // Graduating long after launch still locks the LP for the full duration
+    context
+        .warp_to_slot(abc_token::constants::MIN_LP_LOCK_SLOTS + 2_000)
+        .unwrap();
+    let graduation_slot = context.banks_client.get_sysvar::<Clock>().await?.slot;
+    process_instruction(&mut context.banks_client, &payer, graduate_ix, &[]).await?;
+
+    let pool_state = get_native_pool(&mut context.banks_client, &native_pool).await?;
+    assert_eq!(pool_state.sol_reserve, 3_000_000_000);
+    assert_eq!(pool_state.token_reserve, 380_000_000_000);
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &pool_vault).await?,
+        380_000_000_000
+    );
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &reserve_account).await?,
+        0
+    );
+    // sqrt(3e9 * 380e9), held by the manager under the launch's LP lock
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &lp_vault).await?,
+        33_763_886_032
+    );
+    let lock_account = context
+        .banks_client
+        .get_account(mint_pda(b"lp_lock", &mint))
+        .await?
+        .unwrap();
+    let lp_lock = abc_token::LiquidityLock::try_deserialize(&mut &lock_account.data[..])?;
+    assert_eq!(lp_lock.lp_mint, lp_mint);
+    assert_eq!(lp_lock.amount, 33_763_886_032);
+    assert!(lp_lock.lock_until >= graduation_slot + abc_token::constants::MIN_LP_LOCK_SLOTS);
+
+    let manager_account = context.banks_client.get_account(manager).await?.unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.phase, abc_token::LaunchPhase::Open);
+    assert_eq!(manager_data.curve_sol_raised, 3_000_000_000);
+    assert_eq!(manager_data.curve_tokens_sold, 20_000_000_000);
+    assert_eq!(manager_data.reserve_tokens, 0);
+
+    Ok(())
+}
+
+fn close_launch_instruction(authority: &Pubkey, mint: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(mint_pda(b"abc_manager", mint), false),
+            AccountMeta::new_readonly(launch_config_address(mint), false),
+        ],
+        data: CLOSE_LAUNCH_DISCRIMINATOR.to_vec(),
+    }
+}
+
+fn proposal_address(manager: &Pubkey, id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"proposal", manager.as_ref(), &id.to_le_bytes()],
+        &abc_token::id(),
+    )
+    .0
+}
+
+fn create_proposal_instruction(
+    launch: &NativeLaunch,
+    proposer: &Pubkey,
+    id: u64,
+    action: &abc_token::ProposalAction,
+    lifetime_slots: u64,
+) -> Instruction {
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(*proposer, true),
+            AccountMeta::new_readonly(launch.manager, false),
+            AccountMeta::new(
+                Pubkey::find_program_address(
+                    &[b"approver_set", launch.manager.as_ref()],
+                    &abc_token::id(),
+                )
+                .0,
+                false,
+            ),
+            AccountMeta::new_readonly(launch_config_address(&launch.mint), false),
+            AccountMeta::new(proposal_address(&launch.manager, id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: [
+            CREATE_PROPOSAL_DISCRIMINATOR.as_ref(),
+            &action.try_to_vec().unwrap(),
+            &lifetime_slots.to_le_bytes(),
+        ]
+        .concat(),
+    }
+}
+
+fn approve_instruction(manager: &Pubkey, approver: &Pubkey, id: u64) -> Instruction {
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*approver, true),
+            AccountMeta::new_readonly(
+                Pubkey::find_program_address(
+                    &[b"approver_set", manager.as_ref()],
+                    &abc_token::id(),
+                )
+                .0,
+                false,
+            ),
+            AccountMeta::new(proposal_address(manager, id), false),
+        ],
+        data: APPROVE_DISCRIMINATOR.to_vec(),
+    }
+}
+
+fn execute_instruction(
+    launch: &NativeLaunch,
+    executor: &Pubkey,
+    id: u64,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*executor, true),
+        AccountMeta::new(launch.manager, false),
+        AccountMeta::new(launch_config_address(&launch.mint), false),
+        AccountMeta::new(
+            Pubkey::find_program_address(
+                &[b"approver_set", launch.manager.as_ref()],
+                &abc_token::id(),
+            )
+            .0,
+            false,
+        ),
+        AccountMeta::new(proposal_address(&launch.manager, id), false),
+        AccountMeta::new(launch.reserve_account, false),
+        AccountMeta::new(launch.treasury, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(remaining_accounts);
+
+    Instruction {
+        program_id: abc_token::id(),
+        accounts,
+        data: EXECUTE_DISCRIMINATOR.to_vec(),
+    }
+}
+
+// M-of-N approvals: once an approver set is installed, treasury withdrawals,
+// reserve releases and config changes go through proposals
+#[tokio::test]
+async fn test_approver_set_flow() -> TestResult<()> {
+    let program_test = ProgramTest::new("abc_token", abc_token::id(), processor!(abc_token::entry));
+    let mut context = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    let params = abc_token::LaunchConfigParams {
+        reserve_vesting: abc_token::VestingSchedule {
+            amount: 5_000_000_000,
+            duration: 1,
+            ..Default::default()
+        },
+        lp_lock_until: 1_000,
+        ..native_launch_params()
+    };
+    let launch = setup_native_launch(&mut context.banks_client, &payer, params).await?;
+    let manager = launch.manager;
+    let lp_vault = mint_pda(b"lp_vault", &launch.mint);
+
+    let lock_ix = lock_liquidity_instruction(&launch, launch.lp_balance);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        lock_ix,
+        &[&launch.authority],
+    )
+    .await?;
+
+    let approvers: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+    for approver in &approvers {
+        fund_account(
+            &mut context.banks_client,
+            &payer,
+            &approver.pubkey(),
+            1_000_000_000,
+        )
+        .await?;
+    }
+    let approver_keys: Vec<Pubkey> = approvers.iter().map(|a| a.pubkey()).collect();
+
+    let create_set_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(launch.authority.pubkey(), true),
+            AccountMeta::new(manager, false),
+            AccountMeta::new(
+                Pubkey::find_program_address(
+                    &[b"approver_set", manager.as_ref()],
+                    &abc_token::id(),
+                )
+                .0,
+                false,
+            ),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: [
+            CREATE_APPROVER_SET_DISCRIMINATOR.as_ref(),
+            &approver_keys.try_to_vec().unwrap(),
+            &[2],
+        ]
+        .concat(),
+    };
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        create_set_ix,
+        &[&launch.authority],
+    )
+    .await?;
+
+    // 2 SOL the treasury is free to pay out, but not on the authority's say-so
+    fund_account(
+        &mut context.banks_client,
+        &payer,
+        &launch.treasury,
+        2_000_000_000,
+    )
+    .await?;
+    let destination = Keypair::new().pubkey();
+    let withdraw_ix = withdraw_treasury_instruction(
+        &launch,
+        &launch.authority.pubkey(),
+        &destination,
+        1_000_000_000,
+    );
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        withdraw_ix,
+        &[&launch.authority]
+    )
+    .await
+    .is_err());
+
+    let withdraw = abc_token::ProposalAction::WithdrawTreasury {
+        amount: 1_000_000_000,
+        destination,
+    };
+    let propose_ix = create_proposal_instruction(&launch, &approver_keys[0], 0, &withdraw, 1_000);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        propose_ix,
+        &[&approvers[0]],
+    )
+    .await?;
+
+    let stranger = Keypair::new();
+    fund_account(
+        &mut context.banks_client,
+        &payer,
+        &stranger.pubkey(),
+        1_000_000_000,
+    )
+    .await?;
+    let propose_ix = create_proposal_instruction(&launch, &stranger.pubkey(), 1, &withdraw, 1_000);
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, propose_ix, &[&stranger])
+            .await
+            .is_err()
+    );
+
+    // The proposer's approval alone is one short
+    let withdraw_accounts = vec![
+        AccountMeta::new(destination, false),
+        AccountMeta::new_readonly(launch.native_pool, false),
+    ];
+    let execute_ix = execute_instruction(&launch, &approver_keys[0], 0, withdraw_accounts.clone());
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        execute_ix,
+        &[&approvers[0]]
+    )
+    .await
+    .is_err());
+    let approve_ix = approve_instruction(&manager, &approver_keys[0], 0);
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        approve_ix,
+        &[&approvers[0]]
+    )
+    .await
+    .is_err());
+
+    let approve_ix = approve_instruction(&manager, &approver_keys[1], 0);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        approve_ix,
+        &[&approvers[1]],
+    )
+    .await?;
+    let execute_ix = execute_instruction(&launch, &approver_keys[2], 0, withdraw_accounts.clone());
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        execute_ix,
+        &[&approvers[2]],
+    )
+    .await?;
+    assert_eq!(
+        context.banks_client.get_balance(destination).await?,
+        1_000_000_000
+    );
+
+    let execute_ix = execute_instruction(&launch, &approver_keys[1], 0, withdraw_accounts);
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        execute_ix,
+        &[&approvers[1]]
+    )
+    .await
+    .is_err());
+
+    // Reserve release to a token account
+    let recipient = Keypair::new();
+    let recipient_token_account = create_token_account(
+        &mut context.banks_client,
+        &payer,
+        &launch.mint,
+        &recipient.pubkey(),
+    )
+    .await?;
+    let release = abc_token::ProposalAction::ReleaseReserve {
+        amount: 5_000_000_000,
+        destination: recipient_token_account,
+    };
+    let propose_ix = create_proposal_instruction(&launch, &approver_keys[1], 1, &release, 1_000);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        propose_ix,
+        &[&approvers[1]],
+    )
+    .await?;
+    let approve_ix = approve_instruction(&manager, &approver_keys[2], 1);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        approve_ix,
+        &[&approvers[2]],
+    )
+    .await?;
+    let execute_ix = execute_instruction(
+        &launch,
+        &approver_keys[0],
+        1,
+        vec![AccountMeta::new(recipient_token_account, false)],
+    );
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        execute_ix,
+        &[&approvers[0]],
+    )
+    .await?;
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &recipient_token_account).await?,
+        5_000_000_000
+    );
+
+    // Config changes still wait out the timelock, and their lifetime only
+    // starts once it has passed
+    let update = abc_token::ProposalAction::UpdateConfig(abc_token::ConfigUpdate {
+        min_trade_sol: 200_000,
+        max_trade_sol: 2_000_000_000,
+        max_price_impact_bps: 500,
+        slippage_tolerance_bps: 50,
+        raydium_pool: launch.native_pool,
+    });
+    let propose_ix = create_proposal_instruction(&launch, &approver_keys[0], 2, &update, 1_000);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        propose_ix,
+        &[&approvers[0]],
+    )
+    .await?;
+    let approve_ix = approve_instruction(&manager, &approver_keys[2], 2);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        approve_ix,
+        &[&approvers[2]],
+    )
+    .await?;
+    let execute_ix = execute_instruction(&launch, &approver_keys[1], 2, vec![]);
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        execute_ix,
+        &[&approvers[1]]
+    )
+    .await
+    .is_err());
+
+    let slot = context.banks_client.get_root_slot().await?;
+    context.warp_to_slot(slot + 216_001).unwrap();
+    let execute_ix = execute_instruction(&launch, &approver_keys[0], 2, vec![]);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        execute_ix,
+        &[&approvers[0]],
+    )
+    .await?;
+
+    let config_account = context
+        .banks_client
+        .get_account(launch_config_address(&launch.mint))
+        .await?
+        .unwrap();
+    let config = abc_token::LaunchConfig::try_deserialize(&mut &config_account.data[..])?;
+    assert_eq!(config.min_trade_sol, 200_000);
+    assert_eq!(config.max_trade_sol, 2_000_000_000);
+
+    // Unapproved proposals lapse
+    let shrink = abc_token::ProposalAction::SetApprovers {
+        approvers: approver_keys[..2].to_vec(),
+        threshold: 1,
+    };
+    let propose_ix = create_proposal_instruction(&launch, &approver_keys[0], 3, &shrink, 10);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        propose_ix,
+        &[&approvers[0]],
+    )
+    .await?;
+
+    let slot = context.banks_client.get_root_slot().await?;
+    context.warp_to_slot(slot + 20).unwrap();
+    let approve_ix = approve_instruction(&manager, &approver_keys[1], 3);
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        approve_ix,
+        &[&approvers[1]]
+    )
+    .await
+    .is_err());
+
+    // The expired LP lock only opens through a proposal
+    let unlock_ix = unlock_liquidity_instruction(&launch, &launch.authority_lp_account);
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        unlock_ix,
+        &[&launch.authority]
+    )
+    .await
+    .is_err());
+    let unlock = abc_token::ProposalAction::UnlockLiquidity {
+        destination: launch.authority_lp_account,
+    };
+    let propose_ix = create_proposal_instruction(&launch, &approver_keys[2], 4, &unlock, 1_000);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        propose_ix,
+        &[&approvers[2]],
+    )
+    .await?;
+    let approve_ix = approve_instruction(&manager, &approver_keys[1], 4);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        approve_ix,
+        &[&approvers[1]],
+    )
+    .await?;
+    let lp_lock = mint_pda(b"lp_lock", &launch.mint);
+    let execute_ix = execute_instruction(
+        &launch,
+        &approver_keys[0],
+        4,
+        vec![
+            AccountMeta::new(lp_lock, false),
+            AccountMeta::new(lp_vault, false),
+            AccountMeta::new(launch.authority_lp_account, false),
+        ],
+    );
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        execute_ix,
+        &[&approvers[0]],
+    )
+    .await?;
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &launch.authority_lp_account).await?,
+        launch.lp_balance
+    );
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &lp_vault).await?,
+        0
+    );
+    let lock_account = context.banks_client.get_account(lp_lock).await?.unwrap();
+    let lock_data = abc_token::LiquidityLock::try_deserialize(&mut &lock_account.data[..])?;
+    assert_eq!(lock_data.amount, 0);
+
+    // Closing the launch is a proposal too once approvers are set
+    let close_ix = close_launch_instruction(&launch.authority.pubkey(), &launch.mint);
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        close_ix,
+        &[&launch.authority]
+    )
+    .await
+    .is_err());
+    let propose_ix = create_proposal_instruction(
+        &launch,
+        &approver_keys[1],
+        5,
+        &abc_token::ProposalAction::CloseLaunch,
+        1_000,
+    );
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        propose_ix,
+        &[&approvers[1]],
+    )
+    .await?;
+    let approve_ix = approve_instruction(&manager, &approver_keys[0], 5);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        approve_ix,
+        &[&approvers[0]],
+    )
+    .await?;
+    let execute_ix = execute_instruction(&launch, &approver_keys[2], 5, vec![]);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        execute_ix,
+        &[&approvers[2]],
+    )
+    .await?;
+
+    let manager_account = context.banks_client.get_account(manager).await?.unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.phase, abc_token::LaunchPhase::Closed);
+    Ok(())
+}
+
+fn release_reserve_instruction(
+    launch: &NativeLaunch,
+    destination: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(launch.authority.pubkey(), true),
+            AccountMeta::new(launch.manager, false),
+            AccountMeta::new_readonly(launch_config_address(&launch.mint), false),
+            AccountMeta::new(launch.reserve_account, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: instruction_data(RELEASE_RESERVE_DISCRIMINATOR, &[amount]),
+    }
+}
+
+// Reserve vesting: nothing before the cliff, then a linear release that
+// release_reserve can't run ahead of
+#[tokio::test]
+async fn test_reserve_vesting_flow() -> TestResult<()> {
+    let program_test = ProgramTest::new("abc_token", abc_token::id(), processor!(abc_token::entry));
+    let mut context = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    let params = abc_token::LaunchConfigParams {
+        reserve_vesting: abc_token::VestingSchedule {
+            amount: 100_000_000_000,
+            clock: abc_token::VestingClock::Slot,
+            start: 1_000,
+            cliff: 100,
+            duration: 1_000,
+        },
+        ..native_launch_params()
+    };
+    let launch = setup_native_launch(&mut context.banks_client, &payer, params).await?;
+
+    let recipient = Keypair::new();
+    let recipient_token_account = create_token_account(
+        &mut context.banks_client,
+        &payer,
+        &launch.mint,
+        &recipient.pubkey(),
+    )
+    .await?;
+
+    // Before the cliff nothing has vested
+    let slot = context.banks_client.get_root_slot().await?;
+    assert!(slot < 1_100);
+    let release_ix = release_reserve_instruction(&launch, &recipient_token_account, 1);
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        release_ix,
+        &[&launch.authority]
+    )
+    .await
+    .is_err());
+
+    // At the cliff a tenth has vested
+    context.warp_to_slot(1_100).unwrap();
+    let release_ix = release_reserve_instruction(&launch, &recipient_token_account, 10_000_000_000);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        release_ix,
+        &[&launch.authority],
+    )
+    .await?;
+    let release_ix = release_reserve_instruction(&launch, &recipient_token_account, 2);
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        release_ix,
+        &[&launch.authority]
+    )
+    .await
+    .is_err());
+
+    // Only the authority can release
+    let stranger = Keypair::new();
+    let mut release_ix = release_reserve_instruction(&launch, &recipient_token_account, 3);
+    release_ix.accounts[0].pubkey = stranger.pubkey();
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, release_ix, &[&stranger])
+            .await
+            .is_err()
+    );
+
+    context.warp_to_slot(1_500).unwrap();
+    let release_ix = release_reserve_instruction(&launch, &recipient_token_account, 40_000_000_000);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        release_ix,
+        &[&launch.authority],
+    )
+    .await?;
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &recipient_token_account).await?,
+        50_000_000_000
+    );
+
+    let manager_account = context
+        .banks_client
+        .get_account(launch.manager)
+        .await?
+        .unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.reserve_released, 50_000_000_000);
+    assert_eq!(manager_data.reserve_tokens, 350_000_000_000);
+
+    Ok(())
+}
+
+fn vesting_address(manager: &Pubkey, beneficiary: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"vesting", manager.as_ref(), beneficiary.as_ref()],
+        &abc_token::id(),
+    )
+    .0
+}
+
+fn vesting_vault_address(manager: &Pubkey, beneficiary: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"vesting_vault", manager.as_ref(), beneficiary.as_ref()],
+        &abc_token::id(),
+    )
+    .0
+}
+
+fn create_vesting_instruction(
+    launch: &NativeLaunch,
+    beneficiary: &Pubkey,
+    schedule: [u64; 4],
+    revocable: bool,
+) -> Instruction {
+    let [amount, start, cliff, end] = schedule;
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(launch.authority.pubkey(), true),
+            AccountMeta::new(launch.manager, false),
+            AccountMeta::new_readonly(launch_config_address(&launch.mint), false),
+            AccountMeta::new_readonly(launch.mint, false),
+            AccountMeta::new_readonly(*beneficiary, false),
+            AccountMeta::new(launch.reserve_account, false),
+            AccountMeta::new(vesting_address(&launch.manager, beneficiary), false),
+            AccountMeta::new(vesting_vault_address(&launch.manager, beneficiary), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: [
+            instruction_data(CREATE_VESTING_DISCRIMINATOR, &[amount, start, cliff, end]),
+            vec![abc_token::VestingClock::Slot as u8, revocable as u8],
+        ]
+        .concat(),
+    }
+}
+
+fn claim_vested_instruction(
+    manager: &Pubkey,
+    beneficiary: &Pubkey,
+    destination: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*beneficiary, true),
+            AccountMeta::new_readonly(*manager, false),
+            AccountMeta::new(vesting_address(manager, beneficiary), false),
+            AccountMeta::new(vesting_vault_address(manager, beneficiary), false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: CLAIM_VESTED_DISCRIMINATOR.to_vec(),
+    }
+}
+
+fn revoke_vesting_instruction(launch: &NativeLaunch, beneficiary: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(launch.authority.pubkey(), true),
+            AccountMeta::new(launch.manager, false),
+            AccountMeta::new(vesting_address(&launch.manager, beneficiary), false),
+            AccountMeta::new(vesting_vault_address(&launch.manager, beneficiary), false),
+            AccountMeta::new(launch.reserve_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: REVOKE_VESTING_DISCRIMINATOR.to_vec(),
+    }
+}
+
+// Per-beneficiary grants escrowed out of the reserve: beneficiaries claim as
+// they vest, and revocable grants hand the unvested rest back
+#[tokio::test]
+async fn test_vesting_grant_flow() -> TestResult<()> {
+    let program_test = ProgramTest::new("abc_token", abc_token::id(), processor!(abc_token::entry));
+    let mut context = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    // 350 of the 400 reserve tokens are held back for the launch's own
+    // schedule
+    let params = abc_token::LaunchConfigParams {
+        reserve_vesting: abc_token::VestingSchedule {
+            amount: 350_000_000_000,
+            duration: 1,
+            ..Default::default()
+        },
+        ..native_launch_params()
+    };
+    let launch = setup_native_launch(&mut context.banks_client, &payer, params).await?;
+    let manager = launch.manager;
+
+    let team = Keypair::new();
+    let contributor = Keypair::new();
+    for beneficiary in [&team, &contributor] {
+        fund_account(
+            &mut context.banks_client,
+            &payer,
+            &beneficiary.pubkey(),
+            1_000_000_000,
+        )
+        .await?;
+    }
+    let team_token_account = create_token_account(
+        &mut context.banks_client,
+        &payer,
+        &launch.mint,
+        &team.pubkey(),
+    )
+    .await?;
+    let contributor_token_account = create_token_account(
+        &mut context.banks_client,
+        &payer,
+        &launch.mint,
+        &contributor.pubkey(),
+    )
+    .await?;
+
+    let create_ix = create_vesting_instruction(
+        &launch,
+        &team.pubkey(),
+        [60_000_000_000, 1_000, 1_100, 2_000],
+        true,
+    );
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        create_ix,
+        &[&launch.authority]
+    )
+    .await
+    .is_err());
+
+    let create_ix = create_vesting_instruction(
+        &launch,
+        &team.pubkey(),
+        [40_000_000_000, 1_000, 1_100, 2_000],
+        true,
+    );
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        create_ix,
+        &[&launch.authority],
+    )
+    .await?;
+    let create_ix = create_vesting_instruction(
+        &launch,
+        &contributor.pubkey(),
+        [10_000_000_000, 1_000, 1_000, 1_100],
+        false,
+    );
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        create_ix,
+        &[&launch.authority],
+    )
+    .await?;
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &launch.reserve_account).await?,
+        350_000_000_000
+    );
+
+    // Nothing before the cliff
+    let claim_ix = claim_vested_instruction(&manager, &team.pubkey(), &team_token_account);
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, claim_ix, &[&team])
+            .await
+            .is_err()
+    );
+
+    context.warp_to_slot(1_500).unwrap();
+    let claim_ix = claim_vested_instruction(&manager, &team.pubkey(), &team_token_account);
+    process_instruction(&mut context.banks_client, &payer, claim_ix, &[&team]).await?;
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &team_token_account).await?,
+        20_000_000_000
+    );
+
+    // Revoking at 1600 keeps 24 vested and returns the other 16
+    context.warp_to_slot(1_600).unwrap();
+    let revoke_ix = revoke_vesting_instruction(&launch, &team.pubkey());
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        revoke_ix,
+        &[&launch.authority],
+    )
+    .await?;
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &launch.reserve_account).await?,
+        366_000_000_000
+    );
+    let revoke_ix = revoke_vesting_instruction(&launch, &contributor.pubkey());
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        revoke_ix,
+        &[&launch.authority]
+    )
+    .await
+    .is_err());
+
+    context.warp_to_slot(2_000).unwrap();
+    let claim_ix = claim_vested_instruction(&manager, &team.pubkey(), &team_token_account);
+    process_instruction(&mut context.banks_client, &payer, claim_ix, &[&team]).await?;
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &team_token_account).await?,
+        24_000_000_000
+    );
+    let claim_ix =
+        claim_vested_instruction(&manager, &contributor.pubkey(), &contributor_token_account);
+    process_instruction(&mut context.banks_client, &payer, claim_ix, &[&contributor]).await?;
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &contributor_token_account).await?,
+        10_000_000_000
+    );
+
+    // Only the beneficiary claims, and only once fully vested
+    let mut claim_ix =
+        claim_vested_instruction(&manager, &team.pubkey(), &contributor_token_account);
+    claim_ix.accounts[0].is_signer = false;
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, claim_ix, &[])
+            .await
+            .is_err()
+    );
+    let claim_ix = claim_vested_instruction(&manager, &team.pubkey(), &team_token_account);
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, claim_ix, &[&team])
+            .await
+            .is_err()
+    );
+
+    let manager_account = context.banks_client.get_account(manager).await?.unwrap();
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.reserve_granted, 34_000_000_000);
+    // Grants leave the reserve's books; the revoked 16 came back
+    assert_eq!(manager_data.reserve_tokens, 366_000_000_000);
+
+    Ok(())
+}
+
+fn extend_lock_instruction(launch: &NativeLaunch, lock_until: u64) -> Instruction {
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(launch.authority.pubkey(), true),
+            AccountMeta::new_readonly(launch.manager, false),
+            AccountMeta::new(mint_pda(b"lp_lock", &launch.mint), false),
+        ],
+        data: instruction_data(EXTEND_LOCK_DISCRIMINATOR, &[lock_until]),
+    }
+}
+
+fn lock_liquidity_instruction(launch: &NativeLaunch, amount: u64) -> Instruction {
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(launch.authority.pubkey(), true),
+            AccountMeta::new_readonly(launch.manager, false),
+            AccountMeta::new_readonly(launch_config_address(&launch.mint), false),
+            AccountMeta::new_readonly(launch.mint, false),
+            AccountMeta::new_readonly(mint_pda(b"lp_mint", &launch.mint), false),
+            AccountMeta::new(launch.authority_lp_account, false),
+            AccountMeta::new(mint_pda(b"lp_lock", &launch.mint), false),
+            AccountMeta::new(mint_pda(b"lp_vault", &launch.mint), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: instruction_data(LOCK_LIQUIDITY_DISCRIMINATOR, &[amount]),
+    }
+}
+
+fn unlock_liquidity_instruction(launch: &NativeLaunch, destination: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(launch.authority.pubkey(), true),
+            AccountMeta::new_readonly(launch.manager, false),
+            AccountMeta::new(mint_pda(b"lp_lock", &launch.mint), false),
+            AccountMeta::new(mint_pda(b"lp_vault", &launch.mint), false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: UNLOCK_LIQUIDITY_DISCRIMINATOR.to_vec(),
+    }
+}
+
+// LP locked in the program: only the authority takes it back out, and only
+// after the lock, which can be pushed out but not pulled in, has expired
+#[tokio::test]
+async fn test_liquidity_lock_flow() -> TestResult<()> {
+    let program_test = ProgramTest::new("abc_token", abc_token::id(), processor!(abc_token::entry));
+    let mut context = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    let params = abc_token::LaunchConfigParams {
+        lp_lock_clock: abc_token::VestingClock::Slot,
+        lp_lock_until: 1_000,
+        ..native_launch_params()
+    };
+    let launch = setup_native_launch(&mut context.banks_client, &payer, params).await?;
+    let lp_vault = mint_pda(b"lp_vault", &launch.mint);
+
+    let lock_ix = lock_liquidity_instruction(&launch, launch.lp_balance);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        lock_ix,
+        &[&launch.authority],
+    )
+    .await?;
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &lp_vault).await?,
+        launch.lp_balance
+    );
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &launch.authority_lp_account).await?,
+        0
+    );
+
+    let unlock_ix = unlock_liquidity_instruction(&launch, &launch.authority_lp_account);
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        unlock_ix,
+        &[&launch.authority]
+    )
+    .await
+    .is_err());
+
+    let extend_ix = extend_lock_instruction(&launch, 1_500);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        extend_ix,
+        &[&launch.authority],
+    )
+    .await?;
+    let extend_ix = extend_lock_instruction(&launch, 1_200);
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        extend_ix,
+        &[&launch.authority]
+    )
+    .await
+    .is_err());
+
+    // Past the original expiry but not the extended one
+    context.warp_to_slot(1_200).unwrap();
+    let unlock_ix = unlock_liquidity_instruction(&launch, &launch.authority_lp_account);
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        unlock_ix,
+        &[&launch.authority]
+    )
+    .await
+    .is_err());
+
+    context.warp_to_slot(1_500).unwrap();
+    let stranger = Keypair::new();
+    let mut unlock_ix = unlock_liquidity_instruction(&launch, &launch.authority_lp_account);
+    unlock_ix.accounts[0].pubkey = stranger.pubkey();
+    assert!(
+        process_instruction(&mut context.banks_client, &payer, unlock_ix, &[&stranger])
+            .await
+            .is_err()
+    );
+
+    let unlock_ix = unlock_liquidity_instruction(&launch, &launch.authority_lp_account);
+    process_instruction(
+        &mut context.banks_client,
+        &payer,
+        unlock_ix,
+        &[&launch.authority],
+    )
+    .await?;
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &launch.authority_lp_account).await?,
+        launch.lp_balance
+    );
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &lp_vault).await?,
+        0
+    );
+
+    Ok(())
+}
+
+// A manager written before versioning and LaunchConfig existed: migrating
+// upgrades it and creates its config, after which it trades as usual
+#[tokio::test]
+async fn test_migrate_legacy_manager() -> TestResult<()> {
+    let raydium_program_id = Pubkey::new_unique();
+    let mut program_test =
+        ProgramTest::new("abc_token", abc_token::id(), processor!(abc_token::entry));
+    program_test.add_program(
+        "raydium",
+        raydium_program_id,
+        processor!(mock_raydium_processor),
+    );
+    let mut context = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    let mint_keypair = Keypair::new();
+    let authority = Keypair::new();
+    let mint = mint_keypair.pubkey();
+    setup_mint(&mut context.banks_client, &payer, &mint_keypair, &authority).await?;
+    fund_account(
+        &mut context.banks_client,
+        &payer,
+        &authority.pubkey(),
+        10_000_000_000,
+    )
+    .await?;
+
+    let pool = setup_raydium_accounts(
+        &mut context.banks_client,
+        &payer,
+        &mint,
+        &raydium_program_id,
+    )
+    .await?;
+    mint_tokens(
+        &mut context.banks_client,
+        &payer,
+        &mint,
+        &pool.pool_coin_vault,
+        &authority,
+        200_000_000_000,
+    )
+    .await?;
+    wrap_sol(
+        &mut context.banks_client,
+        &payer,
+        &pool.pool_pc_vault,
+        20_000_000_000,
+    )
+    .await?;
+
+    // Baseline-sized manager and its reserve, as the old program left them
+    let (manager, manager_bump) =
+        Pubkey::find_program_address(&[b"abc_manager", mint.as_ref()], &abc_token::id());
+    let legacy = abc_token::LegacyABCManager {
+        authority: authority.pubkey(),
+        mint,
+        is_launched: true,
+        reserve_tokens: 100_000_000_000,
+        bump: manager_bump,
+        raydium_pool: pool.amm,
+        ..Default::default()
+    };
+    let legacy_data = [
+        <abc_token::ABCManager as anchor_lang::Discriminator>::discriminator().as_ref(),
+        &legacy.try_to_vec().unwrap(),
+    ]
+    .concat();
+    context.set_account(
+        &manager,
+        &AccountSharedData::from(SolanaAccount {
+            lamports: Rent::default().minimum_balance(legacy_data.len()),
+            data: legacy_data,
+            owner: abc_token::id(),
+            executable: false,
+            rent_epoch: 0,
+        }),
+    );
+
+    let reserve_account = mint_pda(b"reserve", &mint);
+    let mut reserve_data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint,
+        owner: manager,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut reserve_data);
+    context.set_account(
+        &reserve_account,
+        &AccountSharedData::from(SolanaAccount {
+            lamports: Rent::default().minimum_balance(reserve_data.len()),
+            data: reserve_data,
+            owner: spl_token::id(),
+            executable: false,
+            rent_epoch: 0,
+        }),
+    );
+    mint_tokens(
+        &mut context.banks_client,
+        &payer,
+        &mint,
+        &reserve_account,
+        &authority,
+        100_000_000_000,
+    )
+    .await?;
+
+    let trader = Keypair::new();
+    fund_account(
+        &mut context.banks_client,
+        &payer,
+        &trader.pubkey(),
+        1_000_000_000,
+    )
+    .await?;
+    let trader_token_account =
+        create_token_account(&mut context.banks_client, &payer, &mint, &trader.pubkey()).await?;
+    let trader_sol_account = create_token_account(
+        &mut context.banks_client,
+        &payer,
+        &spl_token::native_mint::id(),
+        &trader.pubkey(),
+    )
+    .await?;
+    wrap_sol(
+        &mut context.banks_client,
+        &payer,
+        &trader_sol_account,
+        2_000_000_000,
+    )
+    .await?;
+
+    let expected_tokens = swap_output(500_000_000, 20_000_000_000, 200_000_000_000);
+    let buy_ix = trade_instruction(
+        BUY_DISCRIMINATOR,
+        500_000_000,
+        expected_tokens,
+        &manager,
+        &trader.pubkey(),
+        &trader_token_account,
+        &reserve_account,
+        &mint,
+        pool.trade_accounts(&trader_sol_account),
+    );
+
+    // Neither the old layout nor the missing config can trade
+    assert!(process_instruction(
+        &mut context.banks_client,
+        &payer,
+        buy_ix.clone(),
+        &[&trader]
+    )
+    .await
+    .is_err());
+
+    let params = abc_token::LaunchConfigParams {
+        dex_program: raydium_program_id,
+        ..Default::default()
+    };
+    let migrate_ix = Instruction {
+        program_id: abc_token::id(),
+        accounts: vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(manager, false),
+            AccountMeta::new(launch_config_address(&mint), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: [
+            MIGRATE_MANAGER_DISCRIMINATOR.as_ref(),
+            &params.try_to_vec().unwrap(),
+        ]
+        .concat(),
+    };
+    process_instruction(&mut context.banks_client, &payer, migrate_ix, &[&authority]).await?;
+
+    let manager_account = context.banks_client.get_account(manager).await?.unwrap();
+    assert_eq!(manager_account.data.len(), abc_token::ABCManager::LEN);
+    let manager_data = abc_token::ABCManager::try_deserialize(&mut &manager_account.data[..])?;
+    assert_eq!(manager_data.authority, authority.pubkey());
+    assert_eq!(manager_data.phase, abc_token::LaunchPhase::Open);
+    let config_account = context
+        .banks_client
+        .get_account(launch_config_address(&mint))
+        .await?
+        .unwrap();
+    let config = abc_token::LaunchConfig::try_deserialize(&mut &config_account.data[..])?;
+    assert_eq!(config.dex_program, raydium_program_id);
+
+    process_instruction(&mut context.banks_client, &payer, buy_ix, &[&trader]).await?;
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &trader_token_account).await?,
+        expected_tokens
+    );
+
+    Ok(())
+}
+
+const CREATE_NATIVE_POOL_DISCRIMINATOR: [u8; 8] = [237, 223, 217, 142, 217, 5, 186, 244];
+const ADD_LIQUIDITY_DISCRIMINATOR: [u8; 8] = [181, 157, 89, 67, 143, 182, 52, 72];
+const REMOVE_LIQUIDITY_DISCRIMINATOR: [u8; 8] = [80, 85, 209, 72, 24, 206, 177, 108];
+const CLAIM_REFUND_DISCRIMINATOR: [u8; 8] = [15, 16, 30, 161, 255, 228, 97, 60];
+const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+const PRESALE_BUY_DISCRIMINATOR: [u8; 8] = [165, 166, 112, 136, 182, 81, 98, 102];
+const COMMIT_BUY_DISCRIMINATOR: [u8; 8] = [140, 71, 205, 149, 152, 135, 223, 90];
+const REVEAL_BUY_DISCRIMINATOR: [u8; 8] = [83, 2, 28, 194, 96, 217, 33, 87];
+const SETTLE_COMMITMENT_DISCRIMINATOR: [u8; 8] = [131, 217, 252, 92, 136, 78, 89, 78];
+const SET_GUARDIAN_DISCRIMINATOR: [u8; 8] = [147, 243, 50, 121, 154, 164, 50, 30];
+const PAUSE_TRADING_DISCRIMINATOR: [u8; 8] = [196, 206, 8, 164, 69, 49, 79, 234];
+const RESUME_TRADING_DISCRIMINATOR: [u8; 8] = [146, 46, 212, 86, 69, 159, 30, 65];
+const SCHEDULE_LAUNCH_DISCRIMINATOR: [u8; 8] = [209, 8, 171, 3, 114, 254, 104, 175];
+const CLEAR_FLAGGED_WALLET_DISCRIMINATOR: [u8; 8] = [55, 233, 3, 77, 160, 51, 69, 219];
+const CREATE_AUCTION_DISCRIMINATOR: [u8; 8] = [234, 6, 201, 246, 47, 219, 176, 107];
+const PLACE_BID_DISCRIMINATOR: [u8; 8] = [238, 77, 148, 91, 200, 151, 92, 146];
+const SETTLE_AUCTION_DISCRIMINATOR: [u8; 8] = [246, 196, 183, 98, 222, 139, 46, 133];
+const WITHDRAW_TREASURY_DISCRIMINATOR: [u8; 8] = [40, 63, 122, 158, 144, 216, 83, 96];
+const CREATE_APPROVER_SET_DISCRIMINATOR: [u8; 8] = [60, 224, 76, 44, 26, 51, 33, 171];
+const CREATE_PROPOSAL_DISCRIMINATOR: [u8; 8] = [132, 116, 68, 174, 216, 160, 198, 22];
+const APPROVE_DISCRIMINATOR: [u8; 8] = [69, 74, 217, 36, 115, 117, 97, 76];
+const EXECUTE_DISCRIMINATOR: [u8; 8] = [130, 221, 242, 154, 13, 193, 189, 29];
+const CURVE_BUY_DISCRIMINATOR: [u8; 8] = [176, 209, 141, 153, 19, 58, 36, 22];
+const GRADUATE_DISCRIMINATOR: [u8; 8] = [45, 235, 225, 181, 17, 218, 64, 130];
+const CREATE_VESTING_DISCRIMINATOR: [u8; 8] = [135, 184, 171, 156, 197, 162, 246, 44];
+const CLAIM_VESTED_DISCRIMINATOR: [u8; 8] = [208, 190, 166, 114, 203, 225, 140, 208];
+const REVOKE_VESTING_DISCRIMINATOR: [u8; 8] = [12, 252, 252, 168, 39, 101, 98, 9];
+const LOCK_LIQUIDITY_DISCRIMINATOR: [u8; 8] = [179, 201, 236, 158, 212, 98, 70, 182];
+const EXTEND_LOCK_DISCRIMINATOR: [u8; 8] = [68, 151, 140, 144, 139, 122, 118, 170];
+const UNLOCK_LIQUIDITY_DISCRIMINATOR: [u8; 8] = [154, 98, 151, 31, 8, 180, 144, 1];
+const CLOSE_LAUNCH_DISCRIMINATOR: [u8; 8] = [27, 216, 111, 223, 10, 230, 19, 211];
+const MIGRATE_MANAGER_DISCRIMINATOR: [u8; 8] = [188, 148, 45, 107, 91, 157, 215, 15];
+const RELEASE_RESERVE_DISCRIMINATOR: [u8; 8] = [170, 102, 52, 144, 33, 176, 41, 60];
+const PROPOSE_CONFIG_UPDATE_DISCRIMINATOR: [u8; 8] = [134, 33, 64, 84, 30, 156, 236, 79];
+const CANCEL_CONFIG_UPDATE_DISCRIMINATOR: [u8; 8] = [216, 180, 255, 207, 118, 146, 126, 89];
+const RENOUNCE_AUTHORITY_DISCRIMINATOR: [u8; 8] = [78, 110, 117, 127, 89, 23, 253, 153];
+
+fn instruction_data(discriminator: [u8; 8], args: &[u64]) -> Vec<u8> {
+    let mut data = discriminator.to_vec();
+    for arg in args {
+        data.extend_from_slice(&arg.to_le_bytes());
+    }
+    data
+}
+
+// `direction` is the borsh tag of SwapDirection: 0 buys tokens, 1 sells them
+fn swap_data(direction: u8, amount_in: u64, minimum_amount_out: u64) -> Vec<u8> {
+    let mut data = SWAP_DISCRIMINATOR.to_vec();
+    data.push(direction);
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+    data
+}
 
 async fn get_token_balance(
     banks_client: &mut BanksClient,
@@ -558,59 +3997,186 @@ async fn get_token_balance(
     Ok(token_account.amount)
 }
 
+fn swap_output(amount_in: u64, reserve_in: u64, reserve_out: u64) -> u64 {
+    let amount_in = amount_in as u128 * (10_000 - POOL_FEE_BPS) / 10_000;
+    (reserve_out as u128 * amount_in / (reserve_in as u128 + amount_in)) as u64
+}
+
+fn swap_input(amount_out: u64, reserve_in: u64, reserve_out: u64) -> Option<u64> {
+    let numerator = reserve_in as u128 * amount_out as u128 * 10_000;
+    let denominator =
+        (reserve_out as u128).checked_sub(amount_out as u128)? * (10_000 - POOL_FEE_BPS);
+    u64::try_from((numerator + denominator - 1) / denominator).ok()
+}
+
+fn token_account_state(
+    account: &AccountInfo,
+) -> std::result::Result<spl_token::state::Account, ProgramError> {
+    spl_token::state::Account::unpack(&account.data.borrow())
+}
+
+/// Minimal AMM v4: `Initialize2` moves the initial liquidity into the vaults
+/// and mints sqrt(pc * coin) LP, and the swaps price off vault balances, like
+/// the real pool without the order book.
 fn mock_raydium_processor(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    if instruction_data.len() < 8 {
-        return Err(ProgramError::InvalidInstructionData);
-    }
+    let instruction = raydium::AmmInstruction::unpack(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let (amm_authority, nonce) =
+        Pubkey::find_program_address(&[raydium::AUTHORITY_AMM], program_id);
 
-    let (tag, _rest) = instruction_data.split_at(8);
-    match tag {
-        // Initialize pool instruction
-        [1, 0, 0, 0, 0, 0, 0, 0] => {
-            let accounts_iter = &mut accounts.iter();
-            let authority = next_account_info(accounts_iter)?;
-            let pool_account = next_account_info(accounts_iter)?;
-            let token_vault = next_account_info(accounts_iter)?;
-            let sol_vault = next_account_info(accounts_iter)?;
+    match instruction {
+        raydium::AmmInstruction::Initialize2 {
+            init_pc_amount,
+            init_coin_amount,
+            ..
+        } => {
+            let [token_program, _ata_program, _system_program, _rent, amm, authority, _open_orders, lp_mint, _coin_mint, _pc_mint, pool_coin_vault, pool_pc_vault, _target_orders, _amm_config, _fee_destination, _market_program, _market, user_wallet, user_token_coin, user_token_pc, user_token_lp] =
+                accounts
+                    .get(..21)
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?
+            else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
 
             // Basic validation
-            if !authority.is_signer
-                || !pool_account.is_writable
-                || !token_vault.is_writable
-                || !sol_vault.is_writable
-            {
+            if !user_wallet.is_signer || !amm.is_writable || *authority.key != amm_authority {
                 return Err(ProgramError::InvalidAccountData);
             }
 
+            for (source, vault, amount) in [
+                (user_token_coin, pool_coin_vault, init_coin_amount),
+                (user_token_pc, pool_pc_vault, init_pc_amount),
+            ] {
+                invoke(
+                    &spl_token::instruction::transfer(
+                        token_program.key,
+                        source.key,
+                        vault.key,
+                        user_wallet.key,
+                        &[],
+                        amount,
+                    )?,
+                    &[
+                        source.clone(),
+                        vault.clone(),
+                        user_wallet.clone(),
+                        token_program.clone(),
+                    ],
+                )?;
+            }
+
+            let lp_amount = ((init_pc_amount as f64) * (init_coin_amount as f64)).sqrt() as u64;
+            invoke_signed(
+                &spl_token::instruction::mint_to(
+                    token_program.key,
+                    lp_mint.key,
+                    user_token_lp.key,
+                    &amm_authority,
+                    &[],
+                    lp_amount,
+                )?,
+                &[
+                    lp_mint.clone(),
+                    user_token_lp.clone(),
+                    authority.clone(),
+                    token_program.clone(),
+                ],
+                &[&[raydium::AUTHORITY_AMM, &[nonce]]],
+            )?;
+
             Ok(())
         }
 
-        // Swap instruction
-        [2, 0, 0, 0, 0, 0, 0, 0] => {
-            let accounts_iter = &mut accounts.iter();
-            let trader = next_account_info(accounts_iter)?;
-            let pool = next_account_info(accounts_iter)?;
-            let token_vault = next_account_info(accounts_iter)?;
-            let sol_vault = next_account_info(accounts_iter)?;
-            let trader_token_account = next_account_info(accounts_iter)?;
+        raydium::AmmInstruction::SwapBaseIn { .. }
+        | raydium::AmmInstruction::SwapBaseOut { .. } => {
+            let [token_program, amm, authority, _open_orders, _target_orders, pool_coin_vault, pool_pc_vault, _market_program, _market, _bids, _asks, _event_queue, _market_coin_vault, _market_pc_vault, _vault_signer, user_source, user_destination, user_owner] =
+                accounts
+                    .get(..18)
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?
+            else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
 
             // Basic validation
-            if !trader.is_signer
-                || !pool.is_writable
-                || !token_vault.is_writable
-                || !sol_vault.is_writable
-                || !trader_token_account.is_writable
-            {
+            if !user_owner.is_signer || !amm.is_writable || *authority.key != amm_authority {
                 return Err(ProgramError::InvalidAccountData);
             }
 
+            let coin_mint = token_account_state(pool_coin_vault)?.mint;
+            let (vault_in, vault_out) = if token_account_state(user_source)?.mint == coin_mint {
+                (pool_coin_vault, pool_pc_vault)
+            } else {
+                (pool_pc_vault, pool_coin_vault)
+            };
+            let reserve_in = token_account_state(vault_in)?.amount;
+            let reserve_out = token_account_state(vault_out)?.amount;
+
+            let (amount_in, amount_out) = match instruction {
+                raydium::AmmInstruction::SwapBaseIn {
+                    amount_in,
+                    minimum_amount_out,
+                } => {
+                    let amount_out = swap_output(amount_in, reserve_in, reserve_out);
+                    if amount_out < minimum_amount_out {
+                        return Err(ProgramError::Custom(30)); // ExceededSlippage
+                    }
+                    (amount_in, amount_out)
+                }
+                raydium::AmmInstruction::SwapBaseOut {
+                    max_amount_in,
+                    amount_out,
+                } => {
+                    let amount_in = swap_input(amount_out, reserve_in, reserve_out)
+                        .ok_or(ProgramError::InsufficientFunds)?;
+                    if amount_in > max_amount_in {
+                        return Err(ProgramError::Custom(30)); // ExceededSlippage
+                    }
+                    (amount_in, amount_out)
+                }
+                _ => unreachable!(),
+            };
+
+            invoke(
+                &spl_token::instruction::transfer(
+                    token_program.key,
+                    user_source.key,
+                    vault_in.key,
+                    user_owner.key,
+                    &[],
+                    amount_in,
+                )?,
+                &[
+                    user_source.clone(),
+                    vault_in.clone(),
+                    user_owner.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+
+            invoke_signed(
+                &spl_token::instruction::transfer(
+                    token_program.key,
+                    vault_out.key,
+                    user_destination.key,
+                    &amm_authority,
+                    &[],
+                    amount_out,
+                )?,
+                &[
+                    vault_out.clone(),
+                    user_destination.clone(),
+                    authority.clone(),
+                    token_program.clone(),
+                ],
+                &[&[raydium::AUTHORITY_AMM, &[nonce]]],
+            )?;
+
             Ok(())
         }
-
-        _ => Err(ProgramError::InvalidInstructionData),
     }
 }